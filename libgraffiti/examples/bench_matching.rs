@@ -0,0 +1,74 @@
+// usage: cargo run --release --example bench_matching
+//
+// restyle (which uses the rule index) vs. querySelectorAll() with every selector (naive matching)
+
+use graffiti::{Document, Viewport};
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::time::Instant;
+
+const SECTIONS: usize = 100;
+const ITEMS: usize = 99;
+const RULES: usize = 1000;
+
+fn main() {
+    let doc = Rc::new(RefCell::new(Document::new()));
+    let vp = Viewport::new((800, 600), &doc);
+
+    let selectors: Vec<_> = (0..RULES)
+        .map(|i| match i % 5 {
+            0 => format!(".i{}", i % 10),
+            1 => format!(".s{} .item", i),
+            2 => format!("#id{}", i),
+            3 => format!("ul li .x{}", i),
+            _ => format!("span.y{}, div > a.z{}", i, i),
+        })
+        .collect();
+    let css: String = selectors.iter().map(|s| format!("{} {{ opacity: 0.5 }}\n", s)).collect();
+
+    let mut d = doc.borrow_mut();
+    let (root, html, head, style, body) = (
+        d.root(),
+        d.create_element("html"),
+        d.create_element("head"),
+        d.create_element("style"),
+        d.create_element("body"),
+    );
+    let css = d.create_text_node(&css);
+    d.insert_child(root, html, 0);
+    d.insert_child(html, head, 0);
+    d.insert_child(head, style, 0);
+    d.insert_child(style, css, 0);
+    d.insert_child(html, body, 1);
+
+    for i in 0..SECTIONS {
+        let section = d.create_element("section");
+        d.set_attribute(section, "class", &format!("section s{}", i));
+        d.insert_child(body, section, i);
+
+        for j in 0..ITEMS {
+            let item = d.create_element(["div", "span", "p", "a", "li"][j % 5]);
+            d.set_attribute(item, "class", &format!("item i{}", j % 10));
+            d.insert_child(section, item, j);
+        }
+    }
+    drop(d);
+
+    let start = Instant::now();
+    vp.computed_style(body);
+    let restyle = start.elapsed();
+
+    let d = doc.borrow();
+    let start = Instant::now();
+    let matches: usize = selectors.iter().map(|s| d.query_selector_all(root, s).len()).sum();
+    let naive = start.elapsed();
+
+    println!(
+        "{} elements, {} rules ({} matches): restyle {:?}, naive {:?}",
+        4 + SECTIONS * (1 + ITEMS),
+        RULES,
+        matches,
+        restyle,
+        naive
+    );
+}
@@ -0,0 +1,30 @@
+// usage: cargo run --example lint_css -- file.css [...]
+
+use graffiti::StyleSheet;
+use std::process::exit;
+
+fn main() {
+    let mut failed = false;
+
+    for path in std::env::args().skip(1) {
+        let css = match std::fs::read_to_string(&path) {
+            Ok(css) => css,
+            Err(e) => {
+                eprintln!("{}: {}", path, e);
+                failed = true;
+                continue;
+            }
+        };
+        let (_, warnings) = StyleSheet::parse_with_diagnostics(&css);
+
+        for w in &warnings {
+            println!("{}:{}:{}: {}", path, w.line, w.column, w);
+        }
+
+        failed |= !warnings.is_empty();
+    }
+
+    if failed {
+        exit(1)
+    }
+}
@@ -49,8 +49,20 @@ pub const GLFW_OPENGL_CORE_PROFILE: c_int = 0x0003_2001;
 pub const GLFW_RELEASE: c_int = 0;
 pub const GLFW_PRESS: c_int = 1;
 
+pub const GLFW_CURSOR: c_int = 0x0003_3001;
+pub const GLFW_CURSOR_NORMAL: c_int = 0x0003_4001;
+pub const GLFW_CURSOR_HIDDEN: c_int = 0x0003_4002;
+
+pub const GLFW_ARROW_CURSOR: c_int = 0x0003_6001;
+pub const GLFW_IBEAM_CURSOR: c_int = 0x0003_6002;
+pub const GLFW_CROSSHAIR_CURSOR: c_int = 0x0003_6003;
+pub const GLFW_HAND_CURSOR: c_int = 0x0003_6004;
+pub const GLFW_HRESIZE_CURSOR: c_int = 0x0003_6005;
+pub const GLFW_VRESIZE_CURSOR: c_int = 0x0003_6006;
+
 pub type GlfwMonitor = *mut c_void;
 pub type GlfwWindow = *mut c_void;
+pub type GlfwCursor = *mut c_void;
 
 extern "C" {
     // init
@@ -95,6 +107,12 @@ extern "C" {
     pub fn glfwRestoreWindow(window: GlfwWindow);
     pub fn glfwRequestWindowAttention(window: GlfwWindow);
 
+    // cursor
+    pub fn glfwCreateStandardCursor(shape: c_int) -> GlfwCursor;
+    pub fn glfwDestroyCursor(cursor: GlfwCursor);
+    pub fn glfwSetCursor(window: GlfwWindow, cursor: GlfwCursor);
+    pub fn glfwSetInputMode(window: GlfwWindow, mode: c_int, value: c_int);
+
     // event listeners
     pub fn glfwSetCursorPosCallback(window: GlfwWindow, cbfun: unsafe extern "C" fn(GlfwWindow, c_double, c_double));
     pub fn glfwSetScrollCallback(window: GlfwWindow, cbfun: unsafe extern "C" fn(GlfwWindow, c_double, c_double));
@@ -17,6 +17,13 @@ fn main() {
         .status()
         .expect("git submodule update");
 
+    // lib.rs is written against this version (gaps need 2.x)
+    Command::new("git")
+        .args(&["checkout", "v2.0.0"])
+        .current_dir("yoga")
+        .status()
+        .expect("git checkout");
+
     let mut build = Build::new();
 
     build
@@ -100,8 +100,9 @@ pub enum YGDisplay {
 #[repr(u32)]
 #[derive(Debug, Clone, Copy)]
 pub enum YGPositionType {
-    Relative = 0,
-    Absolute = 1,
+    Static = 0,
+    Relative = 1,
+    Absolute = 2,
 }
 
 #[repr(u32)]
@@ -112,6 +113,14 @@ pub enum YGWrap {
     WrapReverse = 2,
 }
 
+#[repr(u32)]
+#[derive(Debug, Clone, Copy)]
+pub enum YGGutter {
+    Column = 0,
+    Row = 1,
+    All = 2,
+}
+
 #[repr(C)]
 #[derive(Debug, Clone, Copy)]
 pub struct YGSize {
@@ -127,6 +136,8 @@ extern "C" {
     pub fn YGNodeInsertChild(node: YGNodeRef, child: YGNodeRef, index: u32);
     pub fn YGNodeRemoveChild(node: YGNodeRef, child: YGNodeRef);
     pub fn YGNodeRemoveAllChildren(node: YGNodeRef);
+    pub fn YGNodeGetChild(node: YGNodeRef, index: u32) -> YGNodeRef;
+    pub fn YGNodeGetChildCount(node: YGNodeRef) -> u32;
 
     pub fn YGNodeStyleSetWidth(node: YGNodeRef, width: c_float);
     pub fn YGNodeStyleSetWidthPercent(node: YGNodeRef, width: c_float);
@@ -157,6 +168,8 @@ extern "C" {
     pub fn YGNodeSetMeasureFunc(node: YGNodeRef, measureFunc: YGMeasureFunc);
 
     // TODO: getters
+    pub fn YGNodeStyleGetFlexDirection(node: YGNodeRef) -> YGFlexDirection;
+    pub fn YGNodeStyleGetPositionType(node: YGNodeRef) -> YGPositionType;
 
     pub fn YGNodeStyleSetFlexDirection(node: YGNodeRef, flexDirection: YGFlexDirection);
     pub fn YGNodeStyleSetFlexWrap(node: YGNodeRef, flexWrap: YGWrap);
@@ -188,8 +201,13 @@ extern "C" {
 
     pub fn YGNodeStyleSetBorder(node: YGNodeRef, edge: YGEdge, border: c_float);
 
+    pub fn YGNodeStyleSetGap(node: YGNodeRef, gutter: YGGutter, gapLength: c_float);
+
     pub fn YGNodeLayoutGetLeft(node: YGNodeRef) -> c_float;
     pub fn YGNodeLayoutGetTop(node: YGNodeRef) -> c_float;
     pub fn YGNodeLayoutGetWidth(node: YGNodeRef) -> c_float;
     pub fn YGNodeLayoutGetHeight(node: YGNodeRef) -> c_float;
+    pub fn YGNodeLayoutGetMargin(node: YGNodeRef, edge: YGEdge) -> c_float;
+    pub fn YGNodeLayoutGetBorder(node: YGNodeRef, edge: YGEdge) -> c_float;
+    pub fn YGNodeLayoutGetPadding(node: YGNodeRef, edge: YGEdge) -> c_float;
 }
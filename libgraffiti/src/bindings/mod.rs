@@ -4,7 +4,8 @@
 
 use crate::gfx::{GlBackend, RenderBackend};
 use crate::util::SlotMap;
-use crate::{App, Document, Event, Viewport, WebView, Window};
+use crate::css::{ColorScheme, CssCursor, CssRule, Rule};
+use crate::{App, Cursor, Document, Event, NodeId, StyleSheet, Viewport, ViewportEvent, WebView, Window};
 use crossbeam_channel::{unbounded as channel, Receiver, Sender};
 use once_cell::sync::Lazy;
 use std::cell::RefCell;
@@ -82,7 +83,23 @@ macro_rules! export_api {
                 //wait.recv().unwrap();
             },
             viewport_resize: |vp, w: f64, h: f64| ctx!().viewports[vp].resize(((w as _, h as _))),
+            viewport_set_resolution: |vp, dppx: f64| ctx!().viewports[vp].set_resolution(dppx as _),
+            viewport_set_prefers_color_scheme: |vp, dark: bool| ctx!().viewports[vp].set_prefers_color_scheme(
+                if dark { ColorScheme::Dark } else { ColorScheme::Light }
+            ),
+            viewport_set_prefers_reduced_motion: |vp, reduce: bool| ctx!().viewports[vp].set_prefers_reduced_motion(reduce),
             viewport_element_from_point: |vp, x: f64, y: f64| ctx!().viewports[vp].element_from_point((x as _, y as _)),
+            viewport_computed_style: |vp, el| computed_style(&ctx!().viewports[vp], el),
+            viewport_next_event: |vp| ctx!().viewports[vp].next_event().map(viewport_event),
+            viewport_animating: |vp| ctx!().viewports[vp].is_animating(),
+            viewport_update_cursor: |w, vp, x: f64, y: f64| {
+                if let Some(c) = ctx!().viewports[vp].update_cursor((x as _, y as _)) {
+                    let c = cursor(c);
+
+                    // window is only accessible from the main thread (nothing to do if it's gone)
+                    let _ = TASK_CHANNEL.0.send(Box::new(move || ctx!().windows[w].set_cursor(c)));
+                }
+            },
             viewport_drop: |vp| drop(ctx!().viewports.remove(vp)),
 
             window_new: |title: String, width, height| {
@@ -104,6 +121,10 @@ macro_rules! export_api {
             window_title: |w| ctx!().windows[w].title().to_owned(),
             window_set_title: |w, title: String| ctx!().windows[w].set_title(&title),
             window_size: |w| ctx!().windows[w].size(),
+            window_content_scale: |w| {
+                let (x, y) = ctx!().windows[w].content_scale();
+                (x as f64, y as f64)
+            },
             window_set_size: |w, width, height| ctx!().windows[w].set_size((width, height)),
             window_show: |w| ctx!().windows[w].show(),
             window_hide: |w| ctx!().windows[w].hide(),
@@ -127,6 +148,8 @@ macro_rules! export_api {
             webview_eval: |wv, js: String| ctx!().webviews[wv].eval(&js),
             webview_drop: |wv| drop(ctx!().webviews.remove(wv)),
 
+            css_diagnostics: |css: String| StyleSheet::parse_with_diagnostics(&css).1.iter().map(|w| (w.line as u32, w.column as u32, w.to_string())).collect::<Vec<_>>(),
+
             document_new: || ctx!().documents.insert(Rc::new(RefCell::new(Document::new()))),
             document_node_type: |doc, node| ctx!().documents[doc].borrow().node_type(node) as u32,
             document_create_text_node: |doc, text: String| ctx!().documents[doc].borrow_mut().create_text_node(&text),
@@ -144,11 +167,142 @@ macro_rules! export_api {
             document_query_selector: |doc, node, sel: String| ctx!().documents[doc].borrow().query_selector(node, &sel),
             document_query_selector_all: |doc, node, sel: String| ctx!().documents[doc].borrow().query_selector_all(node, &sel),
             document_drop_node: |doc, node| ctx!().documents[doc].borrow_mut().drop_node(node),
-            document_drop: |doc| drop(ctx!().documents.remove(doc))
+            document_set_base_url: |doc, url: String| ctx!().documents[doc].borrow_mut().set_base_url(&url),
+            document_drop: |doc| drop(ctx!().documents.remove(doc)),
+
+            // CSSOM, rules are addressed by [style_el, index, nested_index, ...]
+            stylesheet_rule_types: |doc, path: Vec<u32>| with_rules(doc, &path, |rules| rules.iter().map(rule_type).collect::<Vec<_>>()),
+            stylesheet_insert_rule: |doc, path: Vec<u32>, rule: String, index: u32| insert_rule(doc, &path, &rule, index as _),
+            stylesheet_delete_rule: |doc, path: Vec<u32>, index: u32| delete_rule(doc, &path, index as _),
+            stylesheet_rule_css_text: |doc, path: Vec<u32>| with_rule(doc, &path, |r| r.css_text()),
+            stylesheet_rule_selector_text: |doc, path: Vec<u32>| with_style_rule(doc, &path, |r| r.selector_text()),
+            stylesheet_rule_set_selector_text: |doc, path: Vec<u32>, sel: String| with_style_rule_mut(doc, &path, |r| r.set_selector_text(&sel)).is_some(),
+            stylesheet_rule_style_property_value: |doc, path: Vec<u32>, prop: String| with_style_rule(doc, &path, |r| r.style().property_value(&prop)).flatten(),
+            stylesheet_rule_set_style_property: |doc, path: Vec<u32>, prop: String, val: String| with_style_rule_mut(doc, &path, |r| r.style_mut().set_property(&prop, &val)).is_some(),
+            stylesheet_rule_remove_style_property: |doc, path: Vec<u32>, prop: String| with_style_rule_mut(doc, &path, |r| r.style_mut().remove_property(&prop)).is_some(),
+            stylesheet_rule_style_css_text: |doc, path: Vec<u32>| with_style_rule(doc, &path, |r| r.style().css_text()),
+            stylesheet_rule_set_style_css_text: |doc, path: Vec<u32>, css: String| with_style_rule_mut(doc, &path, |r| r.style_mut().set_css_text(&css)).is_some(),
+            stylesheet_media_text: |doc, path: Vec<u32>| with_rule(doc, &path, |r| match r {
+                CssRule::Media(m) => Some(m.media_text()),
+                CssRule::Supports(s) => Some(s.condition_text().to_owned()),
+                CssRule::Import(i) => Some(i.media_text()),
+                _ => None,
+            }).flatten(),
+            stylesheet_import_href: |doc, path: Vec<u32>| with_rule(doc, &path, |r| match r {
+                CssRule::Import(i) => Some(i.href.clone()),
+                _ => None,
+            }).flatten()
         }
     }};
 }
 
+// CSSOM helpers
+
+// CSSRule.type in JS
+fn rule_type(rule: &CssRule) -> u32 {
+    match rule {
+        CssRule::Style(_) => 1,
+        CssRule::Import(_) => 3,
+        CssRule::Media(_) => 4,
+        CssRule::FontFace(_) => 5,
+        CssRule::Keyframes(_) => 7,
+        CssRule::Supports(_) => 12,
+    }
+}
+
+// None if the path is stale or if it points to a different kind of rule
+fn with_rules<R>(doc: DocumentId, path: &[u32], f: impl FnOnce(&[CssRule]) -> R) -> Option<R> {
+    let doc = CTX.with(|ctx| ctx.borrow().documents[doc].clone());
+    let mut doc = doc.borrow_mut();
+    let mut rules = doc.style_sheet(*path.first()?).css_rules();
+
+    for &i in &path[1..] {
+        rules = match rules.get(i as usize)? {
+            CssRule::Media(m) => m.css_rules(),
+            CssRule::Supports(s) => s.css_rules(),
+            _ => return None,
+        }
+    }
+
+    Some(f(rules))
+}
+
+fn with_rules_mut<R>(doc: DocumentId, path: &[u32], f: impl FnOnce(&mut [CssRule]) -> R) -> Option<R> {
+    let doc = CTX.with(|ctx| ctx.borrow().documents[doc].clone());
+    let mut doc = doc.borrow_mut();
+    let mut rules = doc.style_sheet_mut(*path.first()?).css_rules_mut();
+
+    for &i in &path[1..] {
+        rules = match rules.get_mut(i as usize)? {
+            CssRule::Media(m) => m.css_rules_mut(),
+            CssRule::Supports(s) => s.css_rules_mut(),
+            _ => return None,
+        }
+    }
+
+    Some(f(rules))
+}
+
+fn with_rule<R>(doc: DocumentId, path: &[u32], f: impl FnOnce(&CssRule) -> R) -> Option<R> {
+    let (index, parent) = path.split_last()?;
+
+    with_rules(doc, parent, |rules| rules.get(*index as usize).map(f))?
+}
+
+fn with_rule_mut<R>(doc: DocumentId, path: &[u32], f: impl FnOnce(&mut CssRule) -> R) -> Option<R> {
+    let (index, parent) = path.split_last()?;
+
+    with_rules_mut(doc, parent, |rules| rules.get_mut(*index as usize).map(f))?
+}
+
+fn with_style_rule<R>(doc: DocumentId, path: &[u32], f: impl FnOnce(&Rule) -> R) -> Option<R> {
+    with_rule(doc, path, |r| match r {
+        CssRule::Style(r) => Some(f(r)),
+        _ => None,
+    })?
+}
+
+fn with_style_rule_mut<R>(doc: DocumentId, path: &[u32], f: impl FnOnce(&mut Rule) -> R) -> Option<R> {
+    with_rule_mut(doc, path, |r| match r {
+        CssRule::Style(r) => Some(f(r)),
+        _ => None,
+    })?
+}
+
+// false means syntax error or invalid path/index
+fn insert_rule(doc: DocumentId, path: &[u32], rule: &str, index: usize) -> bool {
+    match path {
+        [style] => {
+            let doc = CTX.with(|ctx| ctx.borrow().documents[doc].clone());
+            let res = doc.borrow_mut().style_sheet_mut(*style).insert_rule(rule, index);
+            res.is_ok()
+        }
+        _ => with_rule_mut(doc, path, |r| match r {
+            CssRule::Media(m) => m.insert_rule(rule, index).is_ok(),
+            CssRule::Supports(s) => s.insert_rule(rule, index).is_ok(),
+            _ => false,
+        })
+        .unwrap_or(false),
+    }
+}
+
+// false means invalid path/index
+fn delete_rule(doc: DocumentId, path: &[u32], index: usize) -> bool {
+    match path {
+        [style] => {
+            let doc = CTX.with(|ctx| ctx.borrow().documents[doc].clone());
+            let res = doc.borrow_mut().style_sheet_mut(*style).delete_rule(index);
+            res.is_ok()
+        }
+        _ => with_rule_mut(doc, path, |r| match r {
+            CssRule::Media(m) => m.delete_rule(index).is_ok(),
+            CssRule::Supports(s) => s.delete_rule(index).is_ok(),
+            _ => false,
+        })
+        .unwrap_or(false),
+    }
+}
+
 fn event(ev: Event) -> (String, Option<(f64, f64)>, Option<u32>) {
     let res = match ev {
         Event::CursorPos(x, y) => ("mousemove", Some((x, y)), None),
@@ -169,5 +323,42 @@ fn event(ev: Event) -> (String, Option<(f64, f64)>, Option<u32>) {
     (res.0.to_owned(), res.1, res.2)
 }
 
+// GLFW has only a few standard cursors, the rest falls back to the arrow
+fn cursor(cursor: CssCursor) -> Option<Cursor> {
+    use CssCursor::*;
+
+    Some(match cursor {
+        None => return Option::None,
+        Pointer => Cursor::Hand,
+        Text | VerticalText => Cursor::IBeam,
+        Crosshair | Cell => Cursor::Crosshair,
+        EResize | WResize | EwResize | ColResize => Cursor::HResize,
+        NResize | SResize | NsResize | RowResize => Cursor::VResize,
+        _ => Cursor::Arrow,
+    })
+}
+
+fn computed_style(vp: &Viewport, el: NodeId) -> Vec<(String, String)> {
+    let style = vp.computed_style(el);
+
+    style
+        .props()
+        .map(|p| (p.name().to_owned(), style.property_value(p.name()).unwrap_or_default()))
+        .collect()
+}
+
+fn viewport_event(ev: ViewportEvent) -> (String, u32, (String, f64)) {
+    let (kind, node, name, elapsed) = match ev {
+        ViewportEvent::TransitionEnd(node, prop, elapsed) => ("transitionend", node, prop.name().to_owned(), elapsed),
+        ViewportEvent::AnimationStart(node, name, elapsed) => ("animationstart", node, name.to_string(), elapsed),
+        ViewportEvent::AnimationIteration(node, name, elapsed) => {
+            ("animationiteration", node, name.to_string(), elapsed)
+        }
+        ViewportEvent::AnimationEnd(node, name, elapsed) => ("animationend", node, name.to_string(), elapsed),
+    };
+
+    (kind.to_owned(), node, (name, elapsed as _))
+}
+
 mod deno;
 mod nodejs;
@@ -0,0 +1,699 @@
+// interpolation & timing (shared by transitions & animations)
+// x numbers, colors, lengths (mixed px/% goes through calc())
+// x transforms with the same functions (none is the identity)
+// x timing functions (keywords, cubic-bezier, steps)
+// x transition-* lookup (lists are repeated if needed)
+// x animation-* lookup, timeline (delay, iterations, direction, fill-mode)
+// x keyframes (missing 0%/100% are taken from the underlying style)
+// - everything else is not animatable (and it's switched at 50%)
+
+use super::{
+    shorthand_longhands, CssAnimationDirection, CssAnimationFillMode, CssAnimationPlayState, CssCalc, CssColor,
+    CssDimension, CssIterationCount, CssOrigin, CssStepPosition, CssTime, CssTimingFunction, CssTransform,
+    CssTransformFn, KeyframesRule, Style, StyleProp, StylePropId,
+};
+use crate::util::Atom;
+
+impl CssTimingFunction {
+    // progress (0..1) to output progress
+    pub fn at(&self, t: f32) -> f32 {
+        match *self {
+            Self::Linear => t,
+            Self::Ease => cubic_bezier(0.25, 0.1, 0.25, 1., t),
+            Self::EaseIn => cubic_bezier(0.42, 0., 1., 1., t),
+            Self::EaseOut => cubic_bezier(0., 0., 0.58, 1., t),
+            Self::EaseInOut => cubic_bezier(0.42, 0., 0.58, 1., t),
+            Self::CubicBezier(x1, y1, x2, y2) => cubic_bezier(x1, y1, x2, y2, t),
+            Self::Steps(n, pos) => {
+                use CssStepPosition::*;
+
+                let n = n as f32;
+                let jumps = match pos {
+                    JumpStart | JumpEnd => n,
+                    JumpNone => n - 1.,
+                    JumpBoth => n + 1.,
+                };
+                let mut step = (t * n).floor();
+
+                if let JumpStart | JumpBoth = pos {
+                    step += 1.;
+                }
+
+                step.min(jumps) / jumps.max(1.)
+            }
+        }
+    }
+}
+
+fn cubic_bezier(x1: f32, y1: f32, x2: f32, y2: f32, t: f32) -> f32 {
+    if t <= 0. || t >= 1. {
+        return t;
+    }
+
+    let bezier = |a: f32, b: f32, s: f32| 3. * a * (1. - s) * (1. - s) * s + 3. * b * (1. - s) * s * s + s * s * s;
+    let derivative =
+        |a: f32, b: f32, s: f32| 3. * a * (1. - s) * (1. - s) + 6. * (b - a) * (1. - s) * s + 3. * (1. - b) * s * s;
+
+    // find s for which x(s) == t (newton, bisection if it doesn't converge)
+    let mut s = t;
+
+    for _ in 0..8 {
+        let x = bezier(x1, x2, s) - t;
+
+        if x.abs() < 1e-5 {
+            return bezier(y1, y2, s);
+        }
+
+        let d = derivative(x1, x2, s);
+
+        if d.abs() < 1e-6 {
+            break;
+        }
+
+        s -= x / d;
+    }
+
+    let (mut min, mut max) = (0., 1.);
+    s = t;
+
+    for _ in 0..32 {
+        let x = bezier(x1, x2, s);
+
+        if (x - t).abs() < 1e-5 {
+            break;
+        }
+
+        if x < t {
+            min = s;
+        } else {
+            max = s;
+        }
+
+        s = (min + max) / 2.;
+    }
+
+    bezier(y1, y2, s)
+}
+
+trait Interpolate: Sized {
+    fn interpolate(&self, to: &Self, t: f32) -> Option<Self>;
+}
+
+impl Interpolate for f32 {
+    fn interpolate(&self, to: &Self, t: f32) -> Option<Self> {
+        Some(self + (to - self) * t)
+    }
+}
+
+// font-weight
+impl Interpolate for u16 {
+    fn interpolate(&self, to: &Self, t: f32) -> Option<Self> {
+        Some((*self as f32).interpolate(&(*to as f32), t)?.round() as u16)
+    }
+}
+
+impl Interpolate for CssColor {
+    fn interpolate(&self, to: &Self, t: f32) -> Option<Self> {
+        let ch = |a: u8, b: u8| (a as f32).interpolate(&(b as f32), t).unwrap().round() as u8;
+
+        match (*self, *to) {
+            (Self::Rgba(r1, g1, b1, a1), Self::Rgba(r2, g2, b2, a2)) => {
+                Some(Self::from_rgba8(ch(r1, r2), ch(g1, g2), ch(b1, b2), ch(a1, a2)))
+            }
+            _ => None,
+        }
+    }
+}
+
+impl Interpolate for CssDimension {
+    fn interpolate(&self, to: &Self, t: f32) -> Option<Self> {
+        use CssDimension::*;
+
+        let calc = |d: &Self| match d {
+            Px(v) => Some(CssCalc::Px(*v)),
+            Percent(v) => Some(CssCalc::Percent(*v)),
+            Em(v) => Some(CssCalc::Em(*v)),
+            Rem(v) => Some(CssCalc::Rem(*v)),
+            Vw(v) => Some(CssCalc::Vw(*v)),
+            Vh(v) => Some(CssCalc::Vh(*v)),
+            Vmin(v) => Some(CssCalc::Vmin(*v)),
+            Vmax(v) => Some(CssCalc::Vmax(*v)),
+            Calc(c) => Some((**c).clone()),
+            Auto => None,
+        };
+
+        Some(match (self, to) {
+            (Px(a), Px(b)) => Px(a.interpolate(b, t)?),
+            (Percent(a), Percent(b)) => Percent(a.interpolate(b, t)?),
+            (a, b) => Calc(Box::new(CssCalc::Add(
+                Box::new(CssCalc::Mul(Box::new(calc(a)?), 1. - t)),
+                Box::new(CssCalc::Mul(Box::new(calc(b)?), t)),
+            ))),
+        })
+    }
+}
+
+impl Interpolate for CssOrigin {
+    fn interpolate(&self, to: &Self, t: f32) -> Option<Self> {
+        Some(Self(self.0.interpolate(&to.0, t)?, self.1.interpolate(&to.1, t)?))
+    }
+}
+
+impl Interpolate for CssTransform {
+    // TODO: matrix decomposition for different functions
+    fn interpolate(&self, to: &Self, t: f32) -> Option<Self> {
+        use CssTransformFn::*;
+
+        let identity = |f: &CssTransformFn| match f {
+            Translate(..) => Translate(CssDimension::ZERO, CssDimension::ZERO),
+            Scale(..) => Scale(1., 1.),
+            Rotate(_) => Rotate(0.),
+            Skew(..) => Skew(0., 0.),
+            Matrix(_) => Matrix([1., 0., 0., 1., 0., 0.]),
+        };
+
+        let (from, to) = match (self.0.is_empty(), to.0.is_empty()) {
+            (true, _) => (to.0.iter().map(identity).collect(), to.0.clone()),
+            (_, true) => (self.0.clone(), self.0.iter().map(identity).collect()),
+            _ => (self.0.clone(), to.0.clone()),
+        };
+
+        if from.len() != to.len() {
+            return None;
+        }
+
+        let lerp = |a: &f32, b: &f32| a.interpolate(b, t).unwrap();
+        let funcs = from
+            .iter()
+            .zip(&to)
+            .map(|pair| {
+                Some(match pair {
+                    (Translate(x1, y1), Translate(x2, y2)) => Translate(x1.interpolate(x2, t)?, y1.interpolate(y2, t)?),
+                    (Scale(x1, y1), Scale(x2, y2)) => Scale(lerp(x1, x2), lerp(y1, y2)),
+                    (Rotate(a1), Rotate(a2)) => Rotate(lerp(a1, a2)),
+                    (Skew(x1, y1), Skew(x2, y2)) => Skew(lerp(x1, x2), lerp(y1, y2)),
+                    (Matrix(m1), Matrix(m2)) => {
+                        let mut m = *m1;
+                        for (v, to) in m.iter_mut().zip(m2) {
+                            *v = lerp(v, to);
+                        }
+                        Matrix(m)
+                    }
+                    _ => return None,
+                })
+            })
+            .collect::<Option<_>>()?;
+
+        Some(Self(funcs))
+    }
+}
+
+impl StyleProp {
+    // None if the prop is not animatable (or the values are not compatible)
+    pub fn interpolate(&self, to: &Self, t: f32) -> Option<Self> {
+        macro_rules! interpolate {
+            ($($variant:ident),*) => {
+                match (self, to) {
+                    $((Self::$variant(a), Self::$variant(b)) => a.interpolate(b, t).map(Self::$variant),)*
+                    _ => None
+                }
+            }
+        }
+
+        interpolate!(
+            Width,
+            Height,
+            MinWidth,
+            MinHeight,
+            MaxWidth,
+            MaxHeight,
+            PaddingTop,
+            PaddingRight,
+            PaddingBottom,
+            PaddingLeft,
+            MarginTop,
+            MarginRight,
+            MarginBottom,
+            MarginLeft,
+            BackgroundColor,
+            BorderTopLeftRadius,
+            BorderTopRightRadius,
+            BorderBottomRightRadius,
+            BorderBottomLeftRadius,
+            BorderTopWidth,
+            BorderTopColor,
+            BorderRightWidth,
+            BorderRightColor,
+            BorderBottomWidth,
+            BorderBottomColor,
+            BorderLeftWidth,
+            BorderLeftColor,
+            FlexGrow,
+            FlexShrink,
+            FlexBasis,
+            RowGap,
+            ColumnGap,
+            FontSize,
+            FontWeight,
+            LineHeight,
+            LetterSpacing,
+            WordSpacing,
+            Color,
+            OutlineColor,
+            OutlineWidth,
+            Top,
+            Right,
+            Bottom,
+            Left,
+            Opacity,
+            Transform,
+            TransformOrigin
+        )
+    }
+}
+
+impl Style {
+    // (duration, delay, timing function) if changes of the prop should be transitioned
+    pub fn transition(&self, prop: StylePropId) -> Option<(f32, f32, CssTimingFunction)> {
+        let durations = match self.find_prop(StylePropId::TransitionDuration) {
+            Some(StyleProp::TransitionDuration(v)) => &v.0,
+            _ => return None,
+        };
+        let delays = match self.find_prop(StylePropId::TransitionDelay) {
+            Some(StyleProp::TransitionDelay(v)) => &v.0[..],
+            _ => &[CssTime(0.)],
+        };
+        let timings = match self.find_prop(StylePropId::TransitionTimingFunction) {
+            Some(StyleProp::TransitionTimingFunction(v)) => &v.0[..],
+            _ => &[CssTimingFunction::Ease],
+        };
+
+        // last one wins, default is all
+        let i = match self.find_prop(StylePropId::TransitionProperty) {
+            Some(StyleProp::TransitionProperty(v)) => v.0.iter().rposition(|p| {
+                &**p == "all" || &**p == prop.name() || shorthand_longhands(p).map_or(false, |ids| ids.contains(&prop))
+            })?,
+            _ => 0,
+        };
+
+        let (duration, delay) = (durations[i % durations.len()].0, delays[i % delays.len()].0);
+
+        if duration.max(0.) + delay <= 0. {
+            return None;
+        }
+
+        Some((duration.max(0.), delay, timings[i % timings.len()]))
+    }
+}
+
+// one item of animation-* lists
+#[derive(Debug, Clone, PartialEq)]
+pub struct CssAnimation {
+    pub name: Atom<String>,
+    pub duration: f32,
+    pub timing_function: CssTimingFunction,
+    pub delay: f32,
+    pub iteration_count: f32,
+    pub direction: CssAnimationDirection,
+    pub fill_mode: CssAnimationFillMode,
+    pub play_state: CssAnimationPlayState,
+}
+
+impl CssAnimation {
+    // zero duration is zero even if it's repeated infinitely
+    pub fn active_duration(&self) -> f32 {
+        match self.duration {
+            d if d == 0. => 0.,
+            d => d * self.iteration_count,
+        }
+    }
+
+    // (directed progress, current iteration) at the given time (since the animation was applied)
+    // or None if the animation has no effect at that time
+    pub fn progress(&self, elapsed: f32) -> Option<(f32, u32)> {
+        let local = elapsed - self.delay;
+        let active_duration = self.active_duration();
+
+        let (progress, iteration) = if local < 0. {
+            if let CssAnimationFillMode::None | CssAnimationFillMode::Forwards = self.fill_mode {
+                return None;
+            }
+
+            (0., 0.)
+        } else if local >= active_duration {
+            if let CssAnimationFillMode::None | CssAnimationFillMode::Backwards = self.fill_mode {
+                return None;
+            }
+
+            // ends in the middle of an iteration or at the end of the previous one
+            let count = self.iteration_count;
+            match (count.fract(), count) {
+                (f, _) if f > 0. => (f, count.floor()),
+                (_, c) if c > 0. => (1., c - 1.),
+                _ => (0., 0.),
+            }
+        } else {
+            let iteration = (local / self.duration).floor();
+
+            (local / self.duration - iteration, iteration)
+        };
+
+        let reverse = match self.direction {
+            CssAnimationDirection::Normal => false,
+            CssAnimationDirection::Reverse => true,
+            CssAnimationDirection::Alternate => iteration % 2. == 1.,
+            CssAnimationDirection::AlternateReverse => iteration % 2. == 0.,
+        };
+
+        Some((if reverse { 1. - progress } else { progress }, iteration as u32))
+    }
+}
+
+impl Style {
+    // lists are repeated/truncated to the number of names, none is skipped
+    pub fn animations(&self) -> Vec<CssAnimation> {
+        macro_rules! list {
+            ($variant:ident, $default:expr) => {
+                match self.find_prop(StylePropId::$variant) {
+                    Some(StyleProp::$variant(v)) => &v.0[..],
+                    _ => &[$default],
+                }
+            };
+        }
+
+        let names = list!(AnimationName, Atom::from("none"));
+        let durations = list!(AnimationDuration, CssTime(0.));
+        let timings = list!(AnimationTimingFunction, CssTimingFunction::Ease);
+        let delays = list!(AnimationDelay, CssTime(0.));
+        let counts = list!(AnimationIterationCount, CssIterationCount(1.));
+        let directions = list!(AnimationDirection, CssAnimationDirection::Normal);
+        let fill_modes = list!(AnimationFillMode, CssAnimationFillMode::None);
+        let play_states = list!(AnimationPlayState, CssAnimationPlayState::Running);
+
+        names
+            .iter()
+            .enumerate()
+            .filter(|(_, name)| &***name != "none")
+            .map(|(i, name)| CssAnimation {
+                name: name.clone(),
+                duration: durations[i % durations.len()].0.max(0.),
+                timing_function: timings[i % timings.len()],
+                delay: delays[i % delays.len()].0,
+                iteration_count: counts[i % counts.len()].0,
+                direction: directions[i % directions.len()],
+                fill_mode: fill_modes[i % fill_modes.len()],
+                play_state: play_states[i % play_states.len()],
+            })
+            .collect()
+    }
+}
+
+impl KeyframesRule {
+    // interpolate between keyframes and override the style
+    // (timing function can be changed for each keyframe)
+    pub fn apply(&self, progress: f32, timing_function: CssTimingFunction, style: &mut Style) {
+        let mut ids: Vec<StylePropId> = Vec::new();
+
+        for p in self.keyframes.iter().flat_map(|(_, s)| s.props()) {
+            if !ids.contains(&p.id()) && is_keyframe_prop(p) {
+                ids.push(p.id());
+            }
+        }
+
+        // currentColor is resolved against the animated color
+        ids.sort_by_key(|id| *id != StylePropId::Color);
+
+        for id in ids {
+            let frames: Vec<_> = self
+                .keyframes
+                .iter()
+                .filter_map(|(offset, s)| Some((*offset, s.find_prop(id)?, s)))
+                .collect();
+            let underlying = style.find_prop(id);
+
+            let (from_offset, from, from_style) = match frames.iter().rev().find(|(o, ..)| *o <= progress) {
+                Some(&(o, p, s)) => (o, Some(p), Some(s)),
+                None => (0., underlying, None),
+            };
+            let (to_offset, to) = match frames.iter().find(|(o, ..)| *o > progress) {
+                Some(&(o, p, _)) => (o, Some(p)),
+                None => (1., underlying),
+            };
+
+            let timing_function = match from_style.and_then(|s| s.find_prop(StylePropId::AnimationTimingFunction)) {
+                Some(StyleProp::AnimationTimingFunction(f)) => f.0[0],
+                _ => timing_function,
+            };
+            let t = match to_offset > from_offset {
+                true => timing_function.at((progress - from_offset) / (to_offset - from_offset)),
+                // exactly at the last keyframe
+                false => 0.,
+            };
+
+            let current_color = style.current_color();
+            let resolve = |p: Option<&StyleProp>| {
+                p.cloned().map(|mut p| {
+                    p.resolve_current_color(current_color);
+                    p
+                })
+            };
+
+            let value = match (resolve(from), resolve(to)) {
+                (Some(from), Some(to)) => match from.interpolate(&to, t) {
+                    Some(v) => v,
+                    None if t < 0.5 => from,
+                    None => to,
+                },
+                // initial value (missing prop) is also switched at 50%
+                (Some(from), None) if t < 0.5 => from,
+                (None, Some(to)) if t >= 0.5 => to,
+                _ => continue,
+            };
+
+            style.add_prop(value);
+        }
+    }
+}
+
+// animation-* are ignored (timing function is only used for the keyframe itself)
+fn is_keyframe_prop(prop: &StyleProp) -> bool {
+    use StyleProp::*;
+
+    !matches!(
+        prop,
+        Keyword(..)
+            | AnimationName(_)
+            | AnimationDuration(_)
+            | AnimationTimingFunction(_)
+            | AnimationDelay(_)
+            | AnimationIterationCount(_)
+            | AnimationDirection(_)
+            | AnimationFillMode(_)
+            | AnimationPlayState(_)
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn timing_functions() {
+        use CssStepPosition::*;
+        use CssTimingFunction::*;
+
+        for f in &[Linear, Ease, EaseIn, EaseOut, EaseInOut, CubicBezier(0.1, 0.7, 1., 0.1)] {
+            assert_eq!(f.at(0.), 0.);
+            assert_eq!(f.at(1.), 1.);
+        }
+
+        assert_eq!(Linear.at(0.3), 0.3);
+        assert!((EaseInOut.at(0.5) - 0.5).abs() < 1e-3);
+        assert!(EaseIn.at(0.25) < 0.25);
+        assert!(EaseOut.at(0.25) > 0.25);
+        assert!((CubicBezier(0., 0., 1., 1.).at(0.3) - 0.3).abs() < 1e-3);
+
+        assert_eq!(Steps(4, JumpEnd).at(0.), 0.);
+        assert_eq!(Steps(4, JumpEnd).at(0.3), 0.25);
+        assert_eq!(Steps(4, JumpEnd).at(1.), 1.);
+        assert_eq!(Steps(4, JumpStart).at(0.), 0.25);
+        assert_eq!(Steps(4, JumpStart).at(0.3), 0.5);
+        assert_eq!(Steps(2, JumpNone).at(0.3), 0.);
+        assert_eq!(Steps(2, JumpNone).at(0.6), 1.);
+        assert_eq!(Steps(3, JumpBoth).at(0.), 0.25);
+    }
+
+    #[test]
+    fn interpolate() {
+        let lerp = |a, b, t| {
+            let (a, b) = (&Style::from(a).props[0], &Style::from(b).props[0]);
+            a.interpolate(b, t).map(|p| p.value_as_string())
+        };
+
+        assert_eq!(lerp("opacity: 0", "opacity: 1", 0.25).as_deref(), Some("0.25"));
+        assert_eq!(lerp("width: 10px", "width: 20px", 0.5).as_deref(), Some("15px"));
+        assert_eq!(lerp("width: 10%", "width: 20%", 0.5).as_deref(), Some("15%"));
+        assert_eq!(
+            lerp("width: 10px", "width: 20%", 0.5).as_deref(),
+            Some("calc(10px * 0.5 + 20% * 0.5)")
+        );
+        assert_eq!(
+            lerp("color: #000", "color: #fff", 0.5).as_deref(),
+            Some("rgba(128, 128, 128, 255)")
+        );
+
+        assert_eq!(
+            lerp("transform: none", "transform: rotate(90deg) scale(3)", 0.5).as_deref(),
+            Some("rotate(45deg) scale(2, 2)")
+        );
+        assert_eq!(
+            lerp("transform: translateX(10px)", "transform: translate(20px, 10px)", 0.5).as_deref(),
+            Some("translate(15px, 5px)")
+        );
+        assert_eq!(lerp("transform: rotate(1deg)", "transform: scale(2)", 0.5), None);
+
+        // not animatable
+        assert_eq!(lerp("display: none", "display: flex", 0.5), None);
+        assert_eq!(lerp("width: auto", "width: 10px", 0.5), None);
+        assert_eq!(lerp("width: 10px", "height: 10px", 0.5), None);
+    }
+
+    #[test]
+    fn transition() {
+        use CssTimingFunction::*;
+
+        let t = |s, prop| Style::from(s).transition(prop);
+
+        assert_eq!(t("", StylePropId::Opacity), None);
+        assert_eq!(t("transition-duration: 1s", StylePropId::Opacity), Some((1., 0., Ease)));
+        assert_eq!(t("transition: opacity 1s", StylePropId::Opacity), Some((1., 0., Ease)));
+        assert_eq!(t("transition: opacity 1s", StylePropId::Color), None);
+        assert_eq!(t("transition: opacity 0s", StylePropId::Opacity), None);
+        assert_eq!(
+            t("transition: opacity 0s 1s", StylePropId::Opacity),
+            Some((0., 1., Ease))
+        );
+        assert_eq!(
+            t("transition: opacity 1s linear, color 2s ease-in 1s", StylePropId::Color),
+            Some((2., 1., EaseIn))
+        );
+        assert_eq!(t("transition: all 1s", StylePropId::Width), Some((1., 0., Ease)));
+        assert_eq!(
+            t("transition: margin 1s", StylePropId::MarginLeft),
+            Some((1., 0., Ease))
+        );
+        assert_eq!(
+            t(
+                "transition-property: opacity, color; transition-duration: 1s, 2s, 3s",
+                StylePropId::Color
+            ),
+            Some((2., 0., Ease))
+        );
+        assert_eq!(t("transition: none", StylePropId::Opacity), None);
+    }
+
+    #[test]
+    fn animations() {
+        use CssAnimationDirection::*;
+
+        let a = |s| Style::from(s).animations();
+
+        assert_eq!(a(""), vec![]);
+        assert_eq!(a("animation: none"), vec![]);
+        assert_eq!(
+            a("animation: spin 1s linear infinite"),
+            vec![CssAnimation {
+                name: Atom::from("spin"),
+                duration: 1.,
+                timing_function: CssTimingFunction::Linear,
+                delay: 0.,
+                iteration_count: f32::INFINITY,
+                direction: Normal,
+                fill_mode: CssAnimationFillMode::None,
+                play_state: CssAnimationPlayState::Running,
+            }]
+        );
+
+        let res = a("animation-name: a, none, b; animation-duration: 1s, 2s; animation-direction: alternate");
+        assert_eq!(res.len(), 2);
+        assert_eq!(
+            (&**res[0].name, res[0].duration, res[0].direction),
+            ("a", 1., Alternate)
+        );
+        assert_eq!(
+            (&**res[1].name, res[1].duration, res[1].direction),
+            ("b", 1., Alternate)
+        );
+    }
+
+    #[test]
+    fn animation_progress() {
+        let p = |s, elapsed| Style::from(s).animations()[0].progress(elapsed);
+
+        assert_eq!(p("animation: a 2s", 0.), Some((0., 0)));
+        assert_eq!(p("animation: a 2s", 1.), Some((0.5, 0)));
+        assert_eq!(p("animation: a 2s", 2.), None);
+        assert_eq!(p("animation: a 2s forwards", 3.), Some((1., 0)));
+        assert_eq!(p("animation: a 2s 1s", 0.5), None);
+        assert_eq!(p("animation: a 2s 1s backwards", 0.5), Some((0., 0)));
+
+        assert_eq!(p("animation: a 2s 3", 5.), Some((0.5, 2)));
+        assert_eq!(p("animation: a 2s 1.5 forwards", 10.), Some((0.5, 1)));
+        assert_eq!(p("animation: a 2s infinite", 101.), Some((0.5, 50)));
+
+        assert_eq!(p("animation: a 2s reverse", 0.5), Some((0.75, 0)));
+        assert_eq!(p("animation: a 2s 2 alternate", 2.5), Some((0.75, 1)));
+        assert_eq!(p("animation: a 2s 2 alternate-reverse", 0.5), Some((0.75, 0)));
+        assert_eq!(p("animation: a 2s 2 alternate both", 5.), Some((0., 1)));
+
+        // zero duration
+        assert_eq!(p("animation: a 0s", 0.), None);
+        assert_eq!(p("animation: a 0s forwards", 0.), Some((1., 0)));
+        assert_eq!(p("animation: a 0s infinite", 0.), None);
+        assert_eq!(p("animation: a 0s infinite forwards", 1.), Some((1., u32::MAX)));
+    }
+
+    #[test]
+    fn keyframes() {
+        let sheet = super::super::StyleSheet::from(
+            "@keyframes a { from { opacity: 0 } 50% { opacity: 1; display: none } }
+             @keyframes b { 50% { width: 100px; animation-timing-function: linear } }
+             @keyframes c { to { opacity: 0.5 } }
+             @keyframes d { from { color: #000; outline-color: currentColor } to { color: #fff; outline-color: currentColor } }",
+        );
+        let media = super::super::MediaContext::new(800., 600.);
+        let k = sheet.keyframes(&media);
+        let (a, b, c, d) = (k[0], k[1], k[2], k[3]);
+
+        let apply = |k: &KeyframesRule, progress, style| {
+            let mut style = Style::from(style);
+            k.apply(progress, CssTimingFunction::Linear, &mut style);
+            style.css_text()
+        };
+
+        assert_eq!(apply(a, 0., ""), "opacity: 0;");
+        assert_eq!(apply(a, 0.2, ""), "opacity: 0.4;");
+        assert_eq!(apply(a, 0.5, ""), "opacity: 1;display: none;");
+        assert_eq!(
+            apply(a, 0.75, "opacity: 0.5; display: flex"),
+            "opacity: 0.75;display: flex;"
+        );
+
+        assert_eq!(apply(b, 0., "width: 0"), "width: 0px;");
+        assert_eq!(apply(b, 0.25, "width: 0"), "width: 50px;");
+        assert_eq!(apply(b, 0.5, "width: 0"), "width: 100px;");
+        assert_eq!(apply(b, 0.75, "width: 0"), "width: 50px;");
+        assert_eq!(apply(b, 0.6, ""), "width: 100px;");
+        assert_eq!(apply(b, 0.75, ""), "");
+
+        // end state (forwards)
+        assert_eq!(apply(c, 1., ""), "opacity: 0.5;");
+        assert_eq!(apply(c, 1., "opacity: 1"), "opacity: 0.5;");
+
+        // currentColor follows the animated color
+        assert_eq!(
+            apply(d, 0.5, ""),
+            "color: rgba(128, 128, 128, 255);outline-color: rgba(128, 128, 128, 255);"
+        );
+    }
+}
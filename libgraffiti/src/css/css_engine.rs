@@ -1,21 +1,86 @@
 use super::*;
+use std::collections::HashMap;
+use std::fmt::{self, Write};
 
-// just a fn for now
-pub(crate) fn matching_rules<'a, E: Copy>(ctx: &MatchingContext<'a, E>, sheets: &'a [StyleSheet], el: E) -> impl Iterator<Item = &'a Rule> + 'a {
-    let mut rules: Vec<_> = sheets
-        .iter()
-        .flat_map(|s| &s.rules)
-        .filter_map(|r| ctx.match_selector(&r.selector, el).map(move |spec| (spec, r)))
-        .collect();
+// rules bucketed by the rightmost id/class/local name, so that only
+// those which can possibly match are tested (in the original order)
+pub(crate) struct RuleIndex<'a> {
+    rules: Vec<(&'a Rule, SelectorMask)>,
+    ids: HashMap<&'a str, Vec<usize>>,
+    classes: HashMap<&'a str, Vec<usize>>,
+    local_names: HashMap<&'a str, Vec<usize>>,
+    others: Vec<usize>,
+}
+
+impl<'a> RuleIndex<'a> {
+    pub fn new(rules: &[&'a Rule]) -> Self {
+        let mut index = Self {
+            rules: rules.iter().map(|r| (*r, r.selector.ancestor_mask())).collect(),
+            ids: HashMap::new(),
+            classes: HashMap::new(),
+            local_names: HashMap::new(),
+            others: Vec::new(),
+        };
+
+        for (i, r) in rules.iter().enumerate() {
+            for key in r.selector.keys() {
+                let bucket = match key {
+                    Some(Component::Identifier(id)) => index.ids.entry(id).or_default(),
+                    Some(Component::ClassName(cls)) => index.classes.entry(cls).or_default(),
+                    Some(Component::LocalName(name)) => index.local_names.entry(name).or_default(),
+                    _ => &mut index.others,
+                };
+
+                // a, a:hover
+                if bucket.last() != Some(&i) {
+                    bucket.push(i);
+                }
+            }
+        }
+
+        index
+    }
+
+    // ancestors should be ctx.ancestor_mask(el) or the parent's one extended with ctx.mask(parent)
+    pub fn matching_rules<E: Copy>(
+        &self,
+        ctx: &MatchingContext<'_, E>,
+        el: E,
+        ancestors: SelectorMask,
+    ) -> impl Iterator<Item = &'a Rule> {
+        let mut candidates = self.others.clone();
+        let mut add = |bucket: Option<&Vec<usize>>| candidates.extend(bucket.into_iter().flatten());
 
-    rules.sort_by(|(a, _), (b, _)| a.cmp(b));
+        if let Some(id) = (ctx.identifier)(el) {
+            add(self.ids.get(id));
+        }
 
-    rules.into_iter().map(|(_, r)| r)
+        for cls in ctx.class_names(el) {
+            add(self.classes.get(cls));
+        }
+
+        add(self.local_names.get((ctx.local_name)(el)));
+
+        // original order (it can be in more buckets)
+        candidates.sort_unstable();
+        candidates.dedup();
+
+        let mut rules: Vec<_> = candidates
+            .into_iter()
+            .map(|i| self.rules[i])
+            .filter(|(_, mask)| ancestors.includes(*mask))
+            .filter_map(|(r, _)| ctx.match_selector(&r.selector, el).map(move |spec| (spec, r)))
+            .collect();
+
+        rules.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+        rules.into_iter().map(|(_, r)| r)
+    }
 }
 
 #[derive(Debug, PartialEq)]
 pub struct StyleSheet {
-    pub(super) rules: Vec<Rule>,
+    pub(super) rules: Vec<CssRule>,
 }
 
 impl StyleSheet {
@@ -23,12 +88,127 @@ impl StyleSheet {
         Self { rules: vec![] }
     }
 
-    pub fn insert_rule(&mut self, rule: Rule, index: usize) {
-        self.rules.insert(index, rule);
+    pub fn css_rules(&self) -> &[CssRule] {
+        &self.rules
+    }
+
+    pub fn css_rules_mut(&mut self) -> &mut [CssRule] {
+        &mut self.rules
+    }
+
+    pub fn insert_rule(&mut self, rule: &str, index: usize) -> Result<(), &'static str> {
+        insert_rule(&mut self.rules, rule, index)
+    }
+
+    pub fn delete_rule(&mut self, index: usize) -> Result<(), &'static str> {
+        delete_rule(&mut self.rules, index)
+    }
+
+    pub fn css_text(&self) -> String {
+        let rules: Vec<_> = self.rules.iter().map(CssRule::css_text).collect();
+
+        rules.join("\n")
+    }
+
+    pub fn keyframes(&self, media: &MediaContext) -> Vec<&KeyframesRule> {
+        let mut res = Vec::new();
+        collect_active_rules(&self.rules, media, &mut res);
+
+        res.into_iter()
+            .filter_map(|r| match r {
+                CssRule::Keyframes(k) => Some(k),
+                _ => None,
+            })
+            .collect()
     }
 
-    pub fn delete_rule(&mut self, index: usize) {
-        self.rules.remove(index);
+    // style rules which apply in the given media context (in source order)
+    pub fn active_rules(&self, media: &MediaContext) -> Vec<&Rule> {
+        let mut res = Vec::new();
+        collect_active_rules(&self.rules, media, &mut res);
+
+        res.into_iter()
+            .filter_map(|r| match r {
+                CssRule::Style(r) => Some(r),
+                _ => None,
+            })
+            .collect()
+    }
+
+    // it's up to the viewport to load & insert them (before the sheet itself)
+    pub fn imports(&self, media: &MediaContext) -> Vec<&ImportRule> {
+        // only leading ones are valid
+        self.rules
+            .iter()
+            .map_while(|r| match r {
+                CssRule::Import(i) => Some(i),
+                _ => None,
+            })
+            .filter(|i| i.media.matches(media))
+            .collect()
+    }
+
+    pub fn font_faces(&self, media: &MediaContext) -> Vec<&FontFaceRule> {
+        let mut res = Vec::new();
+        collect_active_rules(&self.rules, media, &mut res);
+
+        res.into_iter()
+            .filter_map(|r| match r {
+                CssRule::FontFace(f) => Some(f),
+                _ => None,
+            })
+            .collect()
+    }
+}
+
+// shared with grouping rules
+fn insert_rule(rules: &mut Vec<CssRule>, rule: &str, index: usize) -> Result<(), &'static str> {
+    if index > rules.len() {
+        return Err("index out of bounds");
+    }
+
+    let tokens = super::parser::tokenize(rule.as_bytes());
+    let parser = super::parser::css_rule() - pom::parser::end();
+    let rule = parser.parse(&tokens).map_err(|_| "invalid rule")?;
+
+    rules.insert(index, rule);
+
+    Ok(())
+}
+
+fn delete_rule(rules: &mut Vec<CssRule>, index: usize) -> Result<(), &'static str> {
+    if index >= rules.len() {
+        return Err("index out of bounds");
+    }
+
+    rules.remove(index);
+
+    Ok(())
+}
+
+// flatten matching groups
+fn collect_active_rules<'a>(rules: &'a [CssRule], media: &MediaContext, res: &mut Vec<&'a CssRule>) {
+    for r in rules {
+        match r {
+            CssRule::Media(m) => {
+                if m.media.matches(media) {
+                    collect_active_rules(&m.rules, media, res);
+                }
+            }
+            CssRule::Supports(s) => {
+                if s.supported {
+                    collect_active_rules(&s.rules, media, res);
+                }
+            }
+            _ => res.push(r),
+        }
+    }
+}
+
+impl StyleSheet {
+    // same as from() but it also reports everything which was skipped
+    pub fn parse_with_diagnostics(sheet: &str) -> (Self, Vec<CssWarning>) {
+        super::parser::sheet_with_diagnostics(sheet)
     }
 }
 
@@ -42,6 +222,250 @@ impl From<&str> for StyleSheet {
     }
 }
 
+#[derive(Debug, Clone, PartialEq)]
+pub struct CssWarning {
+    // 1-based
+    pub line: usize,
+    pub column: usize,
+    pub prop: Option<String>,
+    pub value: String,
+    pub reason: CssWarningReason,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CssWarningReason {
+    UnknownProperty,
+    InvalidValue,
+    UnsupportedSelector,
+    InvalidRule,
+}
+
+impl fmt::Display for CssWarning {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let prop = self.prop.as_deref().unwrap_or("");
+
+        match self.reason {
+            CssWarningReason::UnknownProperty => write!(f, "unknown property `{}`", prop),
+            CssWarningReason::InvalidValue => write!(f, "invalid value `{}` for `{}`", self.value, prop),
+            CssWarningReason::UnsupportedSelector => write!(f, "unsupported selector `{}`", self.value),
+            CssWarningReason::InvalidRule => write!(f, "invalid rule `{}`", self.value),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum CssRule {
+    Style(Rule),
+    Media(MediaRule),
+    FontFace(FontFaceRule),
+    Keyframes(KeyframesRule),
+    Import(ImportRule),
+    Supports(SupportsRule),
+}
+
+impl CssRule {
+    pub fn css_text(&self) -> String {
+        match self {
+            Self::Style(r) => r.css_text(),
+            Self::Media(m) => m.css_text(),
+            Self::Import(i) => i.css_text(),
+            Self::Supports(s) => s.css_text(),
+            Self::FontFace(f) => f.css_text(),
+            Self::Keyframes(k) => k.css_text(),
+        }
+    }
+}
+
+// conditional group
+#[derive(Debug, PartialEq)]
+pub struct MediaRule {
+    pub(super) media: MediaQueryList,
+    pub(super) rules: Vec<CssRule>,
+}
+
+impl MediaRule {
+    pub fn media_text(&self) -> String {
+        self.media.to_string()
+    }
+
+    pub fn css_rules(&self) -> &[CssRule] {
+        &self.rules
+    }
+
+    pub fn css_rules_mut(&mut self) -> &mut [CssRule] {
+        &mut self.rules
+    }
+
+    pub fn insert_rule(&mut self, rule: &str, index: usize) -> Result<(), &'static str> {
+        insert_rule(&mut self.rules, rule, index)
+    }
+
+    pub fn delete_rule(&mut self, index: usize) -> Result<(), &'static str> {
+        delete_rule(&mut self.rules, index)
+    }
+
+    pub fn css_text(&self) -> String {
+        let mut res = match self.media.0.is_empty() {
+            true => "@media {\n".to_owned(),
+            false => format!("@media {} {{\n", self.media),
+        };
+
+        for r in &self.rules {
+            writeln!(res, "  {}", r.css_text()).unwrap();
+        }
+
+        res.push('}');
+        res
+    }
+}
+
+// conditional group, evaluated during parsing (against what we can parse)
+#[derive(Debug, PartialEq)]
+pub struct SupportsRule {
+    pub(super) condition: String,
+    pub(super) supported: bool,
+    pub(super) rules: Vec<CssRule>,
+}
+
+impl SupportsRule {
+    pub fn condition_text(&self) -> &str {
+        &self.condition
+    }
+
+    pub fn supported(&self) -> bool {
+        self.supported
+    }
+
+    pub fn css_rules(&self) -> &[CssRule] {
+        &self.rules
+    }
+
+    pub fn css_rules_mut(&mut self) -> &mut [CssRule] {
+        &mut self.rules
+    }
+
+    pub fn insert_rule(&mut self, rule: &str, index: usize) -> Result<(), &'static str> {
+        insert_rule(&mut self.rules, rule, index)
+    }
+
+    pub fn delete_rule(&mut self, index: usize) -> Result<(), &'static str> {
+        delete_rule(&mut self.rules, index)
+    }
+
+    pub fn css_text(&self) -> String {
+        let mut res = format!("@supports {} {{\n", self.condition);
+
+        for r in &self.rules {
+            writeln!(res, "  {}", r.css_text()).unwrap();
+        }
+
+        res.push('}');
+        res
+    }
+}
+
+// same as with fonts, the href is resolved & loaded in the viewport
+#[derive(Debug, PartialEq)]
+pub struct ImportRule {
+    pub href: String,
+    pub(super) media: MediaQueryList,
+}
+
+impl ImportRule {
+    pub fn media_text(&self) -> String {
+        self.media.to_string()
+    }
+
+    pub fn css_text(&self) -> String {
+        match self.media.0.is_empty() {
+            true => format!("@import url(\"{}\");", self.href),
+            false => format!("@import url(\"{}\") {};", self.href, self.media),
+        }
+    }
+}
+
+// CSS is unaware of fonts, it's up to the viewport to load them
+#[derive(Debug, PartialEq)]
+pub struct FontFaceRule {
+    pub family: String,
+    pub src: Vec<FontFaceSource>,
+    pub weight: (u16, u16),
+    pub italic: bool,
+    // empty means everything
+    pub unicode_range: Vec<(u32, u32)>,
+}
+
+impl FontFaceRule {
+    pub fn css_text(&self) -> String {
+        let mut res = format!("@font-face {{ font-family: \"{}\"; ", self.family);
+
+        if !self.src.is_empty() {
+            let src: Vec<_> = self
+                .src
+                .iter()
+                .map(|s| match s {
+                    FontFaceSource::Url(url) => format!("url(\"{}\")", url),
+                    FontFaceSource::Local(name) => format!("local(\"{}\")", name),
+                })
+                .collect();
+
+            write!(res, "src: {}; ", src.join(", ")).unwrap();
+        }
+
+        match self.weight {
+            (400, 400) => {}
+            (min, max) if min == max => write!(res, "font-weight: {}; ", min).unwrap(),
+            (min, max) => write!(res, "font-weight: {} {}; ", min, max).unwrap(),
+        }
+
+        if self.italic {
+            res.push_str("font-style: italic; ");
+        }
+
+        if !self.unicode_range.is_empty() {
+            let ranges: Vec<_> = self
+                .unicode_range
+                .iter()
+                .map(|&(from, to)| match from == to {
+                    true => format!("U+{:X}", from),
+                    false => format!("U+{:X}-{:X}", from, to),
+                })
+                .collect();
+
+            write!(res, "unicode-range: {}; ", ranges.join(", ")).unwrap();
+        }
+
+        res.push('}');
+        res
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum FontFaceSource {
+    Url(String),
+    Local(String),
+}
+
+#[derive(Debug, PartialEq)]
+pub struct KeyframesRule {
+    pub name: String,
+    // (offset 0..1, style), sorted by offset
+    pub keyframes: Vec<(f32, Style)>,
+}
+
+impl KeyframesRule {
+    pub fn css_text(&self) -> String {
+        let mut res = format!("@keyframes {} {{ ", self.name);
+
+        for (offset, style) in &self.keyframes {
+            write!(res, "{}% {{ {} }} ", offset * 100., style.css_text()).unwrap();
+        }
+
+        res.push('}');
+        res
+    }
+}
+
 #[derive(Debug, PartialEq)]
 pub struct Rule {
     selector: Selector,
@@ -53,9 +477,34 @@ impl Rule {
         Self { selector, style }
     }
 
+    pub fn selector_text(&self) -> String {
+        self.selector.to_string()
+    }
+
+    // invalid selector is ignored
+    pub fn set_selector_text(&mut self, selector: &str) {
+        let tokens = super::parser::tokenize(selector.as_bytes());
+        let parser = super::parser::selector() - pom::parser::end();
+
+        if let Ok(selector) = parser.parse(&tokens) {
+            self.selector = selector;
+        }
+    }
+
     pub fn style(&self) -> &Style {
         &self.style
     }
+
+    pub fn style_mut(&mut self) -> &mut Style {
+        &mut self.style
+    }
+
+    pub fn css_text(&self) -> String {
+        match self.style.css_text() {
+            s if s.is_empty() => format!("{} {{ }}", self.selector),
+            s => format!("{} {{ {} }}", self.selector, s),
+        }
+    }
 }
 
 #[cfg(test)]
@@ -75,4 +524,224 @@ mod tests {
 
         //assert_eq!(res.css_text(), "display: none");
     }
+
+    #[test]
+    fn active_rules() {
+        let sheet = StyleSheet::from(
+            "a {}
+             @media (max-width: 600px) { b {} @media (orientation: portrait) { c {} } }
+             d {}",
+        );
+        let selectors = |w, h| -> Vec<_> {
+            let media = MediaContext::new(w, h);
+            let res = sheet.active_rules(&media).iter().map(|r| r.selector.clone()).collect();
+            res
+        };
+
+        assert_eq!(selectors(800., 600.), vec![Selector::from("a"), Selector::from("d")]);
+        assert_eq!(
+            selectors(600., 400.),
+            vec![Selector::from("a"), Selector::from("b"), Selector::from("d")]
+        );
+        assert_eq!(
+            selectors(400., 600.),
+            vec![
+                Selector::from("a"),
+                Selector::from("b"),
+                Selector::from("c"),
+                Selector::from("d")
+            ]
+        );
+    }
+
+    #[test]
+    fn diagnostics() {
+        let (sheet, warnings) = StyleSheet::parse_with_diagnostics(
+            "a { colr: red; color: #ff }
+@medai screen { b {} }
+@media (max-width: 600px) {
+  a:hover, c ~ d { display: blocky; opacity: 1 }
+}",
+        );
+
+        assert_eq!(sheet.rules.len(), 2);
+
+        let warnings: Vec<_> = warnings
+            .iter()
+            .map(|w| format!("{}:{}: {}", w.line, w.column, w))
+            .collect();
+        assert_eq!(
+            warnings,
+            vec![
+                "1:5: unknown property `colr`",
+                "1:16: invalid value `#ff` for `color`",
+                "2:1: invalid rule `@medai screen`",
+                "4:4: unsupported selector `:hover`",
+                "4:14: unsupported selector `~`",
+                "4:20: invalid value `blocky` for `display`",
+            ]
+        );
+
+        assert_eq!(StyleSheet::parse_with_diagnostics("a { color: inherit }").1, vec![]);
+    }
+
+    #[test]
+    fn cssom() {
+        let mut sheet = StyleSheet::new();
+
+        assert_eq!(sheet.insert_rule("a { color: #000 }", 0), Ok(()));
+        assert_eq!(sheet.insert_rule("@media (max-width: 600px) {}", 1), Ok(()));
+        assert_eq!(sheet.insert_rule("b {", 0), Err("invalid rule"));
+        assert_eq!(sheet.insert_rule("a {} b {}", 0), Err("invalid rule"));
+        assert_eq!(sheet.insert_rule("b {}", 3), Err("index out of bounds"));
+
+        match &mut sheet.css_rules_mut()[0] {
+            CssRule::Style(r) => {
+                r.set_selector_text("div > .a:hover, #b");
+                r.set_selector_text("invalid >");
+                r.style_mut().set_property("display", "block");
+
+                assert_eq!(r.selector_text(), "div > .a:hover, #b");
+            }
+            _ => unreachable!(),
+        }
+
+        match &mut sheet.css_rules_mut()[1] {
+            CssRule::Media(m) => {
+                m.insert_rule("b {}", 0).unwrap();
+                m.insert_rule("@font-face { font-family: x; src: local(x) }", 1)
+                    .unwrap();
+                m.delete_rule(1).unwrap();
+                assert_eq!(m.delete_rule(1), Err("index out of bounds"));
+
+                assert_eq!(m.media_text(), "(max-width: 600px)");
+                assert_eq!(m.css_rules().len(), 1);
+            }
+            _ => unreachable!(),
+        }
+
+        assert_eq!(
+            sheet.css_text(),
+            "div > .a:hover, #b { color: rgba(0, 0, 0, 255);display: block; }\n@media (max-width: 600px) {\n  b { }\n}"
+        );
+
+        sheet.delete_rule(0).unwrap();
+        assert_eq!(sheet.css_rules().len(), 1);
+
+        let sheet = StyleSheet::from(
+            "@font-face { font-family: x; src: url(a.ttf), local(b); font-weight: 700; unicode-range: U+0-7F }
+             @keyframes k { from { opacity: 0 } to { opacity: 1 } }",
+        );
+        assert_eq!(
+            sheet.css_text(),
+            "@font-face { font-family: \"x\"; src: url(\"a.ttf\"), local(\"b\"); font-weight: 700; unicode-range: U+0-7F; }\n@keyframes k { 0% { opacity: 0; } 100% { opacity: 1; } }"
+        );
+    }
+
+    // synthetic tree: html > body > section.s{i} > (div|span|p|a|li).item.i{j}
+    fn tree(sections: usize, items: usize) -> (Vec<&'static str>, Vec<String>, Vec<Option<usize>>) {
+        let mut local_names = vec!["html", "body"];
+        let mut class_names = vec![String::new(), String::new()];
+        let mut parents = vec![None, Some(0)];
+
+        for i in 0..sections {
+            let section = local_names.len();
+            local_names.push("section");
+            class_names.push(format!("section s{}", i));
+            parents.push(Some(1));
+
+            for j in 0..items {
+                local_names.push(["div", "span", "p", "a", "li"][j % 5]);
+                class_names.push(format!("item i{}", j % 10));
+                parents.push(Some(section));
+            }
+        }
+
+        (local_names, class_names, parents)
+    }
+
+    fn sheet(n: usize) -> StyleSheet {
+        let mut css = String::new();
+
+        for i in 0..n {
+            match i % 5 {
+                0 => write!(css, ".i{} {{}}", i % 10),
+                1 => write!(css, ".s{} .item {{}}", i),
+                2 => write!(css, "#id{} {{}}", i),
+                3 => write!(css, "ul li .x{} {{}}", i),
+                _ => write!(css, "span.y{}, div > a.z{} {{}}", i, i),
+            }
+            .unwrap();
+        }
+
+        StyleSheet::from(&*css)
+    }
+
+    #[test]
+    fn rule_index() {
+        let (local_names, class_names, parents) = tree(10, 10);
+        let ctx = MatchingContext {
+            local_name: &|e| local_names[e],
+            identifier: &|_| None,
+            class_name: &|e: usize| Some(&*class_names[e]),
+            parent: &|e| parents[e],
+        };
+
+        let sheet = sheet(100);
+        let media = MediaContext::new(800., 600.);
+        let rules = sheet.active_rules(&media);
+        let index = RuleIndex::new(&rules);
+
+        for el in 0..local_names.len() {
+            let expected: Vec<_> = rules
+                .iter()
+                .filter(|r| ctx.match_selector(&r.selector, el).is_some())
+                .map(|r| *r as *const _)
+                .collect();
+            let res: Vec<_> = index
+                .matching_rules(&ctx, el, ctx.ancestor_mask(el))
+                .map(|r| r as *const _)
+                .collect();
+
+            assert_eq!(res, expected);
+        }
+    }
+
+    // masks are extended from the parent, like during restyle
+    // (timing is in examples/bench_matching.rs)
+    #[test]
+    fn indexed_matching() {
+        let (local_names, class_names, parents) = tree(100, 99);
+        let ctx = MatchingContext {
+            local_name: &|e| local_names[e],
+            identifier: &|_| None,
+            class_name: &|e: usize| Some(&*class_names[e]),
+            parent: &|e| parents[e],
+        };
+
+        let sheet = sheet(1000);
+        let media = MediaContext::new(800., 600.);
+        let rules = sheet.active_rules(&media);
+        let index = RuleIndex::new(&rules);
+        let mut masks = vec![SelectorMask::default(); local_names.len()];
+
+        for el in 0..local_names.len() {
+            // parents first
+            if let Some(p) = parents[el] {
+                masks[el] = masks[p] | ctx.mask(p);
+            }
+
+            let naive: Vec<_> = rules
+                .iter()
+                .filter(|r| ctx.match_selector(&r.selector, el).is_some())
+                .map(|r| *r as *const _)
+                .collect();
+            let indexed: Vec<_> = index
+                .matching_rules(&ctx, el, masks[el])
+                .map(|r| r as *const _)
+                .collect();
+
+            assert_eq!(indexed, naive);
+        }
+    }
 }
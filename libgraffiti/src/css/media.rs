@@ -0,0 +1,289 @@
+// media queries
+// x media types (all, screen, print)
+// x not, only, and, or (comma)
+// x width, height (min/max & range syntax)
+// x orientation
+// x prefers-color-scheme, prefers-reduced-motion
+// x resolution
+// x invalid queries are "not all"
+// - evaluated against MediaContext (provided by viewport)
+
+use std::fmt;
+use std::ops::{Bound, RangeBounds};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct MediaQueryList(pub(super) Vec<MediaQuery>);
+
+impl MediaQueryList {
+    pub fn matches(&self, ctx: &MediaContext) -> bool {
+        // empty list matches everything
+        self.0.is_empty() || self.0.iter().any(|q| q.matches(ctx))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(super) struct MediaQuery {
+    pub not: bool,
+    pub media_type: MediaType,
+    pub features: Vec<MediaFeature>,
+}
+
+impl MediaQuery {
+    pub const NOT_ALL: Self = Self {
+        not: true,
+        media_type: MediaType::All,
+        features: Vec::new(),
+    };
+
+    fn matches(&self, ctx: &MediaContext) -> bool {
+        let type_matches = match self.media_type {
+            MediaType::All | MediaType::Screen => true,
+            MediaType::Print => false,
+        };
+
+        (type_matches && self.features.iter().all(|f| f.matches(ctx))) != self.not
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(super) enum MediaType {
+    All,
+    Screen,
+    Print,
+}
+
+// (min, max)
+pub(super) type MediaRange = (Bound<f32>, Bound<f32>);
+
+#[derive(Debug, Clone, PartialEq)]
+pub(super) enum MediaFeature {
+    Width(MediaRange),
+    Height(MediaRange),
+    Orientation(MediaOrientation),
+    PrefersColorScheme(ColorScheme),
+    PrefersReducedMotion(bool),
+    // dppx
+    Resolution(MediaRange),
+}
+
+impl MediaFeature {
+    fn matches(&self, ctx: &MediaContext) -> bool {
+        match self {
+            Self::Width(range) => range.contains(&ctx.width),
+            Self::Height(range) => range.contains(&ctx.height),
+            Self::Orientation(o) => *o == ctx.orientation(),
+            Self::PrefersColorScheme(scheme) => *scheme == ctx.prefers_color_scheme,
+            Self::PrefersReducedMotion(reduce) => *reduce == ctx.prefers_reduced_motion,
+            Self::Resolution(range) => range.contains(&ctx.resolution),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(super) enum MediaOrientation {
+    Portrait,
+    Landscape,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ColorScheme {
+    Light,
+    Dark,
+}
+
+// what media queries are evaluated against
+#[derive(Debug, Clone, PartialEq)]
+pub struct MediaContext {
+    pub width: f32,
+    pub height: f32,
+    // dppx
+    pub resolution: f32,
+    pub prefers_color_scheme: ColorScheme,
+    pub prefers_reduced_motion: bool,
+}
+
+impl MediaContext {
+    pub fn new(width: f32, height: f32) -> Self {
+        Self {
+            width,
+            height,
+            resolution: 1.,
+            prefers_color_scheme: ColorScheme::Light,
+            prefers_reduced_motion: false,
+        }
+    }
+
+    fn orientation(&self) -> MediaOrientation {
+        if self.height >= self.width {
+            MediaOrientation::Portrait
+        } else {
+            MediaOrientation::Landscape
+        }
+    }
+}
+
+impl fmt::Display for MediaQueryList {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (i, q) in self.0.iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+
+            write!(f, "{}", q)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl fmt::Display for MediaQuery {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let media_type = match self.media_type {
+            MediaType::All => "all",
+            MediaType::Screen => "screen",
+            MediaType::Print => "print",
+        };
+
+        if self.not {
+            write!(f, "not ")?;
+        }
+
+        // all is implied if there are some features
+        if self.not || self.media_type != MediaType::All || self.features.is_empty() {
+            write!(f, "{}", media_type)?;
+
+            if !self.features.is_empty() {
+                write!(f, " and ")?;
+            }
+        }
+
+        for (i, feature) in self.features.iter().enumerate() {
+            if i > 0 {
+                write!(f, " and ")?;
+            }
+
+            write!(f, "{}", feature)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl fmt::Display for MediaFeature {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use Bound::*;
+
+        // min/max if possible, range syntax otherwise
+        let range = |f: &mut fmt::Formatter, name, range: &MediaRange, unit| match *range {
+            (Included(min), Included(max)) if min == max => write!(f, "({}: {}{})", name, min, unit),
+            (Included(min), Unbounded) => write!(f, "(min-{}: {}{})", name, min, unit),
+            (Unbounded, Included(max)) => write!(f, "(max-{}: {}{})", name, max, unit),
+            (min, max) => {
+                write!(f, "(")?;
+
+                match min {
+                    Included(v) => write!(f, "{}{} <= ", v, unit)?,
+                    Excluded(v) => write!(f, "{}{} < ", v, unit)?,
+                    Unbounded => {}
+                }
+
+                write!(f, "{}", name)?;
+
+                match max {
+                    Included(v) => write!(f, " <= {}{}", v, unit)?,
+                    Excluded(v) => write!(f, " < {}{}", v, unit)?,
+                    Unbounded => {}
+                }
+
+                write!(f, ")")
+            }
+        };
+
+        match self {
+            Self::Width(r) => range(f, "width", r, "px"),
+            Self::Height(r) => range(f, "height", r, "px"),
+            Self::Resolution(r) => range(f, "resolution", r, "dppx"),
+            Self::Orientation(MediaOrientation::Portrait) => write!(f, "(orientation: portrait)"),
+            Self::Orientation(MediaOrientation::Landscape) => write!(f, "(orientation: landscape)"),
+            Self::PrefersColorScheme(ColorScheme::Light) => write!(f, "(prefers-color-scheme: light)"),
+            Self::PrefersColorScheme(ColorScheme::Dark) => write!(f, "(prefers-color-scheme: dark)"),
+            Self::PrefersReducedMotion(true) => write!(f, "(prefers-reduced-motion: reduce)"),
+            Self::PrefersReducedMotion(false) => write!(f, "(prefers-reduced-motion: no-preference)"),
+        }
+    }
+}
+
+impl From<&str> for MediaQueryList {
+    fn from(media: &str) -> Self {
+        let tokens = super::parser::tokenize(media.as_bytes());
+
+        super::parser::media_query_list(&tokens)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matches() {
+        let ctx = MediaContext::new(800., 600.);
+        let m = |media| MediaQueryList::from(media).matches(&ctx);
+
+        assert!(m(""));
+        assert!(m("all"));
+        assert!(m("screen"));
+        assert!(!m("print"));
+        assert!(m("not print"));
+        assert!(m("only screen"));
+        assert!(m("print, screen"));
+
+        assert!(m("(min-width: 800px)"));
+        assert!(!m("(min-width: 801px)"));
+        assert!(m("(max-width: 50em)"));
+        assert!(m("(width: 800px)"));
+        assert!(m("screen and (min-width: 600px) and (max-height: 600px)"));
+        assert!(!m("screen and (min-width: 600px) and (max-height: 599px)"));
+        assert!(m("not screen and (max-width: 600px)"));
+
+        assert!(m("(width > 799px)"));
+        assert!(!m("(width < 800px)"));
+        assert!(m("(width <= 800px)"));
+        assert!(m("(600px < width <= 800px)"));
+        assert!(!m("(800px < width)"));
+
+        assert!(m("(orientation: landscape)"));
+        assert!(!m("(orientation: portrait)"));
+        assert!(m("(prefers-color-scheme: light)"));
+        assert!(!m("(prefers-color-scheme: dark)"));
+        assert!(m("(prefers-reduced-motion: no-preference)"));
+        assert!(!m("(prefers-reduced-motion: reduce)"));
+
+        assert!(m("(resolution: 1dppx)"));
+        assert!(m("(min-resolution: 96dpi)"));
+        assert!(!m("(min-resolution: 2x)"));
+
+        // invalid
+        assert!(!m("(unknown: 1px)"));
+        assert!(!m("tv"));
+        assert!(!m("(min-width: 600px) garbage"));
+        assert!(m("(unknown), screen"));
+    }
+
+    #[test]
+    fn display() {
+        let d = |media| MediaQueryList::from(media).to_string();
+
+        assert_eq!(d(""), "");
+        assert_eq!(d("screen, print"), "screen, print");
+        assert_eq!(d("only screen and (min-width:600px)"), "screen and (min-width: 600px)");
+        assert_eq!(
+            d("(max-width: 50em) and (orientation: portrait)"),
+            "(max-width: 800px) and (orientation: portrait)"
+        );
+        assert_eq!(d("(600px < width <= 800px)"), "(600px < width <= 800px)");
+        assert_eq!(d("(width > 10px)"), "(10px < width)");
+        assert_eq!(d("(min-resolution: 2x)"), "(min-resolution: 2dppx)");
+        assert_eq!(d("tv"), "not all");
+    }
+}
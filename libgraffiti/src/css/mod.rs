@@ -1,4 +1,6 @@
+mod animation;
 mod css_engine;
+mod media;
 mod parser;
 mod properties;
 mod selector;
@@ -6,6 +8,7 @@ mod style;
 mod value_types;
 
 pub use css_engine::*;
+pub use media::*;
 pub use properties::*;
 pub use selector::*;
 pub use style::*;
@@ -10,12 +10,19 @@
 //   and we also get correct overriding for free (only valid prop should override prev one)
 
 use super::{
-    Combinator, Component, CssBorderStyle, CssBoxShadow, CssColor, CssDimension, CssOverflow, Rule, Selector,
-    SelectorPart, Style, StyleSheet,
+    ColorScheme, Combinator, Component, CssAnimationDirection, CssAnimationFillMode, CssAnimationPlayState,
+    CssBorderStyle, CssBoxShadow, CssCalc, CssColor, CssColorStop, CssDimension, CssFontStyle, CssGradient,
+    CssGradientDirection, CssGradientShape, CssImage, CssIterationCount, CssList, CssOrigin, CssOverflow,
+    CssRadialExtent, CssRule, CssStepPosition, CssTextDecorationLine, CssTextDecorationStyle, CssTime,
+    CssTimingFunction, CssTransform, CssTransformFn, CssWarning, CssWarningReason, CssWideKeyword, CssZIndex,
+    FontFaceRule, FontFaceSource, ImportRule, KeyframesRule, MediaFeature, MediaOrientation, MediaQuery,
+    MediaQueryList, MediaRange, MediaRule, MediaType, Rule, Selector, SelectorPart, Style, StyleProp, StylePropId,
+    StyleSheet, SupportsRule,
 };
 use crate::util::Atom;
 use pom::char_class::alphanum;
-use pom::parser::{any, empty, is_a, list, none_of, one_of, seq, skip, sym};
+use pom::parser::{any, call, empty, end, is_a, list, none_of, one_of, seq, skip, sym};
+use std::cell::RefCell;
 use std::convert::TryFrom;
 use std::fmt::Debug;
 
@@ -23,17 +30,543 @@ pub(super) type Parser<'a, T> = pom::parser::Parser<'a, Token<'a>, T>;
 type Token<'a> = &'a str;
 // pub type ParseError = pom::Error;
 
+thread_local! {
+    // (token ptrs, prop, value, reason), only collected during sheet_with_diagnostics()
+    static WARNINGS: RefCell<Option<Vec<(Vec<usize>, Option<String>, String, CssWarningReason)>>> = RefCell::new(None);
+}
+
+// collects warnings until it's dropped (so nothing is left behind after panic)
+struct CollectWarnings;
+
+impl CollectWarnings {
+    fn start() -> Self {
+        WARNINGS.with(|w| *w.borrow_mut() = Some(Vec::new()));
+        Self
+    }
+
+    fn finish(self, input: &str) -> Vec<CssWarning> {
+        let warnings = WARNINGS.with(|w| w.borrow_mut().take()).unwrap_or_default();
+        let range = input.as_ptr() as usize..input.as_ptr() as usize + input.len();
+
+        // only tokens which are slices of the input (not the normalized " ")
+        let mut warnings: Vec<_> = warnings
+            .into_iter()
+            .map(|(ptrs, prop, value, reason)| {
+                let offset = ptrs
+                    .into_iter()
+                    .find(|p| range.contains(p))
+                    .map_or(0, |p| p - range.start);
+                (offset, prop, value, reason)
+            })
+            .collect();
+
+        // backtracking can report the same thing more than once
+        warnings.sort_by_key(|w| (w.0, w.3 as u8));
+        warnings.dedup();
+
+        warnings
+            .into_iter()
+            .map(|(offset, prop, value, reason)| {
+                let before = &input[..offset];
+
+                CssWarning {
+                    line: before.matches('\n').count() + 1,
+                    column: before.rsplit('\n').next().unwrap_or("").chars().count() + 1,
+                    prop,
+                    value,
+                    reason,
+                }
+            })
+            .collect()
+    }
+}
+
+impl Drop for CollectWarnings {
+    fn drop(&mut self) {
+        WARNINGS.with(|w| w.borrow_mut().take());
+    }
+}
+
 pub(super) fn sheet<'a>() -> Parser<'a, StyleSheet> {
-    // anything until next "}}" (empty media is matched with unknown)
-    let media = sym("@") * sym("media") * (!seq(&["}", "}"]) * skip(1)).repeat(1..).map(|_| None) - sym("}") - sym("}");
-    // anything until next "}"
-    let unknown = (!sym("}") * skip(1)).repeat(1..).map(|_| None) - sym("}").opt();
+    rules().map(|rules| StyleSheet { rules })
+}
+
+pub(super) fn sheet_with_diagnostics(input: &str) -> (StyleSheet, Vec<CssWarning>) {
+    let collect = CollectWarnings::start();
+    let tokens = tokenize(input.as_bytes());
+    let sheet = sheet().parse(&tokens).unwrap_or_else(|_| StyleSheet::new());
+
+    (sheet, collect.finish(input))
+}
 
-    (rule().map(Option::Some) | media | unknown)
+// position is taken from the first token which is part of the input
+fn warn(at: &str, prop: Option<&str>, value: &[&str], reason: CssWarningReason) {
+    WARNINGS.with(|w| {
+        if let Some(warnings) = &mut *w.borrow_mut() {
+            let ptrs = std::iter::once(at)
+                .chain(value.iter().copied())
+                .map(|t| t.as_ptr() as usize)
+                .collect();
+            warnings.push((ptrs, prop.map(str::to_owned), value.concat(), reason));
+        }
+    })
+}
+
+pub(super) fn css_rule<'a>() -> Parser<'a, CssRule> {
+    rule().map(CssRule::Style)
+        | call(media_rule).map(CssRule::Media)
+        | font_face_rule().map(CssRule::FontFace)
+        | keyframes_rule().map(CssRule::Keyframes)
+        | import_rule().map(CssRule::Import)
+        | call(supports_rule).map(CssRule::Supports)
+}
+
+fn rules<'a>() -> Parser<'a, Vec<CssRule>> {
+    // anything until next ";" or {} block (which can be nested)
+    let prelude = (!sym("{") * !sym("}") * !sym(";") * skip(1)).repeat(0..);
+    let unknown = (prelude * (call(block) | sym(";").discard()))
+        .collect()
+        .map(|tokens: &[&str]| {
+            let head = tokens.split(|t| *t == "{" || *t == ";").next().unwrap_or(tokens);
+            warn(tokens[0], None, head, CssWarningReason::InvalidRule);
+            None
+        });
+
+    // nested rules go first (flat rule() would stop at the first nested "}")
+    let rule = call(style_rules) | css_rule().map(|r| vec![r]);
+
+    (rule.map(Option::Some) | unknown)
         .repeat(0..)
-        .map(|maybe_rules| StyleSheet {
-            rules: maybe_rules.into_iter().flatten().collect(),
+        .map(|maybe_rules| maybe_rules.into_iter().flatten().flatten().collect())
+}
+
+fn block<'a>() -> Parser<'a, ()> {
+    sym("{") * (call(block) | (!sym("{") * !sym("}") * skip(1))).repeat(0..) * sym("}").discard()
+}
+
+fn media_rule<'a>() -> Parser<'a, MediaRule> {
+    let media = (!sym("{") * skip(1)).repeat(0..).collect().map(media_query_list);
+    let media_rule = sym("@") * sym("media") * media - sym("{") + call(rules) - sym("}");
+
+    media_rule.map(|(media, rules)| MediaRule { media, rules })
+}
+
+fn import_rule<'a>() -> Parser<'a, ImportRule> {
+    let url = sym("url") * sym("(") * (!sym(")") * skip(1)).repeat(1..).collect() - sym(")");
+    let string = is_a(|t: &str| t.starts_with('"') || t.starts_with('\'')).collect();
+    let media = (!sym(";") * skip(1)).repeat(0..).collect().map(media_query_list);
+    let rule = sym("@") * sym("import") * space() * (url | string) + media - sym(";");
+
+    rule.map(|(href, media)| ImportRule {
+        href: unquote(&href.concat()),
+        media,
+    })
+}
+
+fn supports_rule<'a>() -> Parser<'a, SupportsRule> {
+    let condition = (!sym("{") * skip(1)).repeat(1..).collect();
+    let rule = sym("@") * sym("supports") * space() * condition - sym("{") + call(rules) - sym("}");
+
+    rule.convert(|(condition, rules)| {
+        let supported = (space() * supports_condition() - space() - end()).parse(condition)?;
+
+        Ok::<_, pom::Error>(SupportsRule {
+            condition: supports_text(condition),
+            supported,
+            rules,
+        })
+    })
+}
+
+// evaluated right away, declarations are supported if we can parse them
+fn supports_condition<'a>() -> Parser<'a, bool> {
+    let not = sym("not") * space() * supports_in_parens().map(|v| !v);
+    let and = supports_in_parens() + (space() * sym("and") * space() * supports_in_parens()).repeat(1..);
+    let or = supports_in_parens() + (space() * sym("or") * space() * supports_in_parens()).repeat(1..);
+
+    not | and.map(|(a, rest)| a && rest.into_iter().all(|b| b))
+        | or.map(|(a, rest)| a || rest.into_iter().any(|b| b))
+        | supports_in_parens()
+}
+
+fn supports_in_parens<'a>() -> Parser<'a, bool> {
+    let value = (call(parens) | (!sym("(") * !sym(")") * skip(1)).discard())
+        .repeat(1..)
+        .collect();
+    let decl = (ident() - sym(":") + value).map(|(prop, value)| parse_prop_into(prop, value, &mut Style::new()));
+    // selector(), unknown functions, etc.
+    let general = (ident().opt() * call(parens)).map(|_| false);
+
+    sym("(") * (call(supports_condition) | decl) - sym(")") | general
+}
+
+// balanced (...)
+fn parens<'a>() -> Parser<'a, ()> {
+    sym("(") * (call(parens) | (!sym("(") * !sym(")") * skip(1))).repeat(0..) * sym(")").discard()
+}
+
+// tokens without (most of) the white-space
+fn supports_text(tokens: &[&str]) -> String {
+    let mut res = String::new();
+
+    for &t in tokens.iter().filter(|t| **t != " ") {
+        match t {
+            "and" | "or" => res.push_str(&format!(" {} ", t)),
+            "not" => res.push_str("not "),
+            ":" => res.push_str(": "),
+            _ => res.push_str(t),
+        }
+    }
+
+    res
+}
+
+pub(super) fn media_query_list(tokens: &[&str]) -> MediaQueryList {
+    if tokens.iter().all(|t| *t == " ") {
+        return MediaQueryList(Vec::new());
+    }
+
+    // invalid queries are replaced with "not all"
+    let queries = tokens
+        .split(|t| *t == ",")
+        .map(|q| {
+            (space() * media_query() - space() - end())
+                .parse(q)
+                .unwrap_or(MediaQuery::NOT_ALL)
         })
+        .collect();
+
+    MediaQueryList(queries)
+}
+
+fn media_query<'a>() -> Parser<'a, MediaQuery> {
+    let and = || space() * sym("and") - space();
+    let media_type = sym("all").map(|_| MediaType::All)
+        | sym("screen").map(|_| MediaType::Screen)
+        | sym("print").map(|_| MediaType::Print);
+    let with_type = (sym("not") - space()).opt()
+        + ((sym("only") - space()).opt() * media_type)
+        + (and() * media_feature()).repeat(0..);
+    let without_type = media_feature() + (and() * media_feature()).repeat(0..);
+
+    with_type.map(|((not, media_type), features)| MediaQuery {
+        not: not.is_some(),
+        media_type,
+        features,
+    }) | without_type.map(|(head, tail)| MediaQuery {
+        not: false,
+        media_type: MediaType::All,
+        features: std::iter::once(head).chain(tail).collect(),
+    })
+}
+
+fn media_feature<'a>() -> Parser<'a, MediaFeature> {
+    use MediaFeature::*;
+
+    let orientation =
+        sym("portrait").map(|_| MediaOrientation::Portrait) | sym("landscape").map(|_| MediaOrientation::Landscape);
+    let color_scheme = sym("light").map(|_| ColorScheme::Light) | sym("dark").map(|_| ColorScheme::Dark);
+    let reduced_motion = sym("reduce").map(|_| true) | sym("no-preference").map(|_| false);
+
+    let feature = media_range("width", "min-width", "max-width", media_length).map(Width)
+        | media_range("height", "min-height", "max-height", media_length).map(Height)
+        | media_range("resolution", "min-resolution", "max-resolution", resolution).map(Resolution)
+        | (sym("orientation") * sym(":") * orientation).map(Orientation)
+        | (sym("prefers-color-scheme") * sym(":") * color_scheme).map(PrefersColorScheme)
+        | (sym("prefers-reduced-motion") * sym(":") * reduced_motion).map(PrefersReducedMotion);
+
+    sym("(") * space() * feature - space() - sym(")")
+}
+
+fn media_range<'a>(
+    name: &'static str,
+    min_name: &'static str,
+    max_name: &'static str,
+    value: fn() -> Parser<'a, f32>,
+) -> Parser<'a, MediaRange> {
+    use std::ops::Bound::*;
+
+    // `feature <op> value`
+    let range = |(op, eq): (&str, bool), v| {
+        let bound = if eq { Included(v) } else { Excluded(v) };
+
+        match op {
+            "<" => (Unbounded, bound),
+            ">" => (bound, Unbounded),
+            _ => (Included(v), Included(v)),
+        }
+    };
+    let flip = |(op, eq)| match op {
+        "<" => (">", eq),
+        ">" => ("<", eq),
+        _ => (op, eq),
+    };
+    let cmp = || (sym("<") | sym(">")) + sym("=").opt().map(|eq| eq.is_some()) | sym("=").map(|op| (op, true));
+
+    let exact = sym(name) * sym(":") * value().map(|v| (Included(v), Included(v)));
+    let min = sym(min_name) * sym(":") * value().map(|v| (Included(v), Unbounded));
+    let max = sym(max_name) * sym(":") * value().map(|v| (Unbounded, Included(v)));
+
+    // level 4
+    let name_first = (sym(name) * cmp() + value()).map(move |(op, v)| range(op, v));
+    let value_first = (value() + cmp() - sym(name) + (cmp() + value()).opt()).map(move |((v, op), rest)| {
+        let (min, max) = range(flip(op), v);
+
+        match rest.map(|(op, v)| range(op, v)) {
+            Some((min2, max2)) => (
+                if let Unbounded = min { min2 } else { min },
+                if let Unbounded = max { max2 } else { max },
+            ),
+            None => (min, max),
+        }
+    });
+
+    exact | min | max | name_first | value_first
+}
+
+fn font_face_rule<'a>() -> Parser<'a, FontFaceRule> {
+    // same as in style() but descriptors are not props
+    let value = (!sym(";") * !sym("}") * skip(1)).repeat(1..).collect();
+    let descriptor = any() - sym(":") + value - sym(";").discard().repeat(0..);
+    let rule = sym("@") * sym("font-face") * sym("{") * descriptor.repeat(0..) - sym("}");
+
+    let url = sym("url") * sym("(") * (!sym(")") * skip(1)).repeat(1..).collect() - sym(")");
+    let local = sym("local") * sym("(") * (!sym(")") * skip(1)).repeat(1..).collect() - sym(")");
+    let format = sym("format") * sym("(") * (!sym(")") * skip(1)).repeat(0..) * sym(")");
+    let source = (url.map(|t: &[&str]| FontFaceSource::Url(unquote(&t.concat())))
+        | local.map(|t: &[&str]| FontFaceSource::Local(unquote(&t.concat()))))
+        - space()
+        - format.opt();
+    let src = list(source, sym(","));
+
+    let weight = (font_weight() + (space() * font_weight()).opt()).map(|(min, max)| (min, max.unwrap_or(min)));
+    let style = sym("normal").map(|_| false) | (sym("italic") | sym("oblique")).map(|_| true);
+
+    rule.convert(move |descriptors| {
+        let mut res = FontFaceRule {
+            family: String::new(),
+            src: Vec::new(),
+            weight: (400, 400),
+            italic: false,
+            unicode_range: Vec::new(),
+        };
+
+        // skip invalid
+        for (d, v) in descriptors {
+            match d {
+                "font-family" => res.family = family_name().parse(v).unwrap_or_default(),
+                "src" => res.src = src.parse(v).unwrap_or_default(),
+                "font-weight" => res.weight = weight.parse(v).unwrap_or(res.weight),
+                "font-style" => res.italic = style.parse(v).unwrap_or(res.italic),
+                "unicode-range" => res.unicode_range = v.concat().split(',').filter_map(unicode_range).collect(),
+                _ => {}
+            }
+        }
+
+        if res.family.is_empty() || res.src.is_empty() {
+            return Err("font-family and src are required");
+        }
+
+        Ok(res)
+    })
+}
+
+fn keyframes_rule<'a>() -> Parser<'a, KeyframesRule> {
+    let offset = sym("from").map(|_| 0.)
+        | sym("to").map(|_| 1.)
+        | (float() - sym("%")).convert(|v| match (0. ..=100.).contains(&v) {
+            true => Ok(v / 100.),
+            false => Err("offset out of range"),
+        });
+    let keyframe = list(offset, sym(",")) - sym("{") + style() - sym("}");
+    let rule = sym("@") * sym("keyframes") * space() * animation_name() - sym("{") + keyframe.repeat(0..) - sym("}");
+
+    rule.convert(|(name, keyframes)| {
+        if &*name == "none" {
+            return Err("invalid name");
+        }
+
+        let mut frames: Vec<_> = keyframes
+            .into_iter()
+            .flat_map(|(offsets, style)| offsets.into_iter().map(move |o| (o, style.clone())))
+            .collect();
+
+        // stable, so the later one wins for the same offset
+        frames.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
+
+        Ok(KeyframesRule {
+            name: name.to_string(),
+            keyframes: frames,
+        })
+    })
+}
+
+// U+26, U+0-7F, U+4??
+fn unicode_range(range: &str) -> Option<(u32, u32)> {
+    let range = range.trim();
+
+    if !range.get(..2)?.eq_ignore_ascii_case("u+") {
+        return None;
+    }
+
+    let range = &range[2..];
+    let hex = |s: &str| u32::from_str_radix(s, 16).ok();
+
+    match range.find('-') {
+        Some(i) => Some((hex(&range[..i])?, hex(&range[i + 1..])?)),
+        None => Some((hex(&range.replace('?', "0"))?, hex(&range.replace('?', "F"))?)),
+    }
+}
+
+fn unquote(s: &str) -> String {
+    s.trim_matches(|c| c == '"' || c == '\'').to_owned()
+}
+
+// relative units are relative to the initial font-size
+fn media_length<'a>() -> Parser<'a, f32> {
+    let px = float() - sym("px");
+    let em = (float() - (sym("em") | sym("rem"))).map(|v| v * 16.);
+    let zero = sym("0").map(|_| 0.);
+
+    px | em | zero
+}
+
+// dppx
+fn resolution<'a>() -> Parser<'a, f32> {
+    let dppx = float() - (sym("dppx") | sym("x"));
+    let dpi = (float() - sym("dpi")).map(|v| v / 96.);
+    let dpcm = (float() - sym("dpcm")).map(|v| v * 2.54 / 96.);
+
+    dppx | dpi | dpcm
+}
+
+// rule body with nesting (before desugaring)
+enum Nested<'a> {
+    Decl(&'a str, &'a [&'a str]),
+    Rule(&'a [&'a str], Vec<Nested<'a>>),
+    Media(MediaQueryList, Vec<Nested<'a>>),
+}
+
+// style rule with nested rules & @media desugared into flat rules
+// (parent goes first, then nested ones in source order)
+fn style_rules<'a>() -> Parser<'a, Vec<CssRule>> {
+    let prelude = (!sym("{") * !sym(";") * !sym("}") * skip(1)).repeat(1..).collect();
+    let rule = prelude - sym("{") + call(nested_block) - sym("}");
+
+    rule.convert(|(selector, items)| {
+        let alternatives = selector_alternatives(&[], selector).ok_or("invalid selector")?;
+        let mut res = Vec::new();
+        desugar(&alternatives, items, true, &mut res);
+
+        Ok::<_, &str>(res)
+    })
+}
+
+fn nested_block<'a>() -> Parser<'a, Vec<Nested<'a>>> {
+    // same as in style() but it can't be followed by a block
+    let value = (!sym(";") * !sym("}") * !sym("{") * skip(1)).repeat(1..).collect();
+    let decl = (any() - sym(":") + value - !sym("{")).map(|(p, v)| Nested::Decl(p, v));
+
+    let media = (!sym("{") * skip(1)).repeat(0..).collect().map(media_query_list);
+    let media = (sym("@") * sym("media") * media - sym("{") + call(nested_block) - sym("}"))
+        .map(|(media, items)| Nested::Media(media, items));
+
+    let prelude = (!sym("{") * !sym(";") * !sym("}") * skip(1)).repeat(1..).collect();
+    let rule = (prelude - sym("{") + call(nested_block) - sym("}")).map(|(sel, items)| Nested::Rule(sel, items));
+
+    ((decl | media | rule) - sym(";").discard().repeat(0..)).repeat(0..)
+}
+
+fn desugar(selector: &[Vec<&str>], items: Vec<Nested>, top: bool, res: &mut Vec<CssRule>) {
+    let mut style = Style::new();
+    let mut has_decls = false;
+
+    for item in &items {
+        if let Nested::Decl(p, v) = item {
+            add_prop(p, v, &mut style);
+            has_decls = true;
+        }
+    }
+
+    // empty rules are kept but only at the top
+    if has_decls || (top && items.is_empty()) {
+        if let Some(sel) = parse_selector(&selector.join(&",")) {
+            res.push(CssRule::Style(Rule::new(sel, style)));
+        }
+    }
+
+    for item in items {
+        match item {
+            Nested::Decl(..) => {}
+            Nested::Rule(nested, items) => match selector_alternatives(selector, nested) {
+                Some(alternatives) => desugar(&alternatives, items, false, res),
+                None => warn(nested[0], None, nested, CssWarningReason::InvalidRule),
+            },
+            Nested::Media(media, items) => {
+                let mut rules = Vec::new();
+                desugar(selector, items, false, &mut rules);
+                res.push(CssRule::Media(MediaRule { media, rules }));
+            }
+        }
+    }
+}
+
+// tokens of each alternative, nested ones are combined with each of the parent ones
+// (`&` is replaced, otherwise it's a descendant, unless it starts with a combinator)
+fn selector_alternatives<'a>(parent: &[Vec<&'a str>], selector: &[&'a str]) -> Option<Vec<Vec<&'a str>>> {
+    let mut res = Vec::new();
+
+    for alt in selector.split(|t| *t == ",") {
+        let alt = trim_space(alt);
+
+        if alt.is_empty() {
+            return None;
+        }
+
+        if parent.is_empty() {
+            res.push(alt.to_vec());
+            continue;
+        }
+
+        for p in parent {
+            let mut tokens = Vec::new();
+
+            if alt.contains(&"&") {
+                for t in alt {
+                    match *t {
+                        "&" => tokens.extend_from_slice(p),
+                        _ => tokens.push(*t),
+                    }
+                }
+            } else {
+                tokens.extend_from_slice(p);
+
+                if !matches!(alt[0], ">" | "+" | "~") {
+                    tokens.push(" ");
+                }
+
+                tokens.extend_from_slice(alt);
+            }
+
+            res.push(tokens);
+        }
+    }
+
+    // validate
+    parse_selector(&res.join(&","))?;
+
+    Some(res)
+}
+
+fn parse_selector(tokens: &[&str]) -> Option<Selector> {
+    (selector() - end()).parse(tokens).ok()
+}
+
+fn trim_space<'a, 'b>(tokens: &'b [&'a str]) -> &'b [&'a str] {
+    let start = tokens.iter().position(|t| *t != " ").unwrap_or(tokens.len());
+    let end = tokens.iter().rposition(|t| *t != " ").map_or(start, |i| i + 1);
+
+    &tokens[start..end]
 }
 
 fn rule<'a>() -> Parser<'a, Rule> {
@@ -48,8 +581,10 @@ pub(super) fn selector<'a>() -> Parser<'a, Selector> {
         let local_name = ident().map(Component::LocalName);
         let id = sym("#") * ident().map(Component::Identifier);
         let class_name = sym(".") * ident().map(Component::ClassName);
-        let attr = sym("[") * (!sym("]") * skip(1)).repeat(1..).map(|_| Component::Unsupported) - sym("]");
-        let pseudo = sym(":").discard().repeat(1..3) * ident().map(|_| Component::Unsupported);
+        let attr = (sym("[") * (!sym("]") * skip(1)).repeat(1..) - sym("]"))
+            .collect()
+            .map(unsupported);
+        let pseudo = (sym(":").discard().repeat(1..3) * ident()).collect().map(unsupported);
         let universal = sym("*").map(|_| SelectorPart::Combinator(Combinator::Universal));
 
         universal | (id | class_name | local_name | attr | pseudo).map(SelectorPart::Component)
@@ -59,8 +594,10 @@ pub(super) fn selector<'a>() -> Parser<'a, Selector> {
     let child = sym(">").map(|_| Combinator::Parent);
     let descendant = sym(" ").map(|_| Combinator::Ancestor);
     let or = sym(",").map(|_| Combinator::Or);
-    let unsupported = (sym("+") | sym("~")).map(|_| SelectorPart::Component(Component::Unsupported));
-    let comb = (child | descendant | or).map(SelectorPart::Combinator) | unsupported;
+    let sibling = (sym("+") | sym("~"))
+        .collect()
+        .map(|t| SelectorPart::Component(unsupported(t)));
+    let comb = (child | descendant | or).map(SelectorPart::Combinator) | sibling;
 
     let selector = tag() + (comb.opt() + tag()).repeat(0..);
 
@@ -82,6 +619,11 @@ pub(super) fn selector<'a>() -> Parser<'a, Selector> {
     })
 }
 
+fn unsupported(tokens: &[&str]) -> Component {
+    warn(tokens[0], None, tokens, CssWarningReason::UnsupportedSelector);
+    Component::Unsupported(Atom::from(&*tokens.concat()))
+}
+
 pub(super) fn style<'a>() -> Parser<'a, Style> {
     // any chunk of tokens before ";" or "}"
     let prop_value = (!sym(";") * !sym("}") * skip(1)).repeat(1..).collect();
@@ -91,22 +633,54 @@ pub(super) fn style<'a>() -> Parser<'a, Style> {
         let mut style = Style::new();
 
         for (p, v) in props {
-            // skip unknown
-            parse_prop_into(p, v, &mut style);
+            add_prop(p, v, &mut style);
         }
 
         style
     })
 }
 
-pub(super) fn parse_prop_into<'a>(prop: &str, value: &[&str], style: &mut Style) {
+// skip unknown/invalid (with a warning)
+fn add_prop(prop: &str, value: &[&str], style: &mut Style) {
+    if !parse_prop_into(prop, value, style) {
+        let known = StylePropId::from_name(prop).is_some() || super::shorthand_longhands(prop).is_some();
+        let reason = match known {
+            true => CssWarningReason::InvalidValue,
+            false => CssWarningReason::UnknownProperty,
+        };
+
+        warn(prop, Some(prop), value, reason);
+    }
+}
+
+// returns false if the prop was skipped
+pub(super) fn parse_prop_into<'a>(prop: &str, value: &[&str], style: &mut Style) -> bool {
+    // inherit, initial, unset, revert (has to go first, some parsers would accept any ident)
+    if let Ok(keyword) = (try_from::<CssWideKeyword>() - end()).parse(value) {
+        if let Some(id) = StylePropId::from_name(prop) {
+            style.add_prop(StyleProp::Keyword(id, keyword));
+        } else if let Some(ids) = super::shorthand_longhands(prop) {
+            for id in ids {
+                style.add_prop(StyleProp::Keyword(*id, keyword));
+            }
+        } else {
+            return false;
+        }
+
+        return true;
+    }
+
     if let Ok(p) = super::prop_parser(prop).parse(value) {
         style.add_prop(p);
     } else if let Ok(props) = super::shorthand_parser(prop).parse(value) {
         for p in props {
             style.add_prop(p);
         }
+    } else {
+        return false;
     }
+
+    true
 }
 
 pub(super) fn try_from<'a, T: 'static + TryFrom<&'a str>>() -> Parser<'a, T>
@@ -119,20 +693,97 @@ where
 pub(super) fn dimension<'a>() -> Parser<'a, CssDimension> {
     let px = (float() - sym("px")).map(CssDimension::Px);
     let percent = (float() - sym("%")).map(CssDimension::Percent);
+    let em = (float() - sym("em")).map(CssDimension::Em);
+    let rem = (float() - sym("rem")).map(CssDimension::Rem);
+    let vw = (float() - sym("vw")).map(CssDimension::Vw);
+    let vh = (float() - sym("vh")).map(CssDimension::Vh);
+    let vmin = (float() - sym("vmin")).map(CssDimension::Vmin);
+    let vmax = (float() - sym("vmax")).map(CssDimension::Vmax);
     let auto = sym("auto").map(|_| CssDimension::Auto);
     let zero = sym("0").map(|_| CssDimension::ZERO);
+    let calc = calc().map(|calc| match calc {
+        CssCalc::Px(v) => CssDimension::Px(v),
+        CssCalc::Percent(v) => CssDimension::Percent(v),
+        _ => CssDimension::Calc(Box::new(calc)),
+    });
+
+    px | percent | em | rem | vw | vh | vmin | vmax | auto | zero | calc
+}
+
+// calc(), min(), max(), clamp()
+fn calc<'a>() -> Parser<'a, CssCalc> {
+    let comma = || space() * sym(",") - space();
+    let args = || {
+        list(call(calc_sum), comma()).convert(|args| match args.is_empty() {
+            true => Err("expected at least one argument"),
+            false => Ok(args),
+        })
+    };
+
+    let calc = sym("calc") * sym("(") * space() * call(calc_sum) - space() - sym(")");
+    let min = sym("min") * sym("(") * args().map(CssCalc::Min) - sym(")");
+    let max = sym("max") * sym("(") * args().map(CssCalc::Max) - sym(")");
+    let clamp = sym("clamp")
+        * sym("(")
+        * (call(calc_sum) - comma() + call(calc_sum) - comma() + call(calc_sum))
+            .map(|((min, v), max)| CssCalc::Clamp(Box::new((min, v, max))))
+        - sym(")");
 
-    px | percent | auto | zero
+    calc | min | max | clamp
 }
 
-pub(super) fn sides_of<'a, V: Copy + 'a>(parser: Parser<'a, V>) -> Parser<'a, (V, V, V, V)> {
+fn calc_sum<'a>() -> Parser<'a, CssCalc> {
+    let op = space() * (sym("+") | sym("-")) - space();
+
+    (call(calc_product) + (op + call(calc_product)).repeat(0..)).map(|(first, rest)| {
+        rest.into_iter().fold(first, |a, (op, b)| match op {
+            "+" => CssCalc::Add(Box::new(a), Box::new(b)),
+            _ => CssCalc::Sub(Box::new(a), Box::new(b)),
+        })
+    })
+}
+
+fn calc_product<'a>() -> Parser<'a, CssCalc> {
+    let op = space() * (sym("*") | sym("/")) - space();
+    let num_first =
+        (float() - space() - sym("*") - space() + call(calc_value)).map(|(n, v)| CssCalc::Mul(Box::new(v), n));
+
+    // division by zero is invalid at parse time
+    let rhs = (op + float()).convert(|(op, n)| match (op, n) {
+        ("/", n) if n == 0. => Err("division by zero"),
+        _ => Ok((op, n)),
+    });
+
+    ((num_first | call(calc_value)) + rhs.repeat(0..)).map(|(first, rest)| {
+        rest.into_iter().fold(first, |a, (op, n)| match op {
+            "*" => CssCalc::Mul(Box::new(a), n),
+            _ => CssCalc::Div(Box::new(a), n),
+        })
+    })
+}
+
+fn calc_value<'a>() -> Parser<'a, CssCalc> {
+    let px = (float() - sym("px")).map(CssCalc::Px);
+    let percent = (float() - sym("%")).map(CssCalc::Percent);
+    let em = (float() - sym("em")).map(CssCalc::Em);
+    let rem = (float() - sym("rem")).map(CssCalc::Rem);
+    let vw = (float() - sym("vw")).map(CssCalc::Vw);
+    let vh = (float() - sym("vh")).map(CssCalc::Vh);
+    let vmin = (float() - sym("vmin")).map(CssCalc::Vmin);
+    let vmax = (float() - sym("vmax")).map(CssCalc::Vmax);
+    let zero = sym("0").map(|_| CssCalc::Px(0.));
+    let parens = sym("(") * space() * call(calc_sum) - space() - sym(")");
+
+    px | percent | em | rem | vw | vh | vmin | vmax | zero | parens | call(calc)
+}
+
+pub(super) fn sides_of<'a, V: Clone + 'a>(parser: Parser<'a, V>) -> Parser<'a, (V, V, V, V)> {
     list(parser, sym(" ")).convert(|sides| {
-        #[allow(clippy::match_ref_pats)]
         Ok(match &sides[..] {
-            &[a, b, c, d] => (a, b, c, d),
-            &[a, b, c] => (a, b, c, b),
-            &[a, b] => (a, b, a, b),
-            &[a] => (a, a, a, a),
+            [a, b, c, d] => (a.clone(), b.clone(), c.clone(), d.clone()),
+            [a, b, c] => (a.clone(), b.clone(), c.clone(), b.clone()),
+            [a, b] => (a.clone(), b.clone(), a.clone(), b.clone()),
+            [a] => (a.clone(), a.clone(), a.clone(), a.clone()),
             _ => return Err("expected 1-4 values"),
         })
     })
@@ -143,6 +794,15 @@ pub(super) fn flex<'a>() -> Parser<'a, (f32, f32, CssDimension)> {
         .map(|((grow, shrink), basis)| (grow, shrink.unwrap_or(1.), basis.unwrap_or(CssDimension::Auto)))
 }
 
+pub(super) fn gap<'a>() -> Parser<'a, (CssDimension, CssDimension)> {
+    (gap_size() + (sym(" ") * gap_size()).opt()).map(|(row, col)| (row.clone(), col.unwrap_or(row)))
+}
+
+// normal is 0 for flex
+pub(super) fn gap_size<'a>() -> Parser<'a, CssDimension> {
+    sym("normal").map(|_| CssDimension::Auto) | dimension()
+}
+
 pub(super) fn overflow<'a>() -> Parser<'a, (CssOverflow, CssOverflow)> {
     (try_from() + (sym(" ") * try_from()).opt()).map(|(x, y)| (x, y.unwrap_or(x)))
 }
@@ -151,8 +811,274 @@ pub(super) fn outline<'a>() -> Parser<'a, (CssDimension, CssBorderStyle, CssColo
     (dimension() + (sym(" ") * try_from()) + (sym(" ") * color())).map(|((dim, style), color)| (dim, style, color))
 }
 
-pub(super) fn background<'a>() -> Parser<'a, CssColor> {
-    sym("none").map(|_| CssColor::TRANSPARENT) | color()
+pub(super) fn border_width<'a>() -> Parser<'a, CssDimension> {
+    sym("thin").map(|_| CssDimension::Px(1.))
+        | sym("medium").map(|_| CssDimension::Px(3.))
+        | sym("thick").map(|_| CssDimension::Px(5.))
+        | dimension()
+}
+
+// any order, everything is optional (but at least one has to be there)
+pub(super) fn border<'a>() -> Parser<'a, (CssDimension, CssBorderStyle, CssColor)> {
+    enum Part {
+        Width(CssDimension),
+        Style(CssBorderStyle),
+        Color(CssColor),
+    }
+
+    let part = border_width().map(Part::Width) | try_from().map(Part::Style) | color().map(Part::Color);
+
+    list(part, sym(" ")).convert(|parts| {
+        let (mut width, mut style, mut color) = (None, None, None);
+
+        for p in parts {
+            let dup = match p {
+                Part::Width(w) => width.replace(w).is_some(),
+                Part::Style(s) => style.replace(s).is_some(),
+                Part::Color(c) => color.replace(c).is_some(),
+            };
+
+            if dup {
+                return Err("duplicate value");
+            }
+        }
+
+        if width.is_none() && style.is_none() && color.is_none() {
+            return Err("expected width, style or color");
+        }
+
+        Ok((
+            width.unwrap_or(CssDimension::Px(3.)),
+            style.unwrap_or(CssBorderStyle::None),
+            color.unwrap_or(CssColor::CurrentColor),
+        ))
+    })
+}
+
+// normal is "auto", which is then resolved in text_style()
+pub(super) fn line_height<'a>() -> Parser<'a, CssDimension> {
+    sym("normal").map(|_| CssDimension::Auto) | dimension()
+}
+
+// TODO: bolder, lighter (relative to the inherited value)
+pub(super) fn font_weight<'a>() -> Parser<'a, u16> {
+    let number = float().convert(|w| match (1. ..=1000.).contains(&w) {
+        true => Ok(w as u16),
+        false => Err("font-weight out of range"),
+    });
+
+    sym("normal").map(|_| 400) | sym("bold").map(|_| 700) | number
+}
+
+// TODO: font-variant and font-stretch are skipped for now
+pub(super) fn font<'a>() -> Parser<'a, (CssFontStyle, u16, CssDimension, CssDimension, Atom<String>)> {
+    enum Part {
+        Style(CssFontStyle),
+        Weight(u16),
+        Skipped,
+    }
+
+    let part = try_from().map(Part::Style) | font_weight().map(Part::Weight) | sym("small-caps").map(|_| Part::Skipped);
+
+    ((part - sym(" ")).repeat(0..4) + dimension() + (sym("/") * line_height()).opt() - space() + font_family()).map(
+        |(((parts, size), line_height), family)| {
+            let (mut style, mut weight) = (CssFontStyle::Normal, 400);
+
+            for p in parts {
+                match p {
+                    Part::Style(s) => style = s,
+                    Part::Weight(w) => weight = w,
+                    Part::Skipped => {}
+                }
+            }
+
+            (style, weight, size, line_height.unwrap_or(CssDimension::Auto), family)
+        },
+    )
+}
+
+// normal is "auto", which is then resolved to 0 in text_style()
+pub(super) fn spacing<'a>() -> Parser<'a, CssDimension> {
+    sym("normal").map(|_| CssDimension::Auto) | dimension()
+}
+
+pub(super) fn text_decoration_line<'a>() -> Parser<'a, CssTextDecorationLine> {
+    list(decoration_line(), sym(" ")).convert(|lines| decoration_lines(&lines))
+}
+
+// any order, everything is optional (but at least one has to be there)
+pub(super) fn text_decoration<'a>() -> Parser<'a, (CssTextDecorationLine, CssTextDecorationStyle, CssColor)> {
+    enum Part<'a> {
+        Line(&'a str),
+        Style(CssTextDecorationStyle),
+        Color(CssColor),
+    }
+
+    let part = decoration_line().map(Part::Line) | try_from().map(Part::Style) | color().map(Part::Color);
+
+    list(part, sym(" ")).convert(|parts| {
+        let (mut lines, mut style, mut color) = (Vec::new(), None, None);
+
+        for p in parts {
+            let dup = match p {
+                Part::Line(l) => {
+                    lines.push(l);
+                    false
+                }
+                Part::Style(s) => style.replace(s).is_some(),
+                Part::Color(c) => color.replace(c).is_some(),
+            };
+
+            if dup {
+                return Err("duplicate value");
+            }
+        }
+
+        Ok((
+            decoration_lines(&lines)?,
+            style.unwrap_or(CssTextDecorationStyle::Solid),
+            color.unwrap_or(CssColor::CurrentColor),
+        ))
+    })
+}
+
+fn decoration_line<'a>() -> Parser<'a, &'a str> {
+    sym("none") | sym("underline") | sym("overline") | sym("line-through")
+}
+
+// none can't be combined with anything else
+fn decoration_lines(lines: &[&str]) -> Result<CssTextDecorationLine, &'static str> {
+    let mut res = CssTextDecorationLine::default();
+
+    for l in lines {
+        let invalid = match *l {
+            "none" => lines.len() > 1,
+            "underline" => std::mem::replace(&mut res.underline, true),
+            "overline" => std::mem::replace(&mut res.overline, true),
+            _ => std::mem::replace(&mut res.line_through, true),
+        };
+
+        if invalid {
+            return Err("invalid text-decoration-line");
+        }
+    }
+
+    Ok(res)
+}
+
+// comma-separated layers of images, color can only be in the final one
+pub(super) fn background<'a>() -> Parser<'a, (CssColor, CssList<CssImage>)> {
+    enum Part {
+        Image(CssImage),
+        Color(CssColor),
+    }
+
+    let part = image().map(Part::Image) | color().map(Part::Color);
+    let layer = (space() * part).repeat(1..);
+
+    list(layer, sym(",")).convert(|layers| {
+        let (mut color, mut images) = (None, Vec::new());
+
+        for (i, parts) in layers.iter().enumerate() {
+            let mut image = None;
+
+            for p in parts {
+                let dup = match p {
+                    Part::Image(img) => image.replace(img.clone()).is_some(),
+                    Part::Color(_) if i != layers.len() - 1 => return Err("color in non-final layer"),
+                    Part::Color(c) => color.replace(*c).is_some(),
+                };
+
+                if dup {
+                    return Err("duplicate value");
+                }
+            }
+
+            images.push(image.unwrap_or(CssImage::None));
+        }
+
+        Ok((color.unwrap_or(CssColor::TRANSPARENT), CssList(images)))
+    })
+}
+
+pub(super) fn image<'a>() -> Parser<'a, CssImage> {
+    sym("none").map(|_| CssImage::None) | gradient().map(|g| CssImage::Gradient(Box::new(g)))
+}
+
+fn gradient<'a>() -> Parser<'a, CssGradient> {
+    let func = |name| sym(name) * sym("(");
+    let comma = || sym(",");
+    let center = || CssOrigin(CssDimension::Percent(50.), CssDimension::Percent(50.));
+    let at = || sym("at") * space() * origin();
+
+    // to [top|bottom] [left|right] (in any order)
+    let x = || sym("left").map(|_| false) | sym("right").map(|_| true);
+    let y = || sym("top").map(|_| false) | sym("bottom").map(|_| true);
+    let to_y = (y() + (space() * x()).opt()).map(|(bottom, right)| match right {
+        Some(right) => CssGradientDirection::Corner(right, bottom),
+        None => CssGradientDirection::Angle(if bottom { 180. } else { 0. }),
+    });
+    let to_x = (x() + (space() * y()).opt()).map(|(right, bottom)| match bottom {
+        Some(bottom) => CssGradientDirection::Corner(right, bottom),
+        None => CssGradientDirection::Angle(if right { 90. } else { 270. }),
+    });
+    let direction = angle().map(CssGradientDirection::Angle) | sym("to") * space() * (to_y | to_x);
+    let linear = (func("linear-gradient") * (direction - comma()).opt() + color_stops(dimension()) - sym(")")).map(
+        |(dir, stops)| CssGradient {
+            shape: CssGradientShape::Linear(dir.unwrap_or(CssGradientDirection::Angle(180.))),
+            stops,
+        },
+    );
+
+    // [circle|ellipse] [extent] (in any order)
+    let circle = || sym("circle").map(|_| true) | sym("ellipse").map(|_| false);
+    let extent = || try_from::<CssRadialExtent>();
+    let ending = (circle() + (space() * extent()).opt()).map(|(c, e)| (Some(c), e))
+        | (extent() + (space() * circle()).opt()).map(|(e, c)| (c, Some(e)));
+    let radial_prelude = (ending.opt() - space() + at().opt() - comma()).convert(|(ending, at)| match ending {
+        None if at.is_none() => Err("expected shape, extent or position"),
+        _ => Ok((ending.unwrap_or((None, None)), at)),
+    });
+    let radial = (func("radial-gradient") * radial_prelude.opt() + color_stops(dimension()) - sym(")")).map(
+        move |(prelude, stops)| {
+            let ((c, e), at) = prelude.unwrap_or(((None, None), None));
+            let shape = CssGradientShape::Radial(
+                c.unwrap_or(false),
+                e.unwrap_or(CssRadialExtent::FarthestCorner),
+                at.unwrap_or_else(center),
+            );
+
+            CssGradient { shape, stops }
+        },
+    );
+
+    let from = sym("from") * space() * angle();
+    let conic_prelude = (from.opt() - space() + at().opt() - comma()).convert(|(from, at)| match (from, at) {
+        (None, None) => Err("expected from or position"),
+        (from, at) => Ok((from, at)),
+    });
+    // angles or percentages of the full turn (not lengths)
+    let conic_pos = angle().map(|a| CssDimension::Percent(a / 3.6)) | (float() - sym("%")).map(CssDimension::Percent);
+    let conic = (func("conic-gradient") * conic_prelude.opt() + color_stops(conic_pos) - sym(")")).map(
+        move |(prelude, stops)| {
+            let (from, at) = prelude.unwrap_or((None, None));
+            let shape = CssGradientShape::Conic(from.unwrap_or(0.), at.unwrap_or_else(center));
+
+            CssGradient { shape, stops }
+        },
+    );
+
+    linear | radial | conic
+}
+
+// at least two
+fn color_stops<'a>(pos: Parser<'a, CssDimension>) -> Parser<'a, Vec<CssColorStop>> {
+    let stop = (color() - space() + pos.opt()).map(|(color, pos)| CssColorStop(color, pos));
+
+    list(stop, sym(",")).convert(|stops| match stops.len() {
+        0 | 1 => Err("expected at least two color stops"),
+        _ => Ok(stops),
+    })
 }
 
 pub(super) fn color<'a>() -> Parser<'a, CssColor> {
@@ -172,58 +1098,408 @@ pub(super) fn color<'a>() -> Parser<'a, CssColor> {
                         num = num << 8 | 0xFF;
                     }
 
-                    CssColor {
-                        r: ((num >> 24) & 0xFF) as u8,
-                        g: ((num >> 16) & 0xFF) as u8,
-                        b: ((num >> 8) & 0xFF) as u8,
-                        a: (num & 0xFF) as u8,
-                    }
+                    CssColor::from_rgba8(
+                        ((num >> 24) & 0xFF) as u8,
+                        ((num >> 16) & 0xFF) as u8,
+                        ((num >> 8) & 0xFF) as u8,
+                        (num & 0xFF) as u8,
+                    )
                 }
 
-                4 | 3 => CssColor {
-                    r: hex_val(hex[0]) * 17,
-                    g: hex_val(hex[1]) * 17,
-                    b: hex_val(hex[2]) * 17,
-                    a: hex.get(3).map(|&v| hex_val(v) * 17).unwrap_or(255),
-                },
+                4 | 3 => CssColor::from_rgba8(
+                    hex_val(hex[0]) * 17,
+                    hex_val(hex[1]) * 17,
+                    hex_val(hex[2]) * 17,
+                    hex.get(3).map(|&v| hex_val(v) * 17).unwrap_or(255),
+                ),
 
                 _ => return Err("invalid hex color"),
             })
         });
 
-    let rgb = sym("rgb")
+    let rgb = (keyword("rgb") | keyword("rgba"))
         * sym("(")
-        * (u8() - sym(",") + u8() - sym(",") + u8()).map(|((r, g), b)| CssColor::from_rgb8(r, g, b))
+        * color_args(rgb_channel, rgb_channel, rgb_channel)
+            .map(|(r, g, b, a)| CssColor::from_rgba8(r as u8, g as u8, b as u8, a))
         - sym(")");
 
-    let rgba = sym("rgba")
-        * sym("(")
-        * (u8() - sym(",") + u8() - sym(",") + u8() - sym(",") + float())
-            .map(|(((r, g), b), a)| CssColor::from_rgba8(r, g, b, (255. * a) as _))
-        - sym(")");
+    // s, l, w, b can be also plain numbers (0..100)
+    let hsl = (keyword("hsl") | keyword("hsla"))
+        * sym("(")
+        * color_args(hue, percentage_or_number, percentage_or_number)
+            .map(|(h, s, l, a)| CssColor::from_hsla(h, s / 100., l / 100., a))
+        - sym(")");
+    let hwb = keyword("hwb")
+        * sym("(")
+        * color_args(hue, percentage_or_number, percentage_or_number)
+            .map(|(h, w, b, a)| CssColor::from_hwba(h, w / 100., b / 100., a))
+        - sym(")");
+
+    let named_color = ident().convert(|name| {
+        let name = name.to_ascii_lowercase();
+
+        CssColor::NAMED_COLORS.get(&*name).copied().ok_or("unknown named color")
+    });
+    let current_color = keyword("currentcolor").map(|_| CssColor::CurrentColor);
+
+    hex_color | rgb | hsl | hwb | current_color | named_color
+}
+
+// legacy (commas) or modern syntax (spaces, slash before alpha)
+fn color_args<'a>(
+    a: fn() -> Parser<'a, f32>,
+    b: fn() -> Parser<'a, f32>,
+    c: fn() -> Parser<'a, f32>,
+) -> Parser<'a, (f32, f32, f32, u8)> {
+    let comma = || space() * sym(",") - space();
+    let alpha = || (percentage().map(|v| v / 100.) | float()).map(|a| (a.clamp(0., 1.) * 255.).round() as u8);
+
+    let legacy = a() - comma() + b() - comma() + c() + (comma() * alpha()).opt();
+    let modern = a() - space() + b() - space() + c() + (space() * sym("/") * space() * alpha()).opt();
+
+    (legacy | modern).map(|(((a, b), c), alpha)| (a, b, c, alpha.unwrap_or(255)))
+}
+
+// 0..255 (or %)
+fn rgb_channel<'a>() -> Parser<'a, f32> {
+    (percentage().map(|v| v * 2.55) | float()).map(|v| v.clamp(0., 255.).round())
+}
+
+// degrees
+fn hue<'a>() -> Parser<'a, f32> {
+    let unit = sym("deg").map(|_| 1.)
+        | sym("rad").map(|_| 180. / std::f32::consts::PI)
+        | sym("grad").map(|_| 0.9)
+        | sym("turn").map(|_| 360.);
+
+    (float() + unit.opt()).map(|(v, unit)| (v * unit.unwrap_or(1.)).rem_euclid(360.))
+}
+
+fn percentage<'a>() -> Parser<'a, f32> {
+    float() - sym("%")
+}
+
+fn percentage_or_number<'a>() -> Parser<'a, f32> {
+    percentage() | float()
+}
+
+// case-insensitive
+fn keyword<'a>(name: &'static str) -> Parser<'a, &'a str> {
+    is_a(move |t: &str| t.eq_ignore_ascii_case(name))
+}
+
+pub(super) fn font_family<'a>() -> Parser<'a, Atom<String>> {
+    // comma-separated but keep it as Atom<String> because that is easy to
+    // map/cache to FontQuery and I'd like to keep CSS unaware of fonts
+    (family_name() + (sym(",") * family_name()).repeat(0..)).map(|(head, tail)| {
+        let mut families = vec![head];
+        families.extend(tail);
+
+        Atom::from(&*families.join(", "))
+    })
+}
+
+// quoted or sequence of idents
+fn family_name<'a>() -> Parser<'a, String> {
+    let quoted = is_a(|t: &str| t.starts_with('"') || t.starts_with('\'')).map(unquote);
+    let idents = (ident() + (sym(" ") * ident()).repeat(0..)).map(|(head, tail)| {
+        let mut res = head.to_owned();
+
+        for t in tail {
+            res.push(' ');
+            res.push_str(t);
+        }
+
+        res
+    });
+
+    quoted | idents
+}
+
+pub(super) fn transform<'a>() -> Parser<'a, CssTransform> {
+    let none = sym("none").map(|_| CssTransform(Vec::new()));
+
+    none | (space() * transform_fn()).repeat(1..).map(CssTransform)
+}
+
+fn transform_fn<'a>() -> Parser<'a, CssTransformFn> {
+    use CssTransformFn::*;
+
+    let func = |name| sym(name) * sym("(");
+    let comma = || sym(",");
+    let zero = || CssDimension::ZERO;
+
+    let translate = (func("translate") * (dimension() + (comma() * dimension()).opt()) - sym(")"))
+        .map(move |(x, y)| Translate(x, y.unwrap_or_else(zero)));
+    let translate_x = (func("translateX") * dimension() - sym(")")).map(move |x| Translate(x, zero()));
+    let translate_y = (func("translateY") * dimension() - sym(")")).map(move |y| Translate(zero(), y));
+
+    let scale =
+        (func("scale") * (float() + (comma() * float()).opt()) - sym(")")).map(|(x, y)| Scale(x, y.unwrap_or(x)));
+    let scale_x = (func("scaleX") * float() - sym(")")).map(|x| Scale(x, 1.));
+    let scale_y = (func("scaleY") * float() - sym(")")).map(|y| Scale(1., y));
+
+    let rotate = (func("rotate") * angle() - sym(")")).map(Rotate);
+
+    let skew = (func("skew") * (angle() + (comma() * angle()).opt()) - sym(")")).map(|(x, y)| Skew(x, y.unwrap_or(0.)));
+    let skew_x = (func("skewX") * angle() - sym(")")).map(|x| Skew(x, 0.));
+    let skew_y = (func("skewY") * angle() - sym(")")).map(|y| Skew(0., y));
+
+    let matrix = func("matrix")
+        * (float() - comma() + float() - comma() + float() - comma() + float() - comma() + float() - comma() + float())
+            .map(|(((((a, b), c), d), e), f)| Matrix([a, b, c, d, e, f]))
+        - sym(")");
+
+    translate | translate_x | translate_y | scale | scale_x | scale_y | rotate | skew | skew_x | skew_y | matrix
+}
+
+// in degrees
+fn angle<'a>() -> Parser<'a, f32> {
+    let deg = float() - sym("deg");
+    let rad = (float() - sym("rad")).map(f32::to_degrees);
+    let turn = (float() - sym("turn")).map(|v| v * 360.);
+    let grad = (float() - sym("grad")).map(|v| v * 0.9);
+    let zero = sym("0").map(|_| 0.);
+
+    deg | rad | turn | grad | zero
+}
+
+// [x] [y] (or y x if it's clear from keywords)
+pub(super) fn origin<'a>() -> Parser<'a, CssOrigin> {
+    let pct = |v| move |_| CssDimension::Percent(v);
+    let center = || sym("center").map(pct(50.));
+    let x_kw = || sym("left").map(pct(0.)) | sym("right").map(pct(100.));
+    let y_kw = || sym("top").map(pct(0.)) | sym("bottom").map(pct(100.));
+    let x = x_kw() | center() | dimension();
+    let y = y_kw() | center() | dimension();
+
+    let yx = (y_kw() + (space() * (x_kw() | center())).opt())
+        .map(|(y, x)| CssOrigin(x.unwrap_or(CssDimension::Percent(50.)), y));
+    let xy = (x + (space() * y).opt()).map(|(x, y)| CssOrigin(x, y.unwrap_or(CssDimension::Percent(50.))));
+
+    yx | xy
+}
+
+pub(super) fn box_shadow<'a>() -> Parser<'a, Box<CssBoxShadow>> {
+    fail("TODO: parse box-shadow")
+}
+
+// non-empty, comma-separated
+pub(super) fn css_list<'a, T: 'a>(parser: Parser<'a, T>) -> Parser<'a, CssList<T>> {
+    list(parser, sym(",")).convert(|items| match items.is_empty() {
+        true => Err("expected at least one item"),
+        false => Ok(CssList(items)),
+    })
+}
+
+pub(super) fn transition_property<'a>() -> Parser<'a, CssList<Atom<String>>> {
+    css_list(ident().map(Atom::from))
+}
+
+pub(super) fn time<'a>() -> Parser<'a, CssTime> {
+    let s = (float() - sym("s")).map(CssTime);
+    let ms = (float() - sym("ms")).map(|v| CssTime(v / 1000.));
+
+    s | ms
+}
+
+pub(super) fn timing_function<'a>() -> Parser<'a, CssTimingFunction> {
+    let comma = || sym(",");
+    let keyword = ident().convert(|name| {
+        Ok(match name {
+            "linear" => CssTimingFunction::Linear,
+            "ease" => CssTimingFunction::Ease,
+            "ease-in" => CssTimingFunction::EaseIn,
+            "ease-out" => CssTimingFunction::EaseOut,
+            "ease-in-out" => CssTimingFunction::EaseInOut,
+            "step-start" => CssTimingFunction::Steps(1, CssStepPosition::JumpStart),
+            "step-end" => CssTimingFunction::Steps(1, CssStepPosition::JumpEnd),
+            _ => return Err("unknown timing function"),
+        })
+    });
+    let cubic_bezier = sym("cubic-bezier")
+        * sym("(")
+        * (float() - comma() + float() - comma() + float() - comma() + float()).convert(
+            |(((x1, y1), x2), y2)| match (0. ..=1.).contains(&x1) && (0. ..=1.).contains(&x2) {
+                true => Ok(CssTimingFunction::CubicBezier(x1, y1, x2, y2)),
+                false => Err("x out of range"),
+            },
+        )
+        - sym(")");
+    let position =
+        sym("start").map(|_| CssStepPosition::JumpStart) | sym("end").map(|_| CssStepPosition::JumpEnd) | try_from();
+    let steps = sym("steps")
+        * sym("(")
+        * (any().convert(str::parse::<u32>) + (comma() * position).opt()).convert(|(n, pos)| {
+            let pos = pos.unwrap_or(CssStepPosition::JumpEnd);
+
+            match n > (pos == CssStepPosition::JumpNone) as u32 {
+                true => Ok(CssTimingFunction::Steps(n, pos)),
+                false => Err("invalid number of steps"),
+            }
+        })
+        - sym(")");
+
+    cubic_bezier | steps | keyword
+}
+
+// (property, duration, timing-function, delay)
+type TransitionLists = (
+    CssList<Atom<String>>,
+    CssList<CssTime>,
+    CssList<CssTimingFunction>,
+    CssList<CssTime>,
+);
+
+pub(super) fn transition<'a>() -> Parser<'a, TransitionLists> {
+    enum Part<'a> {
+        Time(CssTime),
+        Timing(CssTimingFunction),
+        Prop(&'a str),
+    }
+
+    let part = time().map(Part::Time) | timing_function().map(Part::Timing) | ident().map(Part::Prop);
+    let single = list(part, space()).convert(|parts| {
+        let (mut prop, mut times, mut timing) = (None, Vec::new(), None);
+
+        for p in parts {
+            match p {
+                Part::Time(t) if times.len() < 2 => times.push(t),
+                Part::Timing(f) if timing.is_none() => timing = Some(f),
+                Part::Prop(p) if prop.is_none() => prop = Some(p),
+                _ => return Err("invalid transition"),
+            }
+        }
+
+        Ok((
+            Atom::from(prop.unwrap_or("all")),
+            times.get(0).copied().unwrap_or(CssTime(0.)),
+            timing.unwrap_or(CssTimingFunction::Ease),
+            times.get(1).copied().unwrap_or(CssTime(0.)),
+        ))
+    });
+
+    css_list(single).map(|CssList(transitions)| {
+        let mut res = (CssList(vec![]), CssList(vec![]), CssList(vec![]), CssList(vec![]));
+
+        for (prop, duration, timing, delay) in transitions {
+            res.0 .0.push(prop);
+            res.1 .0.push(duration);
+            res.2 .0.push(timing);
+            res.3 .0.push(delay);
+        }
+
+        res
+    })
+}
+
+pub(super) fn animation_name<'a>() -> Parser<'a, Atom<String>> {
+    let quoted = is_a(|t: &str| t.starts_with('"') || t.starts_with('\'')).map(|t| Atom::from(&*unquote(t)));
+
+    quoted | ident().map(Atom::from)
+}
 
-    let named_color = ident().convert(|name| CssColor::NAMED_COLORS.get(name).copied().ok_or("unknown named color"));
+pub(super) fn iteration_count<'a>() -> Parser<'a, CssIterationCount> {
+    let infinite = sym("infinite").map(|_| CssIterationCount(f32::INFINITY));
+    let count = float().convert(|n| match n >= 0. {
+        true => Ok(CssIterationCount(n)),
+        false => Err("negative iteration count"),
+    });
 
-    hex_color | rgb | rgba | named_color
+    infinite | count
 }
 
-pub(super) fn font_family<'a>() -> Parser<'a, Atom<String>> {
-    // TODO: multiple, strings
-    //       but keep it as Atom<String> because that is easy to
-    //       map/cache to FontQuery and I'd like to keep CSS unaware of fonts
-    is_a(|t: &str| alphanum_dash(t.as_bytes()[0])).map(Atom::from)
+pub(super) fn z_index<'a>() -> Parser<'a, CssZIndex> {
+    sym("auto").map(|_| CssZIndex::Auto) | any().convert(str::parse).map(CssZIndex::Index)
 }
 
-pub(super) fn box_shadow<'a>() -> Parser<'a, Box<CssBoxShadow>> {
-    fail("TODO: parse box-shadow")
+// (name, duration, timing-function, delay, iteration-count, direction, fill-mode, play-state)
+type AnimationLists = (
+    CssList<Atom<String>>,
+    CssList<CssTime>,
+    CssList<CssTimingFunction>,
+    CssList<CssTime>,
+    CssList<CssIterationCount>,
+    CssList<CssAnimationDirection>,
+    CssList<CssAnimationFillMode>,
+    CssList<CssAnimationPlayState>,
+);
+
+pub(super) fn animation<'a>() -> Parser<'a, AnimationLists> {
+    enum Part {
+        Time(CssTime),
+        Timing(CssTimingFunction),
+        Count(CssIterationCount),
+        Direction(CssAnimationDirection),
+        FillMode(CssAnimationFillMode),
+        PlayState(CssAnimationPlayState),
+        Name(Atom<String>),
+    }
+
+    // keywords go first, anything else is the name
+    let part = time().map(Part::Time)
+        | timing_function().map(Part::Timing)
+        | iteration_count().map(Part::Count)
+        | try_from().map(Part::Direction)
+        | try_from().map(Part::FillMode)
+        | try_from().map(Part::PlayState)
+        | animation_name().map(Part::Name);
+
+    let single = list(part, space()).convert(|parts| {
+        let mut times = Vec::new();
+        let (mut name, mut timing, mut count, mut direction, mut fill_mode, mut play_state) =
+            (None, None, None, None, None, None);
+
+        for p in parts {
+            match p {
+                Part::Time(t) if times.len() < 2 => times.push(t),
+                Part::Timing(f) if timing.is_none() => timing = Some(f),
+                Part::Count(c) if count.is_none() => count = Some(c),
+                Part::Direction(d) if direction.is_none() => direction = Some(d),
+                Part::FillMode(f) if fill_mode.is_none() => fill_mode = Some(f),
+                Part::PlayState(s) if play_state.is_none() => play_state = Some(s),
+                Part::Name(n) if name.is_none() => name = Some(n),
+                _ => return Err("invalid animation"),
+            }
+        }
+
+        Ok((
+            name.unwrap_or_else(|| Atom::from("none")),
+            times.get(0).copied().unwrap_or(CssTime(0.)),
+            timing.unwrap_or(CssTimingFunction::Ease),
+            times.get(1).copied().unwrap_or(CssTime(0.)),
+            count.unwrap_or(CssIterationCount(1.)),
+            direction.unwrap_or(CssAnimationDirection::Normal),
+            fill_mode.unwrap_or(CssAnimationFillMode::None),
+            play_state.unwrap_or(CssAnimationPlayState::Running),
+        ))
+    });
+
+    css_list(single).map(|CssList(animations)| {
+        let mut res: AnimationLists = Default::default();
+
+        for a in animations {
+            res.0 .0.push(a.0);
+            res.1 .0.push(a.1);
+            res.2 .0.push(a.2);
+            res.3 .0.push(a.3);
+            res.4 .0.push(a.4);
+            res.5 .0.push(a.5);
+            res.6 .0.push(a.6);
+            res.7 .0.push(a.7);
+        }
+
+        res
+    })
 }
 
 pub(super) fn float<'a>() -> Parser<'a, f32> {
     any().convert(str::parse)
 }
 
-fn u8<'a>() -> Parser<'a, u8> {
-    any().convert(str::parse)
+// optional white-space (only relevant where tokenizer keeps it)
+fn space<'a>() -> Parser<'a, ()> {
+    sym(" ").discard().repeat(0..).discard()
 }
 
 fn ident<'a>() -> Parser<'a, &'a str> {
@@ -279,14 +1555,14 @@ pub(super) fn tokenize(input: &[u8]) -> Vec<Token> {
             }
 
             if let Some(&next) = tokens.get(i + 1) {
-                if !(alphanum_dash(next.as_bytes()[0]) || next == "." || next == "#" || next == "*") {
+                if !(alphanum_dash(next.as_bytes()[0]) || next == "." || next == "#" || next == "*" || next == "&") {
                     continue;
                 }
             }
         }
 
         res.push(t);
-        keep_space = alphanum_dash(t.as_bytes()[0]) || t == "*" || t == "]"
+        keep_space = alphanum_dash(t.as_bytes()[0]) || t == "*" || t == "]" || t == "&"
     }
 
     res
@@ -329,6 +1605,7 @@ mod tests {
         );
 
         assert_eq!(tokenize(b"/**/ a /**/ b {}"), vec!["a", " ", "b", "{", "}"]);
+        assert_eq!(tokenize(b"& .a, b &"), vec!["&", " ", ".", "a", ",", "b", " ", "&"]);
 
         let ua = include_bytes!("../../resources/ua.css");
         let _tokens = tokenize(ua);
@@ -336,15 +1613,37 @@ mod tests {
         // println!("{:#?}", _tokens);
     }
 
+    #[test]
+    fn warnings() {
+        let input = "a {}\nb {}";
+
+        // normalized space is not part of the input
+        let collect = CollectWarnings::start();
+        warn(" ", None, &[" ", &input[5..6]], CssWarningReason::InvalidRule);
+        let warnings = collect.finish(input);
+        assert_eq!((warnings[0].line, warnings[0].column), (2, 1));
+
+        // nothing is collected after panic
+        assert!(std::panic::catch_unwind(|| {
+            let _collect = CollectWarnings::start();
+            panic!()
+        })
+        .is_err());
+        assert!(WARNINGS.with(|w| w.borrow().is_none()));
+    }
+
     #[test]
     fn basic() {
         let sheet = StyleSheet::from("div { color: #fff }");
 
         assert_eq!(
             sheet.rules[0],
-            Rule::new(Selector::from("div"), Style::from("color: #fff"))
+            CssRule::Style(Rule::new(Selector::from("div"), Style::from("color: #fff")))
         );
-        assert_eq!(sheet.rules[0].style().css_text(), "color: rgba(255, 255, 255, 255);");
+
+        if let CssRule::Style(r) = &sheet.rules[0] {
+            assert_eq!(r.style().css_text(), "color: rgba(255, 255, 255, 255);");
+        }
 
         // white-space
         assert_eq!(StyleSheet::from(" *{}").rules.len(), 1);
@@ -352,8 +1651,293 @@ mod tests {
 
         // forgiving/future-compatibility
         assert_eq!(StyleSheet::from(":root {} a { v: 0 }").rules.len(), 2);
-        assert_eq!(StyleSheet::from("a {} @media { a { v: 0 } } b {}").rules.len(), 2);
-        assert_eq!(StyleSheet::from("@media { a { v: 0 } } a {} b {}").rules.len(), 2);
+        assert_eq!(StyleSheet::from("a {} @media { a { v: 0 } } b {}").rules.len(), 3);
+        assert_eq!(StyleSheet::from("@media { a { v: 0 } } a {} b {}").rules.len(), 3);
+        assert_eq!(StyleSheet::from("@media { a { v: 0 } x } a {} b {}").rules.len(), 2);
+        assert_eq!(StyleSheet::from("@font-face { a: b } a {} b {}").rules.len(), 2);
+    }
+
+    #[test]
+    fn media() {
+        let sheet = StyleSheet::from(
+            "a { color: #fff }
+             @media screen and (max-width: 600px) { a { color: #000 } b {} }
+             @media print { c {} }",
+        );
+
+        assert_eq!(sheet.rules.len(), 3);
+
+        match &sheet.rules[1] {
+            CssRule::Media(MediaRule { media, rules }) => {
+                assert_eq!(media, &MediaQueryList::from("screen and (max-width: 600px)"));
+                assert_eq!(rules.len(), 2);
+            }
+            _ => panic!("expected @media"),
+        }
+
+        // nested
+        let sheet = StyleSheet::from("@media screen { @media (min-width: 100px) { a {} } b {} }");
+
+        match &sheet.rules[0] {
+            CssRule::Media(MediaRule { rules, .. }) => assert!(matches!(rules[0], CssRule::Media(_))),
+            _ => panic!("expected @media"),
+        }
+    }
+
+    #[test]
+    fn nesting() {
+        let css = |sheet: &StyleSheet| sheet.rules.iter().map(CssRule::css_text).collect::<Vec<_>>();
+
+        let sheet = StyleSheet::from(
+            ".card {
+                 color: #000;
+                 & .title { color: #f00 }
+                 &.active, &:hover { opacity: 1 }
+                 > p { margin: 0; span { color: #00f } }
+                 .a &, b { padding: 0 }
+                 opacity: 0.5;
+             }
+             a, b { c { display: block } }",
+        );
+
+        assert_eq!(
+            css(&sheet),
+            vec![
+                ".card { color: rgba(0, 0, 0, 255);opacity: 0.5; }",
+                ".card .title { color: rgba(255, 0, 0, 255); }",
+                ".card.active, .card:hover { opacity: 1; }",
+                ".card > p { margin: 0px; }",
+                ".card > p span { color: rgba(0, 0, 255, 255); }",
+                ".a .card, .card b { padding: 0px; }",
+                "a c, b c { display: block; }",
+            ]
+        );
+
+        let sheet = StyleSheet::from(
+            "a {
+                 color: #000;
+                 @media (min-width: 100px) { color: #fff; b { opacity: 1 } }
+             }",
+        );
+
+        assert_eq!(sheet.rules.len(), 2);
+        match &sheet.rules[1] {
+            CssRule::Media(m) => assert_eq!(
+                m.rules.iter().map(CssRule::css_text).collect::<Vec<_>>(),
+                vec!["a { color: rgba(255, 255, 255, 255); }", "a b { opacity: 1; }"]
+            ),
+            _ => panic!("expected @media"),
+        }
+
+        // invalid nested rule is skipped
+        let sheet = StyleSheet::from("a { color: #000; ! {} b { opacity: 1 } } c {}");
+        assert_eq!(css(&sheet).len(), 3);
+    }
+
+    #[test]
+    fn import() {
+        let sheet = StyleSheet::from(
+            "@import 'a.css';
+             @import url(b.css) screen and (min-width: 100px);
+             @import url(\"dir/c.css\");
+             a {}",
+        );
+
+        let media = MediaContext::new(200., 100.);
+        let hrefs: Vec<_> = sheet.imports(&media).iter().map(|i| &*i.href).collect();
+        assert_eq!(hrefs, vec!["a.css", "b.css", "dir/c.css"]);
+        assert_eq!(
+            sheet.rules[1].css_text(),
+            "@import url(\"b.css\") screen and (min-width: 100px);"
+        );
+        assert_eq!(sheet.active_rules(&media).len(), 1);
+
+        let media = MediaContext::new(50., 100.);
+        assert_eq!(sheet.imports(&media).len(), 2);
+
+        // has to be before other rules
+        let sheet = StyleSheet::from("a {} @import 'a.css';");
+        assert_eq!(sheet.imports(&media).len(), 0);
+    }
+
+    #[test]
+    fn supports() {
+        let supported = |cond: &str| {
+            let sheet = StyleSheet::from(&*format!("@supports {} {{ a {{}} }}", cond));
+
+            match &sheet.rules[..] {
+                [CssRule::Supports(s)] => s.supported,
+                _ => panic!("invalid @supports {}", cond),
+            }
+        };
+
+        assert!(supported("(display: flex)"));
+        assert!(supported("(margin: 0 auto)"));
+        assert!(supported("(color: rgb(0, 0, 0))"));
+        assert!(!supported("(display: grid)"));
+        assert!(!supported("(foo: bar)"));
+        assert!(!supported("selector(a > b)"));
+
+        assert!(supported("not (display: grid)"));
+        assert!(supported("(display: flex) and (not (display: grid))"));
+        assert!(!supported("(display: flex) and (display: grid)"));
+        assert!(supported("(display: grid) or (display: flex)"));
+        assert!(supported("((display: grid) or (display: flex)) and (opacity: 1)"));
+
+        // invalid conditions are skipped
+        assert_eq!(StyleSheet::from("@supports display: flex { a {} } b {}").rules.len(), 1);
+        assert_eq!(
+            StyleSheet::from("@supports (a: b) and (c: d) or (e: f) { a {} }")
+                .rules
+                .len(),
+            0
+        );
+
+        let sheet = StyleSheet::from("@supports not (display: grid) { a {} } @supports (display: grid) { b {} }");
+        let media = MediaContext::new(100., 100.);
+        assert_eq!(sheet.active_rules(&media).len(), 1);
+        assert_eq!(sheet.rules[0].css_text(), "@supports not (display: grid) {\n  a { }\n}");
+    }
+
+    #[test]
+    fn font_face() {
+        let sheet = StyleSheet::from(
+            "@font-face {
+                font-family: 'My Font';
+                src: local(Foo Bold), url(fonts/my-font.ttf) format('truetype'), url(\"a b.otf\");
+                font-weight: 100 900;
+                font-style: italic;
+                unicode-range: U+0-7F, u+0100-024f, U+4??;
+             }
+             @font-face { font-family: Foo; src: url(foo.ttf) }
+             @font-face { font-family: Bar }
+             a {}",
+        );
+
+        assert_eq!(sheet.rules.len(), 3);
+        assert_eq!(
+            sheet.rules[0],
+            CssRule::FontFace(FontFaceRule {
+                family: "My Font".to_owned(),
+                src: vec![
+                    FontFaceSource::Local("Foo Bold".to_owned()),
+                    FontFaceSource::Url("fonts/my-font.ttf".to_owned()),
+                    FontFaceSource::Url("a b.otf".to_owned())
+                ],
+                weight: (100, 900),
+                italic: true,
+                unicode_range: vec![(0, 0x7f), (0x100, 0x24f), (0x400, 0x4ff)]
+            })
+        );
+        assert_eq!(
+            sheet.rules[1],
+            CssRule::FontFace(FontFaceRule {
+                family: "Foo".to_owned(),
+                src: vec![FontFaceSource::Url("foo.ttf".to_owned())],
+                weight: (400, 400),
+                italic: false,
+                unicode_range: vec![]
+            })
+        );
+    }
+
+    #[test]
+    fn parse_font_family() {
+        let family = |s| match Style::from(s).props.get(0) {
+            Some(StyleProp::FontFamily(f)) => f.to_string(),
+            _ => String::new(),
+        };
+
+        assert_eq!(family("font-family: serif"), "serif");
+        assert_eq!(family("font-family: Open Sans"), "Open Sans");
+        assert_eq!(
+            family("font-family: 'Open Sans', \"Foo\", sans-serif"),
+            "Open Sans, Foo, sans-serif"
+        );
+    }
+
+    #[test]
+    fn parse_media_query() {
+        use std::ops::Bound::*;
+        use MediaFeature::*;
+
+        let q = |s: &str| {
+            let tokens = tokenize(s.as_bytes());
+            let res = media_query_list(&tokens).0;
+            res
+        };
+        let query = |not, media_type, features| MediaQuery {
+            not,
+            media_type,
+            features,
+        };
+
+        assert_eq!(q(""), vec![]);
+        assert_eq!(q("screen"), vec![query(false, MediaType::Screen, vec![])]);
+        assert_eq!(q("only screen"), vec![query(false, MediaType::Screen, vec![])]);
+        assert_eq!(q("not print"), vec![query(true, MediaType::Print, vec![])]);
+        assert_eq!(
+            q("all, print"),
+            vec![
+                query(false, MediaType::All, vec![]),
+                query(false, MediaType::Print, vec![])
+            ]
+        );
+        assert_eq!(
+            q("screen and (min-width: 10px) and (max-height: 2em)"),
+            vec![query(
+                false,
+                MediaType::Screen,
+                vec![Width((Included(10.), Unbounded)), Height((Unbounded, Included(32.)))]
+            )]
+        );
+        assert_eq!(
+            q("(width >= 10px)"),
+            vec![query(false, MediaType::All, vec![Width((Included(10.), Unbounded))])]
+        );
+        assert_eq!(
+            q("(10px < width <= 20px)"),
+            vec![query(
+                false,
+                MediaType::All,
+                vec![Width((Excluded(10.), Included(20.)))]
+            )]
+        );
+        assert_eq!(
+            q("(orientation: portrait)"),
+            vec![query(
+                false,
+                MediaType::All,
+                vec![Orientation(MediaOrientation::Portrait)]
+            )]
+        );
+        assert_eq!(
+            q("(prefers-color-scheme: dark)"),
+            vec![query(
+                false,
+                MediaType::All,
+                vec![PrefersColorScheme(ColorScheme::Dark)]
+            )]
+        );
+        assert_eq!(
+            q("(prefers-reduced-motion: reduce)"),
+            vec![query(false, MediaType::All, vec![PrefersReducedMotion(true)])]
+        );
+        assert_eq!(
+            q("(min-resolution: 192dpi)"),
+            vec![query(
+                false,
+                MediaType::All,
+                vec![Resolution((Included(2.), Unbounded))]
+            )]
+        );
+
+        // invalid
+        assert_eq!(q("(foo: bar)"), vec![MediaQuery::NOT_ALL]);
+        assert_eq!(
+            q("(foo: bar), screen"),
+            vec![MediaQuery::NOT_ALL, query(false, MediaType::Screen, vec![])]
+        );
     }
 
     #[test]
@@ -402,22 +1986,296 @@ mod tests {
 
         assert_eq!(
             &Style::from("background: none").props,
-            &[StyleProp::BackgroundColor(CssColor::TRANSPARENT)]
+            &[
+                StyleProp::BackgroundColor(CssColor::TRANSPARENT),
+                StyleProp::BackgroundImage(CssList(vec![CssImage::None]))
+            ]
         );
         assert_eq!(
             &Style::from("background: #000").props,
-            &[StyleProp::BackgroundColor(CssColor::BLACK)]
+            &[
+                StyleProp::BackgroundColor(CssColor::BLACK),
+                StyleProp::BackgroundImage(CssList(vec![CssImage::None]))
+            ]
+        );
+        assert_eq!(
+            &Style::from("background: linear-gradient(red, #000), none #000").props,
+            &[
+                StyleProp::BackgroundColor(CssColor::BLACK),
+                StyleProp::BackgroundImage(CssList(vec![
+                    CssImage::Gradient(Box::new(CssGradient {
+                        shape: CssGradientShape::Linear(CssGradientDirection::Angle(180.)),
+                        stops: vec![
+                            CssColorStop(CssColor::from_rgba8(255, 0, 0, 255), None),
+                            CssColorStop(CssColor::BLACK, None)
+                        ]
+                    })),
+                    CssImage::None
+                ]))
+            ]
+        );
+        assert_eq!(Style::from("background: #000, none").props, &[]);
+
+        assert_eq!(
+            &Style::from("border-top: dashed 2px").props,
+            &[
+                BorderTopWidth(CssDimension::Px(2.)),
+                BorderTopStyle(CssBorderStyle::Dashed),
+                BorderTopColor(CssColor::CurrentColor)
+            ]
+        );
+        assert_eq!(Style::from("border: thin solid #000").props.len(), 12);
+        assert_eq!(
+            Style::from("border: thin solid #000").find_prop(StylePropId::BorderLeftWidth),
+            Some(&BorderLeftWidth(CssDimension::Px(1.)))
+        );
+        assert_eq!(Style::from("border: solid solid").props, &[]);
+
+        assert_eq!(
+            &Style::from("font: italic bold 16px/20px Arial, sans-serif").props,
+            &[
+                FontStyle(CssFontStyle::Italic),
+                FontWeight(700),
+                FontSize(CssDimension::Px(16.)),
+                LineHeight(CssDimension::Px(20.)),
+                FontFamily("Arial, sans-serif".into())
+            ]
+        );
+        assert_eq!(
+            &Style::from("font: 12px 'Open Sans'").props,
+            &[
+                FontStyle(CssFontStyle::Normal),
+                FontWeight(400),
+                FontSize(CssDimension::Px(12.)),
+                LineHeight(CssDimension::Auto),
+                FontFamily("Open Sans".into())
+            ]
         );
+        assert_eq!(Style::from("font: 12px").props, &[]);
+        assert_eq!(
+            Style::from("font: 300 small-caps 12px serif").find_prop(StylePropId::FontWeight),
+            Some(&FontWeight(300))
+        );
+
+        assert_eq!(
+            &Style::from("text-decoration: red underline line-through").props,
+            &[
+                TextDecorationLine(CssTextDecorationLine {
+                    underline: true,
+                    overline: false,
+                    line_through: true
+                }),
+                TextDecorationStyle(CssTextDecorationStyle::Solid),
+                TextDecorationColor(CssColor::from_rgba8(255, 0, 0, 255))
+            ]
+        );
+        assert_eq!(Style::from("text-decoration: none dotted").props.len(), 3);
+        assert_eq!(Style::from("text-decoration: none underline").props, &[]);
+        assert_eq!(Style::from("text-decoration: underline underline").props, &[]);
 
         // override
         let mut s = Style::from("background-color: #fff");
         s.set_property("background", "#000");
-        assert_eq!(s.props, &[StyleProp::BackgroundColor(CssColor::BLACK)]);
+        assert_eq!(
+            s.find_prop(StylePropId::BackgroundColor),
+            Some(&StyleProp::BackgroundColor(CssColor::BLACK))
+        );
 
         // remove
         let mut s = Style::from("background-color: #fff");
         s.set_property("background", "none");
-        assert_eq!(s.props, &[StyleProp::BackgroundColor(CssColor::TRANSPARENT)]);
+        assert_eq!(
+            s.find_prop(StylePropId::BackgroundColor),
+            Some(&StyleProp::BackgroundColor(CssColor::TRANSPARENT))
+        );
+    }
+
+    #[test]
+    fn parse_gradient() {
+        use CssGradientDirection::*;
+
+        let parse = |s: &str| match Style::from(&*format!("background-image: {}", s)).props().next() {
+            Some(StyleProp::BackgroundImage(CssList(images))) => images.clone(),
+            p => panic!("invalid image {:?}", p),
+        };
+        let gradient = |shape, stops| CssImage::Gradient(Box::new(CssGradient { shape, stops }));
+        let red = CssColor::from_rgba8(255, 0, 0, 255);
+        let center = CssOrigin(CssDimension::Percent(50.), CssDimension::Percent(50.));
+
+        assert_eq!(parse("none"), vec![CssImage::None]);
+        assert_eq!(
+            parse("linear-gradient(red, #000 50%)"),
+            vec![gradient(
+                CssGradientShape::Linear(Angle(180.)),
+                vec![
+                    CssColorStop(red, None),
+                    CssColorStop(CssColor::BLACK, Some(CssDimension::Percent(50.)))
+                ]
+            )]
+        );
+        assert_eq!(
+            parse("linear-gradient(to right, red, red), linear-gradient(0.5turn, red, red)"),
+            vec![
+                gradient(
+                    CssGradientShape::Linear(Angle(90.)),
+                    vec![CssColorStop(red, None), CssColorStop(red, None)]
+                ),
+                gradient(
+                    CssGradientShape::Linear(Angle(180.)),
+                    vec![CssColorStop(red, None), CssColorStop(red, None)]
+                )
+            ]
+        );
+        assert_eq!(
+            parse("linear-gradient(to left bottom, red, red)"),
+            vec![gradient(
+                CssGradientShape::Linear(Corner(false, true)),
+                vec![CssColorStop(red, None), CssColorStop(red, None)]
+            )]
+        );
+        assert_eq!(
+            parse("radial-gradient(circle closest-side at left, red, red 10px)"),
+            vec![gradient(
+                CssGradientShape::Radial(
+                    true,
+                    CssRadialExtent::ClosestSide,
+                    CssOrigin(CssDimension::Percent(0.), CssDimension::Percent(50.))
+                ),
+                vec![CssColorStop(red, None), CssColorStop(red, Some(CssDimension::Px(10.)))]
+            )]
+        );
+        assert_eq!(
+            parse("radial-gradient(red, red)"),
+            vec![gradient(
+                CssGradientShape::Radial(false, CssRadialExtent::FarthestCorner, center.clone()),
+                vec![CssColorStop(red, None), CssColorStop(red, None)]
+            )]
+        );
+        assert_eq!(
+            parse("conic-gradient(from 90deg, red, red 180deg)"),
+            vec![gradient(
+                CssGradientShape::Conic(90., center),
+                vec![
+                    CssColorStop(red, None),
+                    CssColorStop(red, Some(CssDimension::Percent(50.)))
+                ]
+            )]
+        );
+
+        assert_eq!(Style::from("background-image: linear-gradient(red)").length(), 0);
+        assert_eq!(
+            Style::from("background-image: conic-gradient(red, red 10px)").length(),
+            0
+        );
+        assert_eq!(Style::from("background-image: radial-gradient(, red, red)").length(), 0);
+        assert_eq!(
+            Style::from("background-image: conic-gradient(red, red)").css_text(),
+            "background-image: conic-gradient(from 0deg at 50% 50%, rgba(255, 0, 0, 255), rgba(255, 0, 0, 255));"
+        );
+    }
+
+    #[test]
+    fn parse_transform() {
+        use CssTransformFn::*;
+
+        let parse = |s: &str| match Style::from(&*format!("transform: {}", s)).props().next() {
+            Some(StyleProp::Transform(t)) => t.0.clone(),
+            p => panic!("invalid transform {:?}", p),
+        };
+
+        assert_eq!(parse("none"), vec![]);
+        assert_eq!(
+            parse("translate(10px) translateY(50%)"),
+            vec![
+                Translate(CssDimension::Px(10.), CssDimension::ZERO),
+                Translate(CssDimension::ZERO, CssDimension::Percent(50.))
+            ]
+        );
+        assert_eq!(parse("scale(2) scaleX(0.5)"), vec![Scale(2., 2.), Scale(0.5, 1.)]);
+        assert_eq!(parse("rotate(0.5turn)"), vec![Rotate(180.)]);
+        assert_eq!(parse("rotate(90deg) skew(10deg, 0)"), vec![Rotate(90.), Skew(10., 0.)]);
+        assert_eq!(
+            parse("matrix(1, 0, 0, 1, 10, 20)"),
+            vec![Matrix([1., 0., 0., 1., 10., 20.])]
+        );
+
+        assert_eq!(Style::from("transform: rotate(10)").length(), 0);
+
+        let origin = |s: &str| Style::from(&*format!("transform-origin: {}", s)).property_value("transform-origin");
+
+        assert_eq!(origin("center").as_deref(), Some("50% 50%"));
+        assert_eq!(origin("left").as_deref(), Some("0% 50%"));
+        assert_eq!(origin("top").as_deref(), Some("50% 0%"));
+        assert_eq!(origin("top right").as_deref(), Some("100% 0%"));
+        assert_eq!(origin("10px bottom").as_deref(), Some("10px 100%"));
+    }
+
+    #[test]
+    fn parse_transition() {
+        use CssTimingFunction::*;
+
+        let t = |s| Style::from(s).css_text();
+
+        assert_eq!(t("transition: opacity 1s"), "transition: opacity 1s ease 0s;");
+        assert_eq!(
+            t("transition: 200ms ease-in 1s width, color 1s steps(2, start)"),
+            "transition: width 0.2s ease-in 1s, color 1s steps(2, jump-start) 0s;"
+        );
+        assert_eq!(t("transition: opacity 1s 2s 3s"), "");
+        assert_eq!(t("transition: opacity width 1s"), "");
+
+        assert_eq!(time().parse(&["1", "s"]), Ok(CssTime(1.)));
+        assert_eq!(time().parse(&["150", "ms"]), Ok(CssTime(0.15)));
+
+        let f = |s: &str| {
+            let tokens = tokenize(s.as_bytes());
+            let res = timing_function().parse(&tokens);
+            res
+        };
+
+        assert_eq!(f("ease-out"), Ok(EaseOut));
+        assert_eq!(f("step-end"), Ok(Steps(1, CssStepPosition::JumpEnd)));
+        assert_eq!(f("cubic-bezier(0.1, -1, 0.5, 2)"), Ok(CubicBezier(0.1, -1., 0.5, 2.)));
+        assert!(f("cubic-bezier(2, 0, 0, 0)").is_err());
+        assert_eq!(f("steps(3)"), Ok(Steps(3, CssStepPosition::JumpEnd)));
+        assert!(f("steps(1, jump-none)").is_err());
+    }
+
+    #[test]
+    fn parse_animation() {
+        let t = |s| Style::from(s).css_text();
+
+        assert_eq!(
+            t("animation: 1s infinite alternate spin"),
+            "animation: 1s ease 0s infinite alternate none running spin;"
+        );
+        assert_eq!(
+            t("animation-name: a, 'b'; animation-iteration-count: 2, 0.5; animation-fill-mode: both"),
+            "animation-name: a, b;animation-iteration-count: 2, 0.5;animation-fill-mode: both;"
+        );
+        assert_eq!(t("animation: a 1s 2s 3s"), "");
+        assert_eq!(t("animation-iteration-count: -1"), "");
+    }
+
+    #[test]
+    fn parse_keyframes() {
+        let sheet = StyleSheet::from(
+            "@keyframes spin { from { opacity: 0 } 50%, to { opacity: 1 } }
+             @keyframes none {}
+             @keyframes x { 200% { opacity: 1 } }",
+        );
+
+        assert_eq!(
+            sheet.rules,
+            vec![CssRule::Keyframes(KeyframesRule {
+                name: "spin".to_owned(),
+                keyframes: vec![
+                    (0., Style::from("opacity: 0")),
+                    (0.5, Style::from("opacity: 1")),
+                    (1., Style::from("opacity: 1"))
+                ]
+            })]
+        );
     }
 
     #[test]
@@ -426,7 +2284,7 @@ mod tests {
         let tokens = tokenize(ua.as_bytes());
         let sheet = super::sheet().parse(&tokens).unwrap();
 
-        assert_eq!(sheet.rules.len(), 24);
+        assert_eq!(sheet.rules.len(), 29);
     }
 
     #[test]
@@ -504,27 +2362,73 @@ mod tests {
         );
 
         // unsupported for now
-        assert_eq!(s(":root"), &[Component(Unsupported)]);
+        assert_eq!(s(":root"), &[Component(Unsupported(":root".into()))]);
         assert_eq!(
             s("* + *"),
-            &[Combinator(Universal), Component(Unsupported), Combinator(Universal)]
+            &[
+                Combinator(Universal),
+                Component(Unsupported("+".into())),
+                Combinator(Universal)
+            ]
         );
         assert_eq!(
             s("* ~ *"),
-            &[Combinator(Universal), Component(Unsupported), Combinator(Universal)]
+            &[
+                Combinator(Universal),
+                Component(Unsupported("~".into())),
+                Combinator(Universal)
+            ]
         );
 
         // invalid
-        assert_eq!(s(""), &[Component(Unsupported)]);
-        assert_eq!(s(" "), &[Component(Unsupported)]);
-        assert_eq!(s("a,,b"), &[Component(Unsupported)]);
-        assert_eq!(s("a>>b"), &[Component(Unsupported)]);
+        assert_eq!(s(""), &[Component(Unsupported("".into()))]);
+        assert_eq!(s(" "), &[Component(Unsupported(" ".into()))]);
+        assert_eq!(s("a,,b"), &[Component(Unsupported("a,,b".into()))]);
+        assert_eq!(s("a>>b"), &[Component(Unsupported("a>>b".into()))]);
 
         // bugs & edge-cases
         assert_eq!(
             s("input[type=\"submit\"]"),
-            &[Component(Unsupported), Component(LocalName("input".into()))]
+            &[
+                Component(Unsupported("[type=\"submit\"]".into())),
+                Component(LocalName("input".into()))
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_keywords() {
+        use StyleProp::*;
+
+        assert_eq!(
+            &Style::from("color: inherit").props,
+            &[Keyword(StylePropId::Color, CssWideKeyword::Inherit)]
         );
+        assert_eq!(
+            &Style::from("font-family: initial").props,
+            &[Keyword(StylePropId::FontFamily, CssWideKeyword::Initial)]
+        );
+        assert_eq!(
+            &Style::from("overflow: unset").props,
+            &[
+                Keyword(StylePropId::OverflowX, CssWideKeyword::Unset),
+                Keyword(StylePropId::OverflowY, CssWideKeyword::Unset)
+            ]
+        );
+        assert_eq!(Style::from("display: revert").css_text(), "display: revert;");
+
+        // override
+        assert_eq!(
+            &Style::from("color: #000; color: inherit").props,
+            &[Keyword(StylePropId::Color, CssWideKeyword::Inherit)]
+        );
+        assert_eq!(
+            &Style::from("color: inherit; color: #000").props,
+            &[Color(CssColor::BLACK)]
+        );
+
+        // unknown
+        assert_eq!(&Style::from("xxx: inherit").props, &[]);
     }
 
     #[test]
@@ -577,6 +2481,59 @@ mod tests {
         assert_eq!(dimension().parse(&["10", "px"]), Ok(CssDimension::Px(10.)));
         assert_eq!(dimension().parse(&["100", "%"]), Ok(CssDimension::Percent(100.)));
         assert_eq!(dimension().parse(&["0"]), Ok(CssDimension::Px(0.)));
+        assert_eq!(dimension().parse(&["1.5", "em"]), Ok(CssDimension::Em(1.5)));
+        assert_eq!(dimension().parse(&["2", "rem"]), Ok(CssDimension::Rem(2.)));
+        assert_eq!(dimension().parse(&["100", "vw"]), Ok(CssDimension::Vw(100.)));
+        assert_eq!(dimension().parse(&["50", "vh"]), Ok(CssDimension::Vh(50.)));
+        assert_eq!(dimension().parse(&["10", "vmin"]), Ok(CssDimension::Vmin(10.)));
+        assert_eq!(dimension().parse(&["10", "vmax"]), Ok(CssDimension::Vmax(10.)));
+    }
+
+    #[test]
+    fn parse_calc() {
+        let dim = |s: &str| {
+            let tokens = tokenize(s.as_bytes());
+            let res = dimension().parse(&tokens);
+            res
+        };
+        let calc = |s| match dim(s) {
+            Ok(CssDimension::Calc(calc)) => *calc,
+            res => panic!("{:?}", res),
+        };
+
+        assert_eq!(
+            calc("calc(100% - 240px)"),
+            CssCalc::Sub(Box::new(CssCalc::Percent(100.)), Box::new(CssCalc::Px(240.)))
+        );
+        assert_eq!(calc("calc(100% - 240px)").resolve(1000.), 760.);
+        assert_eq!(calc("calc(50% + 10px)").resolve(100.), 60.);
+        assert_eq!(calc("calc((100% - 20px) / 2)").resolve(120.), 50.);
+        assert_eq!(calc("calc(2 * 10px + 5%)").resolve(100.), 25.);
+        assert_eq!(calc("min(100%, 500px)").resolve(1000.), 500.);
+        assert_eq!(calc("max(50%, 200px)").resolve(1000.), 500.);
+        assert_eq!(calc("clamp(200px, 50%, 800px)").resolve(200.), 200.);
+        assert_eq!(calc("clamp(200px, 50%, 800px)").resolve(1000.), 500.);
+        assert_eq!(calc("clamp(200px, 50%, 800px)").resolve(2000.), 800.);
+        assert_eq!(calc("calc(100% - min(10%, 20px))").resolve(100.), 90.);
+
+        // simple values are unwrapped
+        assert_eq!(dim("calc(10px)"), Ok(CssDimension::Px(10.)));
+        assert_eq!(dim("calc(50%)"), Ok(CssDimension::Percent(50.)));
+
+        // invalid
+        assert!(dim("calc()").is_err());
+        assert!(dim("min()").is_err());
+        assert!(dim("calc(100% / 0)").is_err());
+        assert!(dim("calc(10px / 0.0)").is_err());
+
+        // serialization
+        assert_eq!(calc("calc(100% - 240px)").to_string(), "calc(100% - 240px)");
+        assert_eq!(calc("calc((100% - 20px) / 2)").to_string(), "calc((100% - 20px) / 2)");
+        assert_eq!(calc("clamp(200px, 50%, 800px)").to_string(), "clamp(200px, 50%, 800px)");
+        assert_eq!(
+            Style::from("width: calc(100% - 240px)").css_text(),
+            "width: calc(100% - 240px);"
+        );
     }
 
     #[test]
@@ -610,6 +2567,37 @@ mod tests {
 
         assert_eq!(color().parse(&["transparent"]), Ok(CssColor::TRANSPARENT));
         assert_eq!(color().parse(&["black"]), Ok(CssColor::BLACK));
+        assert_eq!(color().parse(&["Black"]), Ok(CssColor::BLACK));
+        assert_eq!(color().parse(&["currentColor"]), Ok(CssColor::CurrentColor));
+
+        let c = |s: &str| {
+            let tokens = tokenize(s.as_bytes());
+            let res = (color() - end()).parse(&tokens).map(|c| c.to_string());
+            res
+        };
+
+        assert_eq!(c("rgb(255, 0, 0, 0.5)").as_deref(), Ok("rgba(255, 0, 0, 128)"));
+        assert_eq!(c("rgba(255, 0, 0)").as_deref(), Ok("rgba(255, 0, 0, 255)"));
+        assert_eq!(c("rgb(100%, 50%, 0%)").as_deref(), Ok("rgba(255, 128, 0, 255)"));
+        assert_eq!(c("rgb(255 0 0)").as_deref(), Ok("rgba(255, 0, 0, 255)"));
+        assert_eq!(c("rgb(255 0 0 / 50%)").as_deref(), Ok("rgba(255, 0, 0, 128)"));
+        assert_eq!(c("RGB(0 0 0 / 0)").as_deref(), Ok("rgba(0, 0, 0, 0)"));
+        assert_eq!(c("rgb(300, -1, 0)").as_deref(), Ok("rgba(255, 0, 0, 255)"));
+
+        assert_eq!(c("hsl(0, 100%, 50%)").as_deref(), Ok("rgba(255, 0, 0, 255)"));
+        assert_eq!(c("hsl(120 100% 25%)").as_deref(), Ok("rgba(0, 128, 0, 255)"));
+        assert_eq!(c("hsla(240deg, 100%, 50%, 0.5)").as_deref(), Ok("rgba(0, 0, 255, 128)"));
+        assert_eq!(c("hsl(0.5turn 100% 50% / 1)").as_deref(), Ok("rgba(0, 255, 255, 255)"));
+        assert_eq!(c("hsl(-120 100% 50%)").as_deref(), Ok("rgba(0, 0, 255, 255)"));
+        assert_eq!(c("hsl(0 0% 100%)").as_deref(), Ok("rgba(255, 255, 255, 255)"));
+
+        assert_eq!(c("hwb(0 0% 0%)").as_deref(), Ok("rgba(255, 0, 0, 255)"));
+        assert_eq!(c("hwb(120 20% 20%)").as_deref(), Ok("rgba(51, 204, 51, 255)"));
+        assert_eq!(c("hwb(0 60% 60% / 50%)").as_deref(), Ok("rgba(128, 128, 128, 128)"));
+
+        assert!(c("rgb(0, 0)").is_err());
+        assert!(c("rgb(0, 0 0)").is_err());
+        assert!(c("unknown").is_err());
     }
 
     #[test]
@@ -668,6 +2656,50 @@ mod tests {
         assert_eq!(try_from().parse(&["sticky"]), Ok(CssPosition::Sticky));
     }
 
+    #[test]
+    fn parse_text_props() {
+        let parse = |s: &str| Style::from(s).props().next().cloned();
+
+        assert_eq!(parse("font-weight: bold"), Some(StyleProp::FontWeight(700)));
+        assert_eq!(parse("font-weight: 350"), Some(StyleProp::FontWeight(350)));
+        assert_eq!(parse("font-weight: 0"), None);
+        assert_eq!(
+            parse("font-style: oblique"),
+            Some(StyleProp::FontStyle(CssFontStyle::Oblique))
+        );
+        assert_eq!(
+            parse("letter-spacing: normal"),
+            Some(StyleProp::LetterSpacing(CssDimension::Auto))
+        );
+        assert_eq!(
+            parse("word-spacing: 2px"),
+            Some(StyleProp::WordSpacing(CssDimension::Px(2.)))
+        );
+        assert_eq!(
+            parse("text-transform: uppercase"),
+            Some(StyleProp::TextTransform(CssTextTransform::Uppercase))
+        );
+        assert_eq!(
+            parse("white-space: pre-wrap"),
+            Some(StyleProp::WhiteSpace(CssWhiteSpace::PreWrap))
+        );
+        assert_eq!(
+            parse("text-decoration-line: overline"),
+            Some(StyleProp::TextDecorationLine(CssTextDecorationLine {
+                overline: true,
+                ..Default::default()
+            }))
+        );
+    }
+
+    #[test]
+    fn parse_z_index() {
+        assert_eq!(z_index().parse(&["auto"]), Ok(CssZIndex::Auto));
+        assert_eq!(z_index().parse(&["0"]), Ok(CssZIndex::Index(0)));
+        assert_eq!(z_index().parse(&["-2"]), Ok(CssZIndex::Index(-2)));
+        assert!(z_index().parse(&["1.5"]).is_err());
+    }
+
     #[test]
     fn parse_text_align() {
         assert_eq!(try_from().parse(&["left"]), Ok(CssTextAlign::Left));
@@ -682,4 +2714,18 @@ mod tests {
         assert_eq!(try_from().parse(&["hidden"]), Ok(CssVisibility::Hidden));
         assert_eq!(try_from().parse(&["collapse"]), Ok(CssVisibility::Collapse));
     }
+
+    #[test]
+    fn parse_cursor() {
+        assert_eq!(try_from().parse(&["auto"]), Ok(CssCursor::Auto));
+        assert_eq!(try_from().parse(&["pointer"]), Ok(CssCursor::Pointer));
+        assert_eq!(try_from().parse(&["not-allowed"]), Ok(CssCursor::NotAllowed));
+        assert_eq!(try_from().parse(&["ns-resize"]), Ok(CssCursor::NsResize));
+    }
+
+    #[test]
+    fn parse_pointer_events() {
+        assert_eq!(try_from().parse(&["auto"]), Ok(CssPointerEvents::Auto));
+        assert_eq!(try_from().parse(&["none"]), Ok(CssPointerEvents::None));
+    }
 }
@@ -1,11 +1,17 @@
 // supported CSS props
 
 use super::parser::{
-    background, box_shadow, color, dimension, flex, float, font_family, outline, overflow, sides_of, try_from,
+    animation, animation_name, background, border, border_width, box_shadow, color, css_list, dimension, flex, float,
+    font, font_family, font_weight, gap, gap_size, image, iteration_count, line_height, origin, outline, overflow,
+    sides_of, spacing, text_decoration, text_decoration_line, time, timing_function, transform, transition,
+    transition_property, try_from, z_index,
 };
 use super::{
-    CssAlign, CssBorderStyle, CssBoxShadow, CssColor, CssDimension, CssDisplay, CssFlexDirection, CssFlexWrap,
-    CssJustify, CssOverflow, CssPosition, CssTextAlign, CssVisibility,
+    CssAlign, CssAnimationDirection, CssAnimationFillMode, CssAnimationPlayState, CssBorderStyle, CssBoxShadow,
+    CssColor, CssCursor, CssDimension, CssDisplay, CssFlexDirection, CssFlexWrap, CssFontStyle, CssImage,
+    CssIterationCount, CssJustify, CssList, CssOrigin, CssOverflow, CssPointerEvents, CssPosition, CssTextAlign,
+    CssTextDecorationLine, CssTextDecorationStyle, CssTextTransform, CssTime, CssTimingFunction, CssTransform,
+    CssUnits, CssVisibility, CssWhiteSpace, CssWideKeyword, CssZIndex,
 };
 use crate::util::Atom;
 
@@ -16,27 +22,47 @@ macro_rules! css_properties {
             $($variant,)*
         }
 
+        impl StylePropId {
+            pub const ALL: &'static [Self] = &[$(Self::$variant),*];
+
+            pub fn name(&self) -> &'static str {
+                match self {
+                    $(Self::$variant => $name,)*
+                }
+            }
+
+            pub(super) fn from_name(name: &str) -> Option<Self> {
+                match name {
+                    $($name => Some(Self::$variant),)*
+                    _ => None
+                }
+            }
+        }
+
         #[derive(Debug, Clone, PartialEq)]
         pub enum StyleProp {
             $($variant($value_type),)*
+
+            // inherit, initial, unset, revert (valid for any prop)
+            Keyword(StylePropId, CssWideKeyword),
         }
 
         impl StyleProp {
             pub fn id(&self) -> StylePropId {
                 match self {
                     $(Self::$variant(_) => StylePropId::$variant,)*
+                    Self::Keyword(id, _) => *id,
                 }
             }
 
             pub fn name(&self) -> &'static str {
-                match self {
-                    $(Self::$variant(_) => $name,)*
-                }
+                self.id().name()
             }
 
             pub(super) fn value_as_string(&self) -> String {
                 let v: &dyn std::fmt::Display = match self {
-                    $(Self::$variant(ref v) => v),*
+                    $(Self::$variant(ref v) => v,)*
+                    Self::Keyword(_, ref k) => k,
                 };
 
                 format!("{}", v)
@@ -77,6 +103,7 @@ css_properties! {
 
     // background
     ("background-color", color()) => BackgroundColor(CssColor),
+    ("background-image", css_list(image())) => BackgroundImage(CssList<CssImage>),
 
     // border-radius
     ("border-top-left-radius", dimension()) => BorderTopLeftRadius(CssDimension),
@@ -85,16 +112,16 @@ css_properties! {
     ("border-bottom-left-radius", dimension()) => BorderBottomLeftRadius(CssDimension),
 
     // border
-    ("border-top-width", dimension()) => BorderTopWidth(CssDimension),
+    ("border-top-width", border_width()) => BorderTopWidth(CssDimension),
     ("border-top-style", try_from()) => BorderTopStyle(CssBorderStyle),
     ("border-top-color", color()) => BorderTopColor(CssColor),
-    ("border-right-width", dimension()) => BorderRightWidth(CssDimension),
+    ("border-right-width", border_width()) => BorderRightWidth(CssDimension),
     ("border-right-style", try_from()) => BorderRightStyle(CssBorderStyle),
     ("border-right-color", color()) => BorderRightColor(CssColor),
-    ("border-bottom-width", dimension()) => BorderBottomWidth(CssDimension),
+    ("border-bottom-width", border_width()) => BorderBottomWidth(CssDimension),
     ("border-bottom-style", try_from()) => BorderBottomStyle(CssBorderStyle),
     ("border-bottom-color", color()) => BorderBottomColor(CssColor),
-    ("border-left-width", dimension()) => BorderLeftWidth(CssDimension),
+    ("border-left-width", border_width()) => BorderLeftWidth(CssDimension),
     ("border-left-style", try_from()) => BorderLeftStyle(CssBorderStyle),
     ("border-left-color", color()) => BorderLeftColor(CssColor),
 
@@ -111,13 +138,24 @@ css_properties! {
     ("align-items", try_from()) => AlignItems(CssAlign),
     ("align-self", try_from()) => AlignSelf(CssAlign),
     ("justify-content", try_from()) => JustifyContent(CssJustify),
+    ("row-gap", gap_size()) => RowGap(CssDimension),
+    ("column-gap", gap_size()) => ColumnGap(CssDimension),
 
     // text
     ("font-family", font_family()) => FontFamily(Atom<String>),
     ("font-size", dimension()) => FontSize(CssDimension),
-    ("line-height", dimension()) => LineHeight(CssDimension),
+    ("font-weight", font_weight()) => FontWeight(u16),
+    ("font-style", try_from()) => FontStyle(CssFontStyle),
+    ("line-height", line_height()) => LineHeight(CssDimension),
     ("text-align", try_from()) => TextAlign(CssTextAlign),
     ("color", color()) => Color(CssColor),
+    ("letter-spacing", spacing()) => LetterSpacing(CssDimension),
+    ("word-spacing", spacing()) => WordSpacing(CssDimension),
+    ("text-transform", try_from()) => TextTransform(CssTextTransform),
+    ("white-space", try_from()) => WhiteSpace(CssWhiteSpace),
+    ("text-decoration-line", text_decoration_line()) => TextDecorationLine(CssTextDecorationLine),
+    ("text-decoration-style", try_from()) => TextDecorationStyle(CssTextDecorationStyle),
+    ("text-decoration-color", color()) => TextDecorationColor(CssColor),
 
     // outline
     ("outline-color", color()) => OutlineColor(CssColor),
@@ -134,15 +172,245 @@ css_properties! {
     ("right", dimension()) => Right(CssDimension),
     ("bottom", dimension()) => Bottom(CssDimension),
     ("left", dimension()) => Left(CssDimension),
+    ("z-index", z_index()) => ZIndex(CssZIndex),
+
+    // transform
+    ("transform", transform()) => Transform(CssTransform),
+    ("transform-origin", origin()) => TransformOrigin(CssOrigin),
 
     // other
     ("display", try_from()) => Display(CssDisplay),
     ("opacity", float()) => Opacity(f32),
     ("visibility", try_from()) => Visibility(CssVisibility),
+    ("cursor", try_from()) => Cursor(CssCursor),
+    ("pointer-events", try_from()) => PointerEvents(CssPointerEvents),
+
+    // transition
+    ("transition-property", transition_property()) => TransitionProperty(CssList<Atom<String>>),
+    ("transition-duration", css_list(time())) => TransitionDuration(CssList<CssTime>),
+    ("transition-timing-function", css_list(timing_function())) => TransitionTimingFunction(CssList<CssTimingFunction>),
+    ("transition-delay", css_list(time())) => TransitionDelay(CssList<CssTime>),
+
+    // animation
+    ("animation-name", css_list(animation_name())) => AnimationName(CssList<Atom<String>>),
+    ("animation-duration", css_list(time())) => AnimationDuration(CssList<CssTime>),
+    ("animation-timing-function", css_list(timing_function())) => AnimationTimingFunction(CssList<CssTimingFunction>),
+    ("animation-delay", css_list(time())) => AnimationDelay(CssList<CssTime>),
+    ("animation-iteration-count", css_list(iteration_count())) => AnimationIterationCount(CssList<CssIterationCount>),
+    ("animation-direction", css_list(try_from())) => AnimationDirection(CssList<CssAnimationDirection>),
+    ("animation-fill-mode", css_list(try_from())) => AnimationFillMode(CssList<CssAnimationFillMode>),
+    ("animation-play-state", css_list(try_from())) => AnimationPlayState(CssList<CssAnimationPlayState>),
+}
+
+impl StylePropId {
+    // https://www.w3.org/TR/CSS22/propidx.html
+    pub fn is_inherited(&self) -> bool {
+        use StylePropId::*;
+
+        matches!(
+            self,
+            FontFamily
+                | FontSize
+                | FontWeight
+                | FontStyle
+                | LineHeight
+                | TextAlign
+                | Color
+                | LetterSpacing
+                | WordSpacing
+                | TextTransform
+                | WhiteSpace
+                | Visibility
+                | Cursor
+                | PointerEvents
+        )
+    }
+
+    // props which are used for layout (LayoutStyle::from() in viewport.rs)
+    pub fn affects_layout(&self) -> bool {
+        use StylePropId::*;
+
+        matches!(
+            self,
+            Width
+                | Height
+                | MinWidth
+                | MinHeight
+                | MaxWidth
+                | MaxHeight
+                | PaddingTop
+                | PaddingRight
+                | PaddingBottom
+                | PaddingLeft
+                | MarginTop
+                | MarginRight
+                | MarginBottom
+                | MarginLeft
+                | BorderTopWidth
+                | BorderRightWidth
+                | BorderBottomWidth
+                | BorderLeftWidth
+                | BorderTopStyle
+                | BorderRightStyle
+                | BorderBottomStyle
+                | BorderLeftStyle
+                | Position
+                | Top
+                | Right
+                | Bottom
+                | Left
+                | FlexGrow
+                | FlexShrink
+                | FlexBasis
+                | FlexWrap
+                | FlexDirection
+                | AlignContent
+                | AlignItems
+                | AlignSelf
+                | JustifyContent
+                | RowGap
+                | ColumnGap
+                | Display
+        )
+    }
+
+    // None if it can't be represented (box-shadow: none)
+    pub fn initial_value(&self) -> Option<StyleProp> {
+        use StylePropId::*;
+
+        let value = match self {
+            Width | Height | MinWidth | MinHeight | FlexBasis | Top | Right | Bottom | Left | AlignSelf => "auto",
+            // TODO: none
+            MaxWidth | MaxHeight => "auto",
+            PaddingTop | PaddingRight | PaddingBottom | PaddingLeft => "0px",
+            MarginTop | MarginRight | MarginBottom | MarginLeft => "0px",
+            BorderTopLeftRadius | BorderTopRightRadius | BorderBottomRightRadius | BorderBottomLeftRadius => "0px",
+            BorderTopWidth | BorderRightWidth | BorderBottomWidth | BorderLeftWidth | OutlineWidth => "3px",
+            BorderTopStyle | BorderRightStyle | BorderBottomStyle | BorderLeftStyle | OutlineStyle => "none",
+            BorderTopColor | BorderRightColor | BorderBottomColor | BorderLeftColor | OutlineColor => "currentcolor",
+            BackgroundColor => "transparent",
+            BackgroundImage => "none",
+            BoxShadow => "none",
+            FlexGrow => "0",
+            FlexShrink => "1",
+            FlexDirection => "row",
+            FlexWrap => "nowrap",
+            AlignContent | AlignItems => "stretch",
+            JustifyContent => "flex-start",
+            RowGap | ColumnGap => "normal",
+            FontFamily => "sans-serif",
+            FontSize => "16px",
+            FontWeight => "400",
+            FontStyle => "normal",
+            LineHeight => "normal",
+            TextAlign => "left",
+            Color => "black",
+            LetterSpacing | WordSpacing => "normal",
+            TextTransform | TextDecorationLine => "none",
+            WhiteSpace => "normal",
+            TextDecorationStyle => "solid",
+            TextDecorationColor => "currentcolor",
+            OverflowX | OverflowY => "visible",
+            Position => "static",
+            ZIndex => "auto",
+            Transform => "none",
+            TransformOrigin => "50% 50%",
+            Display => "inline",
+            Opacity => "1",
+            Visibility => "visible",
+            Cursor | PointerEvents => "auto",
+            TransitionProperty => "all",
+            TransitionDuration | TransitionDelay | AnimationDuration | AnimationDelay => "0s",
+            TransitionTimingFunction | AnimationTimingFunction => "ease",
+            AnimationName | AnimationFillMode => "none",
+            AnimationIterationCount => "1",
+            AnimationDirection => "normal",
+            AnimationPlayState => "running",
+        };
+
+        let tokens = super::parser::tokenize(value.as_bytes());
+        let parser = prop_parser(self.name()) - pom::parser::end();
+
+        parser.parse(&tokens).ok()
+    }
+}
+
+impl StyleProp {
+    // em, rem, vw, ... to px
+    // (font-size is special, it should be resolved with em/% relative to the parent)
+    pub(super) fn resolve_units(&mut self, units: &CssUnits) {
+        use StyleProp::*;
+
+        match self {
+            Width(v)
+            | Height(v)
+            | MinWidth(v)
+            | MinHeight(v)
+            | MaxWidth(v)
+            | MaxHeight(v)
+            | PaddingTop(v)
+            | PaddingRight(v)
+            | PaddingBottom(v)
+            | PaddingLeft(v)
+            | MarginTop(v)
+            | MarginRight(v)
+            | MarginBottom(v)
+            | MarginLeft(v)
+            | BorderTopLeftRadius(v)
+            | BorderTopRightRadius(v)
+            | BorderBottomRightRadius(v)
+            | BorderBottomLeftRadius(v)
+            | BorderTopWidth(v)
+            | BorderRightWidth(v)
+            | BorderBottomWidth(v)
+            | BorderLeftWidth(v)
+            | FlexBasis(v)
+            | RowGap(v)
+            | ColumnGap(v)
+            | FontSize(v)
+            | LineHeight(v)
+            | LetterSpacing(v)
+            | WordSpacing(v)
+            | OutlineWidth(v)
+            | Top(v)
+            | Right(v)
+            | Bottom(v)
+            | Left(v) => *v = v.resolve_units(units),
+            TransformOrigin(CssOrigin(x, y)) => {
+                *x = x.resolve_units(units);
+                *y = y.resolve_units(units);
+            }
+            Transform(t) => {
+                for f in &mut t.0 {
+                    if let super::CssTransformFn::Translate(x, y) = f {
+                        *x = x.resolve_units(units);
+                        *y = y.resolve_units(units);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    pub fn resolve_current_color(&mut self, current_color: CssColor) {
+        use StyleProp::*;
+
+        match self {
+            BackgroundColor(c) | BorderTopColor(c) | BorderRightColor(c) | BorderBottomColor(c)
+            | BorderLeftColor(c) | Color(c) | OutlineColor(c) => {
+                if *c == CssColor::CurrentColor {
+                    *c = current_color
+                }
+            }
+            _ => {}
+        }
+    }
 }
 
 macro_rules! css_shorthands {
-    ($(($name:literal, $parser:expr) => ($($variant:ident),*),)*) => {
+    ($(($name:literal, $parser:expr) => $serialize:ident($($variant:ident),*),)*) => {
+        pub(super) const SHORTHANDS: &[&str] = &[$($name),*];
+
         pub(super) fn shorthand_parser<'a>(prop: &str) -> super::parser::Parser<'a, Vec<StyleProp>> {
             #[allow(non_snake_case)]
             match prop {
@@ -151,41 +419,218 @@ macro_rules! css_shorthands {
             }
         }
 
+        pub(super) fn shorthand_longhands(shorthand_name: &str) -> Option<&'static [StylePropId]> {
+            match shorthand_name {
+                $($name => Some(&[$(StylePropId::$variant),*]),)*
+                _ => None
+            }
+        }
+
         impl super::Style {
+            // None if any of the longhands is missing (or if it can't be expressed with the shorthand)
             pub(super) fn shorthand_value(&self, shorthand_name: &str) -> Option<String> {
                 match shorthand_name {
-                    //$($name => todo!(),)*
-                    _ => return None
+                    $($name => serialize_shorthand(&[$(self.find_prop(StylePropId::$variant)?),*], $serialize),)*
+                    _ => None
                 }
             }
         }
     };
 }
 
+// shorthand_name(parser) => serializer(Longhand, ...)
 css_shorthands! {
-    // TODO: multi, image, gradient
-    ("background", background()) => (BackgroundColor),
+    // TODO: position, size, repeat, ...
+    ("background", background()) => background_value(BackgroundColor, BackgroundImage),
 
-    // TODO: line-height should be delimited with /
-    //"font" => (FontStyle, FontVariant, FontWeight, FontStretch, FontSize, LineHeight, FontFamily],
+    // TODO: FontVariant, FontStretch
+    ("font", font()) => font_value(FontStyle, FontWeight, FontSize, LineHeight, FontFamily),
 
-    ("flex", flex()) => (FlexGrow, FlexShrink, FlexBasis),
-    ("padding", sides_of(dimension())) => (PaddingTop, PaddingRight, PaddingBottom, PaddingLeft),
-    ("margin", sides_of(dimension())) => (MarginTop, MarginRight, MarginBottom, MarginLeft),
+    ("flex", flex()) => spaced(FlexGrow, FlexShrink, FlexBasis),
+    ("gap", gap()) => sides(RowGap, ColumnGap),
+    ("padding", sides_of(dimension())) => sides(PaddingTop, PaddingRight, PaddingBottom, PaddingLeft),
+    ("margin", sides_of(dimension())) => sides(MarginTop, MarginRight, MarginBottom, MarginLeft),
 
-    // TODO
-    // ("border", border()) => (BorderTopWidth, BorderTopStyle, BorderTopColor, BorderRightWidth, BorderRightStyle, BorderRightColor, BorderBottomWidth, BorderBottomStyle, BorderBottomColor, BorderLeftWidth, BorderLeftStyle, BorderLeftColor)
+    ("border", border().map(|(w, s, c)| (w.clone(), s, c.clone(), w.clone(), s, c.clone(), w.clone(), s, c.clone(), w, s, c))) => same_sides(BorderTopWidth, BorderTopStyle, BorderTopColor, BorderRightWidth, BorderRightStyle, BorderRightColor, BorderBottomWidth, BorderBottomStyle, BorderBottomColor, BorderLeftWidth, BorderLeftStyle, BorderLeftColor),
+    ("border-top", border()) => spaced(BorderTopWidth, BorderTopStyle, BorderTopColor),
+    ("border-right", border()) => spaced(BorderRightWidth, BorderRightStyle, BorderRightColor),
+    ("border-bottom", border()) => spaced(BorderBottomWidth, BorderBottomStyle, BorderBottomColor),
+    ("border-left", border()) => spaced(BorderLeftWidth, BorderLeftStyle, BorderLeftColor),
 
-    ("border-width", sides_of(dimension())) => (BorderTopWidth, BorderRightWidth, BorderBottomWidth, BorderLeftWidth),
-    ("border-style", sides_of(try_from())) => (BorderTopStyle, BorderRightStyle, BorderBottomStyle, BorderLeftStyle),
-    ("border-color", sides_of(color())) => (BorderTopColor, BorderRightColor, BorderBottomColor, BorderLeftColor),
+    ("border-width", sides_of(border_width())) => sides(BorderTopWidth, BorderRightWidth, BorderBottomWidth, BorderLeftWidth),
+    ("border-style", sides_of(try_from())) => sides(BorderTopStyle, BorderRightStyle, BorderBottomStyle, BorderLeftStyle),
+    ("border-color", sides_of(color())) => sides(BorderTopColor, BorderRightColor, BorderBottomColor, BorderLeftColor),
 
     // TODO(maybe): two dimensions
-    ("border-radius", sides_of(dimension())) => (BorderTopLeftRadius, BorderTopRightRadius, BorderBottomLeftRadius, BorderBottomRightRadius),
+    ("border-radius", sides_of(dimension())) => sides(BorderTopLeftRadius, BorderTopRightRadius, BorderBottomRightRadius, BorderBottomLeftRadius),
+
+    ("overflow", overflow()) => sides(OverflowX, OverflowY),
+    ("outline", outline()) => spaced(OutlineWidth, OutlineStyle, OutlineColor),
+    ("transition", transition()) => lists(TransitionProperty, TransitionDuration, TransitionTimingFunction, TransitionDelay),
+    ("animation", animation()) => animation_lists(AnimationName, AnimationDuration, AnimationTimingFunction, AnimationDelay, AnimationIterationCount, AnimationDirection, AnimationFillMode, AnimationPlayState),
+    // TODO: TextDecorationThickness
+    ("text-decoration", text_decoration()) => text_decoration_value(TextDecorationLine, TextDecorationStyle, TextDecorationColor),
+}
 
-    ("overflow", overflow()) => (OverflowX, OverflowY),
-    ("outline", outline()) => (OutlineWidth, OutlineStyle, OutlineColor),
-    //"text-decoration" => ["text-decoration-color", "text-decoration-style", "text-decoration-line", "text-decoration-thickness"]
+fn serialize_shorthand(longhands: &[&StyleProp], serialize: fn(&[&StyleProp]) -> Option<String>) -> Option<String> {
+    // css-wide keywords can only be used if they are the same for all longhands
+    match longhands[0] {
+        StyleProp::Keyword(_, k) if longhands.iter().all(|p| p == &&StyleProp::Keyword(p.id(), *k)) => {
+            Some(k.to_string())
+        }
+        _ if longhands.iter().any(|p| matches!(p, StyleProp::Keyword(..))) => None,
+        _ => serialize(longhands),
+    }
+}
+
+fn spaced(longhands: &[&StyleProp]) -> Option<String> {
+    let values: Vec<_> = longhands.iter().map(|p| p.value_as_string()).collect();
+
+    Some(values.join(" "))
+}
+
+// all sides have to be the same
+fn same_sides(longhands: &[&StyleProp]) -> Option<String> {
+    let values: Vec<_> = longhands.iter().map(|p| p.value_as_string()).collect();
+
+    if values.chunks(3).any(|side| side != &values[0..3]) {
+        return None;
+    }
+
+    spaced(&longhands[0..3])
+}
+
+// image layers, color is part of the final one
+fn background_value(longhands: &[&StyleProp]) -> Option<String> {
+    let (color, images) = match longhands {
+        [StyleProp::BackgroundColor(c), StyleProp::BackgroundImage(images)] => (c, images),
+        _ => return None,
+    };
+    let mut layers: Vec<_> = images.0.iter().map(ToString::to_string).collect();
+    let last = layers.pop()?;
+
+    layers.push(match (last.as_str(), *color == CssColor::TRANSPARENT) {
+        ("none", false) => color.to_string(),
+        (_, true) => last,
+        (_, false) => format!("{} {}", last, color),
+    });
+
+    Some(layers.join(", "))
+}
+
+// [style] [weight] size[/line-height] family
+fn font_value(longhands: &[&StyleProp]) -> Option<String> {
+    let (style, weight, rest) = match longhands {
+        [style, weight, rest @ ..] => (style, weight, rest),
+        _ => return None,
+    };
+    let mut res = String::new();
+
+    if style != &&StyleProp::FontStyle(CssFontStyle::Normal) {
+        res.push_str(&format!("{} ", style.value_as_string()));
+    }
+
+    if weight != &&StyleProp::FontWeight(400) {
+        res.push_str(&format!("{} ", weight.value_as_string()));
+    }
+
+    match rest {
+        [size, StyleProp::LineHeight(CssDimension::Auto), family] => {
+            res.push_str(&format!("{} {}", size.value_as_string(), family.value_as_string()))
+        }
+        [size, line_height, family] => res.push_str(&format!(
+            "{}/{} {}",
+            size.value_as_string(),
+            line_height.value_as_string(),
+            family.value_as_string()
+        )),
+        _ => return None,
+    }
+
+    Some(res)
+}
+
+// initial values are omitted (but at least the line is always there)
+fn text_decoration_value(longhands: &[&StyleProp]) -> Option<String> {
+    let mut values = vec![longhands[0].value_as_string()];
+
+    if longhands[1] != &StyleProp::TextDecorationStyle(CssTextDecorationStyle::Solid) {
+        values.push(longhands[1].value_as_string());
+    }
+
+    if longhands[2] != &StyleProp::TextDecorationColor(CssColor::CurrentColor) {
+        values.push(longhands[2].value_as_string());
+    }
+
+    Some(values.join(" "))
+}
+
+// top right bottom left (or x y), collapsed to the shortest form
+fn sides(longhands: &[&StyleProp]) -> Option<String> {
+    let mut values: Vec<_> = longhands.iter().map(|p| p.value_as_string()).collect();
+
+    if values.len() == 4 && values[3] == values[1] {
+        values.pop();
+    }
+
+    if values.len() == 3 && values[2] == values[0] {
+        values.pop();
+    }
+
+    if values.len() == 2 && values[1] == values[0] {
+        values.pop();
+    }
+
+    Some(values.join(" "))
+}
+
+// comma-separated, all lists have to be of the same length
+fn lists(longhands: &[&StyleProp]) -> Option<String> {
+    let lists: Vec<Vec<String>> = longhands.iter().map(|p| list_items(p)).collect::<Option<_>>()?;
+
+    if lists.iter().any(|l| l.len() != lists[0].len()) {
+        return None;
+    }
+
+    let items: Vec<_> = (0..lists[0].len())
+        .map(|i| lists.iter().map(|l| &*l[i]).collect::<Vec<_>>().join(" "))
+        .collect();
+
+    Some(items.join(", "))
+}
+
+// name goes last so it can't be mistaken for a keyword
+fn animation_lists(longhands: &[&StyleProp]) -> Option<String> {
+    let mut longhands = longhands.to_vec();
+    longhands.rotate_left(1);
+
+    lists(&longhands)
+}
+
+fn list_items(prop: &StyleProp) -> Option<Vec<String>> {
+    macro_rules! items {
+        ($($variant:ident),*) => {
+            match prop {
+                $(StyleProp::$variant(l) => Some(l.0.iter().map(|v| v.to_string()).collect()),)*
+                _ => None
+            }
+        }
+    }
+
+    items!(
+        TransitionProperty,
+        TransitionDuration,
+        TransitionTimingFunction,
+        TransitionDelay,
+        AnimationName,
+        AnimationDuration,
+        AnimationTimingFunction,
+        AnimationDelay,
+        AnimationIterationCount,
+        AnimationDirection,
+        AnimationFillMode,
+        AnimationPlayState
+    )
 }
 
 #[cfg(test)]
@@ -205,4 +650,19 @@ mod tests {
         // TODO: gets broken when Atom<> or Box<> is added
         assert_eq!(size_of::<StyleProp>(), size_of::<(u8, CssDimension)>())
     }
+
+    #[test]
+    fn initial_value() {
+        for id in StylePropId::ALL {
+            match id {
+                StylePropId::BoxShadow => assert_eq!(id.initial_value(), None),
+                _ => assert_eq!(id.initial_value().map(|p| p.id()), Some(*id), "{}", id.name()),
+            }
+        }
+
+        assert_eq!(
+            StylePropId::BorderTopColor.initial_value(),
+            Some(StyleProp::BorderTopColor(CssColor::CurrentColor))
+        );
+    }
 }
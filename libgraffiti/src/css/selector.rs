@@ -13,6 +13,7 @@
 // x decoupled from other systems
 
 use crate::util::Atom;
+use std::fmt;
 
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct Selector {
@@ -33,7 +34,8 @@ pub(super) enum Component {
     Identifier(Atom<String>),
     ClassName(Atom<String>),
 
-    Unsupported,
+    // original text, so it can be serialized back
+    Unsupported(Atom<String>),
     // AttrExists(Atom<String>),
     // AttrEq(Atom<(Atom<String>, Atom<String>)>) // deref first, then compare both atoms
     // FirstChild // (prev_element_sibling == None)
@@ -54,25 +56,59 @@ pub(super) enum Combinator {
 }
 
 pub(crate) struct MatchingContext<'a, E> {
-    pub has_local_name: &'a dyn Fn(E, &Atom<String>) -> bool,
-    pub has_identifier: &'a dyn Fn(E, &Atom<String>) -> bool,
-    pub has_class: &'a dyn Fn(E, &Atom<String>) -> bool,
+    pub local_name: &'a dyn Fn(E) -> &'a str,
+    pub identifier: &'a dyn Fn(E) -> Option<&'a str>,
+    // whole attribute (space-separated)
+    pub class_name: &'a dyn Fn(E) -> Option<&'a str>,
     //has_pseudo_class: &'a dyn Fn(E, &Atom<String>) -> bool,
     pub parent: &'a dyn Fn(E) -> Option<E>,
 }
 
-impl<E: Copy> MatchingContext<'_, E> {
+impl<'a, E: Copy> MatchingContext<'a, E> {
     fn match_component(&self, component: &Component, el: E) -> bool {
         use Component::*;
 
         match component {
-            LocalName(name) => (self.has_local_name)(el, name),
-            Identifier(id) => (self.has_identifier)(el, id),
-            ClassName(cls) => (self.has_class)(el, cls),
-            Unsupported => false,
+            LocalName(name) => **name == *(self.local_name)(el),
+            Identifier(id) => Some(id.as_str()) == (self.identifier)(el),
+            ClassName(cls) => self.class_names(el).any(|c| **cls == *c),
+            Unsupported(_) => false,
         }
     }
 
+    pub fn class_names(&self, el: E) -> impl Iterator<Item = &'a str> {
+        (self.class_name)(el).unwrap_or("").split_ascii_whitespace()
+    }
+
+    // what the element can offer to descendant selectors
+    pub fn mask(&self, el: E) -> SelectorMask {
+        let mut mask = SelectorMask::default();
+        mask.add(b' ', (self.local_name)(el));
+
+        if let Some(id) = (self.identifier)(el) {
+            mask.add(b'#', id);
+        }
+
+        for cls in self.class_names(el) {
+            mask.add(b'.', cls);
+        }
+
+        mask
+    }
+
+    // usually it's cheaper to extend the mask of the parent
+    pub fn ancestor_mask(&self, el: E) -> SelectorMask {
+        let mut mask = SelectorMask::default();
+        let mut ancestor = (self.parent)(el);
+
+        while let Some(a) = ancestor {
+            mask = mask | self.mask(a);
+            ancestor = (self.parent)(a);
+        }
+
+        mask
+    }
+
     pub fn match_selector(&self, selector: &Selector, el: E) -> Option<u32> {
         // so we can fast-forward to next OR
         let mut parts_iter = selector.parts.iter();
@@ -140,6 +176,65 @@ impl<E: Copy> MatchingContext<'_, E> {
     }
 }
 
+impl Selector {
+    // parts are in reverse order so it's rightmost compound first
+    fn alternatives(&self) -> impl Iterator<Item = &[SelectorPart]> {
+        self.parts.split(|p| p == &SelectorPart::Combinator(Combinator::Or))
+    }
+
+    // id, class or local name of the rightmost compound, for each alternative
+    // (None means it has to be tested against every element)
+    pub(super) fn keys(&self) -> Vec<Option<&Component>> {
+        self.alternatives()
+            .map(|parts| {
+                parts
+                    .iter()
+                    .take_while(|p| !is_relation(p))
+                    .filter_map(|p| match p {
+                        SelectorPart::Component(c) => Some(c),
+                        _ => None,
+                    })
+                    .min_by_key(|c| match c {
+                        Component::Identifier(_) => 0,
+                        Component::ClassName(_) => 1,
+                        Component::LocalName(_) => 2,
+                        Component::Unsupported(_) => 3,
+                    })
+                    .filter(|c| !matches!(c, Component::Unsupported(_)))
+            })
+            .collect()
+    }
+
+    // what has to be present in ancestors (in any of the alternatives)
+    pub(super) fn ancestor_mask(&self) -> SelectorMask {
+        let mut masks = self.alternatives().map(|parts| {
+            let mut mask = SelectorMask::default();
+
+            for p in parts.iter().skip_while(|p| !is_relation(p)) {
+                match p {
+                    SelectorPart::Component(Component::LocalName(name)) => mask.add(b' ', name),
+                    SelectorPart::Component(Component::Identifier(id)) => mask.add(b'#', id),
+                    SelectorPart::Component(Component::ClassName(cls)) => mask.add(b'.', cls),
+                    _ => {}
+                }
+            }
+
+            mask
+        });
+
+        let first = masks.next().unwrap_or_default();
+
+        masks.fold(first, |res, mask| SelectorMask(res.0 & mask.0))
+    }
+}
+
+fn is_relation(part: &SelectorPart) -> bool {
+    matches!(
+        part,
+        SelectorPart::Combinator(Combinator::Parent) | SelectorPart::Combinator(Combinator::Ancestor)
+    )
+}
+
 // never fails
 impl From<&str> for Selector {
     fn from(selector: &str) -> Self {
@@ -147,42 +242,63 @@ impl From<&str> for Selector {
         let parser = super::parser::selector() - pom::parser::end();
 
         parser.parse(&tokens).unwrap_or(Selector {
-            parts: vec![SelectorPart::Component(Component::Unsupported)],
+            parts: vec![SelectorPart::Component(Component::Unsupported(selector.into()))],
         })
     }
 }
 
-/*
-pub struct SelectorMask(u32);
+impl fmt::Display for Selector {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        // parts are stored in reverse order
+        for p in self.parts.iter().rev() {
+            match p {
+                SelectorPart::Component(Component::LocalName(name)) => write!(f, "{}", name)?,
+                SelectorPart::Component(Component::Identifier(id)) => write!(f, "#{}", id)?,
+                SelectorPart::Component(Component::ClassName(cls)) => write!(f, ".{}", cls)?,
+                SelectorPart::Component(Component::Unsupported(text)) => write!(f, "{}", text)?,
+                SelectorPart::Combinator(Combinator::Universal) => write!(f, "*")?,
+                SelectorPart::Combinator(Combinator::Parent) => write!(f, " > ")?,
+                SelectorPart::Combinator(Combinator::Ancestor) => write!(f, " ")?,
+                SelectorPart::Combinator(Combinator::Or) => write!(f, ", ")?,
+            }
+        }
+
+        Ok(())
+    }
+}
+
+// bloom filter of ids/classes/local names, so descendant selectors can be
+// rejected without walking up the tree (false positives are matched as usual)
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub(crate) struct SelectorMask(u64);
 
 impl SelectorMask {
-    const BITS: usize = core::mem::size_of::<usize>() * 8;
+    const BITS: u64 = 64;
 
     pub fn includes(&self, other: SelectorMask) -> bool {
-        (self.0 & other.0) != 0
+        (self.0 & other.0) == other.0
     }
-}
 
-impl<'a, T: IntoIterator<Item = &'a Atom<Tag>>> From<T> for SelectorMask {
-    fn from(tags: T) -> Self {
-        use std::hash::{Hash, Hasher};
+    // kind is there so that #foo, .foo and foo are different
+    fn add(&mut self, kind: u8, name: &str) {
+        // FNV-1a
+        let hash = std::iter::once(kind)
+            .chain(name.bytes())
+            .fold(0xcbf29ce484222325_u64, |h, b| {
+                (h ^ b as u64).wrapping_mul(0x100000001b3)
+            });
 
-        // TODO: maybe it could be oneliner too (fold)
-        let hash = |tag: &Tag| {
-            let mut hasher = fnv::FnvHasher::default();
-            tag.hash(&mut hasher);
-            hasher.finish()
-        };
+        self.0 |= 1 << (hash % Self::BITS);
+    }
+}
+
+impl std::ops::BitOr for SelectorMask {
+    type Output = Self;
 
-        // TODO: test
-        // TODO: zero?
-        Self(
-            tags.into_iter()
-                .fold(0, |res, t| res | 1 << (hash(t) as usize - 1) % Self::BITS),
-        )
+    fn bitor(self, other: Self) -> Self {
+        Self(self.0 | other.0)
     }
 }
-*/
 
 #[cfg(test)]
 mod tests {
@@ -206,9 +322,9 @@ mod tests {
         let parents = &vec![None, Some(0), Some(1), Some(2), Some(3)];
 
         let ctx = MatchingContext {
-            has_local_name: &|e, n| **n == local_names[e],
-            has_identifier: &|e, id| **id == ids[e],
-            has_class: &|e, cls| **cls == class_names[e],
+            local_name: &|e| local_names[e],
+            identifier: &|e| Some(ids[e]).filter(|id: &&str| !id.is_empty()),
+            class_name: &|e| Some(class_names[e]),
             parent: &|e| parents[e],
         };
 
@@ -251,4 +367,44 @@ mod tests {
         assert!(match_sel("div, span.foo, #panel span", 4));
         assert!(match_sel("a b c d e f g, span", 4));
     }
+
+    #[test]
+    fn keys() {
+        let keys = |s| -> Vec<_> {
+            let sel = Selector::from(s);
+            let res = sel.keys().iter().map(|k| k.map(|c| c.clone())).collect();
+            res
+        };
+
+        assert_eq!(keys("*"), vec![None]);
+        assert_eq!(keys("div"), vec![Some(Component::LocalName("div".into()))]);
+        assert_eq!(keys("div.btn#app"), vec![Some(Component::Identifier("app".into()))]);
+        assert_eq!(keys("#app div.btn"), vec![Some(Component::ClassName("btn".into()))]);
+        // reversed
+        assert_eq!(keys("a, :hover"), vec![None, Some(Component::LocalName("a".into()))]);
+    }
+
+    #[test]
+    fn mask() {
+        let local_names = &vec!["html", "body", "div"];
+        let parents = &vec![None, Some(0), Some(1)];
+
+        let ctx = MatchingContext {
+            local_name: &|e| local_names[e],
+            identifier: &|e| Some("app").filter(|_| e == 1),
+            class_name: &|e| Some("a b").filter(|_| e == 1),
+            parent: &|e| parents[e],
+        };
+
+        let ancestors = ctx.ancestor_mask(2);
+        let includes = |s| ancestors.includes(Selector::from(s).ancestor_mask());
+
+        assert_eq!(ancestors, ctx.mask(0) | ctx.mask(1));
+        assert!(includes("div"));
+        assert!(includes("body div"));
+        assert!(includes("html > body#app.b div"));
+        assert!(includes("span div, .a div"));
+        assert!(!includes("main div"));
+        assert!(!includes(".c div"));
+    }
 }
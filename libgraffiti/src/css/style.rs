@@ -1,11 +1,9 @@
 // TODO:
-// - shorthands
 // - normalize (bold -> 700)
 
-use super::StyleProp;
+use super::{CssColor, CssDimension, CssUnits, CssWideKeyword, StyleProp, StylePropId};
 use once_cell::sync::Lazy;
 use std::fmt::Write;
-use std::mem::discriminant;
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct Style {
@@ -43,6 +41,10 @@ impl Style {
         self.props.iter().find(|p| p.name() == prop)
     }
 
+    pub fn find_prop(&self, id: StylePropId) -> Option<&StyleProp> {
+        self.props.iter().find(|p| p.id() == id)
+    }
+
     // TODO: priority
     pub fn set_property(&mut self, prop: &str, value: &str) {
         let tokens = super::parser::tokenize(value.as_bytes());
@@ -52,14 +54,49 @@ impl Style {
 
     // TODO: should return previous value
     pub fn remove_property(&mut self, prop: &str) {
-        self.props.retain(|p| p.name() == prop);
+        match super::shorthand_longhands(prop) {
+            Some(ids) => self.props.retain(|p| !ids.contains(&p.id())),
+            None => self.props.retain(|p| p.name() != prop),
+        }
     }
 
+    // longhands are replaced with shorthands if possible
     pub fn css_text(&self) -> String {
-        self.props().fold(String::new(), |mut s, p| {
-            write!(s, "{}: {};", p.name(), p.value_as_string());
-            s
-        })
+        let mut res = String::new();
+        let mut done = vec![false; self.props.len()];
+
+        for (i, p) in self.props.iter().enumerate() {
+            if done[i] {
+                continue;
+            }
+
+            // the biggest one which is complete & wasn't used yet
+            let shorthand = super::SHORTHANDS
+                .iter()
+                .filter_map(|&name| Some((name, super::shorthand_longhands(name)?)))
+                .filter(|(_, ids)| ids.len() > 1 && ids.contains(&p.id()))
+                .filter(|(_, ids)| {
+                    ids.iter()
+                        .all(|id| self.props.iter().zip(&done).any(|(p, d)| p.id() == *id && !d))
+                })
+                .filter_map(|(name, ids)| Some((name, ids, self.shorthand_value(name)?)))
+                .max_by_key(|(_, ids, _)| ids.len());
+
+            match shorthand {
+                Some((name, ids, value)) => {
+                    write!(res, "{}: {};", name, value).unwrap();
+
+                    for (p, d) in self.props.iter().zip(&mut done) {
+                        *d |= ids.contains(&p.id());
+                    }
+                }
+                None => {
+                    write!(res, "{}: {};", p.name(), p.value_as_string()).unwrap();
+                }
+            }
+        }
+
+        res
     }
 
     pub fn set_css_text(&mut self, css_text: &str) {
@@ -70,15 +107,127 @@ impl Style {
         self.props.iter()
     }
 
+    // resolve relative units (em, rem, vw, vh, vmin, vmax) to px,
+    // font-size goes first (relative to the parent) so that em can be used for the rest
+    // returns the resulting font-size
+    pub fn resolve_units(&mut self, parent_font_size: f32, root_font_size: f32, viewport_size: (f32, f32)) -> f32 {
+        let mut units = CssUnits::new(parent_font_size, root_font_size, viewport_size);
+        let mut font_size = parent_font_size;
+
+        for p in &mut self.props {
+            if let StyleProp::FontSize(v) = p {
+                *v = match v.resolve_units(&units) {
+                    CssDimension::Percent(v) => CssDimension::Px(parent_font_size * v / 100.),
+                    CssDimension::Calc(calc) => CssDimension::Px(calc.resolve(parent_font_size)),
+                    v => v,
+                };
+
+                if let CssDimension::Px(v) = v {
+                    font_size = *v;
+                }
+            }
+        }
+
+        units.em = font_size;
+
+        for p in &mut self.props {
+            p.resolve_units(&units);
+        }
+
+        font_size
+    }
+
     pub fn add_prop(&mut self, new_prop: StyleProp) {
-        let d = discriminant(&new_prop);
+        let id = new_prop.id();
 
-        if let Some(existing) = self.props.iter_mut().find(|p| d == discriminant(p)) {
+        if let Some(existing) = self.props.iter_mut().find(|p| id == p.id()) {
             *existing = new_prop;
         } else {
             self.props.push(new_prop);
         }
     }
+
+    fn remove_prop(&mut self, id: StylePropId) {
+        self.props.retain(|p| p.id() != id);
+    }
+
+    // turn cascaded style into computed style:
+    // - inherited props are taken from the parent (unless overridden)
+    // - css-wide keywords are resolved
+    // - initial values are not stored (it's up to the consumer, layout/renderer/...)
+    // - revert goes back to the user-agent style (which is only computed if needed)
+    pub fn compute(&mut self, parent: Option<&Style>, ua_style: impl FnOnce() -> Style) {
+        use CssWideKeyword::*;
+
+        let mut res = Style::new();
+        let mut ua_style = Some(ua_style);
+        let mut ua = None;
+
+        if let Some(parent) = parent {
+            for p in parent.props().filter(|p| p.id().is_inherited()) {
+                res.add_prop(p.clone());
+            }
+        }
+
+        for p in self.props.drain(..) {
+            let (id, mut keyword) = match p {
+                StyleProp::Keyword(id, keyword) => (id, keyword),
+                _ => {
+                    res.add_prop(p);
+                    continue;
+                }
+            };
+
+            if keyword == Revert {
+                let ua: &Style = ua.get_or_insert_with(|| (ua_style.take().unwrap())());
+
+                match ua.find_prop(id) {
+                    Some(StyleProp::Keyword(_, Revert)) | None => keyword = Unset,
+                    Some(StyleProp::Keyword(_, k)) => keyword = *k,
+                    Some(p) => {
+                        res.add_prop(p.clone());
+                        continue;
+                    }
+                }
+            }
+
+            if keyword == Unset {
+                keyword = if id.is_inherited() { Inherit } else { Initial };
+            }
+
+            match parent.and_then(|p| p.find_prop(id)) {
+                Some(p) if keyword == Inherit => res.add_prop(p.clone()),
+                _ => res.remove_prop(id),
+            }
+        }
+
+        // color: currentColor is the same as inherit
+        if let Some(StyleProp::Color(CssColor::CurrentColor)) = res.find_prop(StylePropId::Color) {
+            match parent.and_then(|p| p.find_prop(StylePropId::Color)) {
+                Some(p) => res.add_prop(p.clone()),
+                None => res.remove_prop(StylePropId::Color),
+            }
+        }
+
+        *self = res;
+    }
+
+    // black is the initial value
+    pub fn current_color(&self) -> CssColor {
+        match self.find_prop(StylePropId::Color) {
+            Some(StyleProp::Color(c)) => *c,
+            _ => CssColor::BLACK,
+        }
+    }
+
+    // separate from compute() because color can be animated/transitioned too
+    pub fn resolve_current_color(&mut self) {
+        let current_color = self.current_color();
+
+        for p in &mut self.props {
+            p.resolve_current_color(current_color);
+        }
+    }
 }
 
 // never fails
@@ -101,7 +250,67 @@ mod tests {
         let mut s = Style::new();
 
         s.set_css_text("display: block;");
-        assert_eq!(&s.css_text(), "display: block;")
+        assert_eq!(&s.css_text(), "display: block;");
+
+        s.set_css_text("margin: 0; padding: 1px 2px 3px; margin-left: 4px");
+        assert_eq!(&s.css_text(), "margin: 0px 0px 0px 4px;padding: 1px 2px 3px;");
+
+        s.set_css_text("margin-top: 0; display: block; overflow: hidden; flex: 1");
+        assert_eq!(
+            &s.css_text(),
+            "margin-top: 0px;display: block;overflow: hidden;flex: 1 1 auto;"
+        );
+
+        s.set_css_text("padding: inherit; border-width: 1px; border-top-width: inherit");
+        assert_eq!(
+            &s.css_text(),
+            "padding: inherit;border-top-width: inherit;border-right-width: 1px;border-bottom-width: 1px;border-left-width: 1px;"
+        );
+    }
+
+    #[test]
+    fn border_and_font() {
+        let mut s = Style::from("border: 1px solid #000; font: 12px/1em serif");
+        assert_eq!(
+            &s.css_text(),
+            "border: 1px solid rgba(0, 0, 0, 255);font: 12px/1em serif;"
+        );
+
+        s.set_property("border-left-style", "dotted");
+        s.set_property("line-height", "normal");
+        assert_eq!(
+            &s.css_text(),
+            "border-width: 1px;border-style: solid solid solid dotted;border-color: rgba(0, 0, 0, 255);font: 12px serif;"
+        );
+    }
+
+    #[test]
+    fn shorthand_value() {
+        let s = Style::from("border-radius: 1px 2px 3px; outline: 1px solid #000; transition-property: a, b");
+
+        assert_eq!(s.shorthand_value("border-radius"), Some("1px 2px 3px".into()));
+        assert_eq!(
+            s.shorthand_value("outline"),
+            Some("1px solid rgba(0, 0, 0, 255)".into())
+        );
+        assert_eq!(s.shorthand_value("margin"), None);
+        assert_eq!(s.shorthand_value("transition"), None);
+        assert_eq!(s.property_value("border-radius"), Some("1px 2px 3px".into()));
+
+        let s = Style::from("gap: 10px 5%");
+
+        assert_eq!(s.property_value("row-gap"), Some("10px".into()));
+        assert_eq!(s.property_value("column-gap"), Some("5%".into()));
+        assert_eq!(s.shorthand_value("gap"), Some("10px 5%".into()));
+        assert_eq!(Style::from("gap: 2px").shorthand_value("gap"), Some("2px".into()));
+
+        let s = Style::from("font: italic 700 12px serif; text-decoration: dashed underline overline");
+
+        assert_eq!(s.shorthand_value("font"), Some("italic 700 12px serif".into()));
+        assert_eq!(
+            s.shorthand_value("text-decoration"),
+            Some("underline overline dashed".into())
+        );
     }
 
     #[test]
@@ -113,4 +322,96 @@ mod tests {
 
         assert!(Iterator::eq(s.props(), &vec![StyleProp::Display(CssDisplay::Block)]));
     }
+
+    #[test]
+    fn remove_property() {
+        let mut s = Style::from("display: block; color: #000; opacity: 0.5");
+
+        s.remove_property("color");
+        assert_eq!(&s.css_text(), "display: block;opacity: 0.5;");
+
+        s.remove_property("float");
+        assert_eq!(&s.css_text(), "display: block;opacity: 0.5;");
+
+        let mut s = Style::from("margin: 10px; margin-left: 5px; padding: 5px");
+
+        s.remove_property("margin");
+        assert_eq!(&s.css_text(), "padding: 5px;");
+    }
+
+    #[test]
+    fn compute() {
+        let parent = Style::from("color: #f00; font-size: 20px; display: flex; padding: 10px");
+        let ua = || Style::from("display: block; color: #0f0");
+        let computed = |s, parent| {
+            let mut s = Style::from(s);
+            s.compute(parent, ua);
+            s.resolve_current_color();
+            s.css_text()
+        };
+
+        // inherited props
+        assert_eq!(
+            computed("", Some(&parent)),
+            "color: rgba(255, 0, 0, 255);font-size: 20px;"
+        );
+        assert_eq!(
+            computed("color: #000", Some(&parent)),
+            "color: rgba(0, 0, 0, 255);font-size: 20px;"
+        );
+
+        // keywords
+        assert_eq!(
+            computed("display: inherit; padding-top: inherit", Some(&parent)),
+            "color: rgba(255, 0, 0, 255);font-size: 20px;display: flex;padding-top: 10px;"
+        );
+        assert_eq!(computed("color: initial", Some(&parent)), "font-size: 20px;");
+        assert_eq!(
+            computed("color: unset; display: unset", Some(&parent)),
+            "color: rgba(255, 0, 0, 255);font-size: 20px;"
+        );
+        assert_eq!(
+            computed("color: revert; display: revert; padding-top: revert", Some(&parent)),
+            "color: rgba(0, 255, 0, 255);font-size: 20px;display: block;"
+        );
+
+        // root
+        assert_eq!(computed("color: inherit; display: flex", None), "display: flex;");
+
+        // currentColor
+        assert_eq!(
+            computed("color: #00f; outline-color: currentColor", Some(&parent)),
+            "color: rgba(0, 0, 255, 255);font-size: 20px;outline-color: rgba(0, 0, 255, 255);"
+        );
+        assert_eq!(
+            computed("color: currentColor; background-color: currentcolor", Some(&parent)),
+            "color: rgba(255, 0, 0, 255);font-size: 20px;background-color: rgba(255, 0, 0, 255);"
+        );
+        assert_eq!(
+            computed("background-color: currentColor", None),
+            "background-color: rgba(0, 0, 0, 255);"
+        );
+    }
+
+    #[test]
+    fn resolve_units() {
+        let mut s = Style::from("font-size: 2em; padding: 1em 1rem; width: 50vw; height: 10vh; min-width: 10vmin");
+
+        assert_eq!(s.resolve_units(10., 16., (800., 600.)), 20.);
+        assert_eq!(
+            s.css_text(),
+            "font-size: 20px;padding: 20px 16px;width: 400px;height: 60px;min-width: 60px;"
+        );
+
+        let mut s = Style::from("font-size: 150%; width: calc(100% - 2em); height: calc(50vh + 1rem)");
+
+        assert_eq!(s.resolve_units(10., 16., (800., 600.)), 15.);
+        assert_eq!(s.css_text(), "font-size: 15px;width: calc(100% - 30px);height: 316px;");
+
+        // inherited
+        let mut s = Style::from("width: 2em");
+
+        assert_eq!(s.resolve_units(10., 16., (800., 600.)), 10.);
+        assert_eq!(s.css_text(), "width: 20px;");
+    }
 }
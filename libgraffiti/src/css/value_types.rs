@@ -83,6 +83,47 @@ css_enums! {
         Outset = "outset",
     }
 
+    // https://drafts.csswg.org/css-ui-3/#cursor
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub enum CssCursor {
+        Auto = "auto",
+        Default = "default",
+        None = "none",
+        ContextMenu = "context-menu",
+        Help = "help",
+        Pointer = "pointer",
+        Progress = "progress",
+        Wait = "wait",
+        Cell = "cell",
+        Crosshair = "crosshair",
+        Text = "text",
+        VerticalText = "vertical-text",
+        Alias = "alias",
+        Copy = "copy",
+        Move = "move",
+        NoDrop = "no-drop",
+        NotAllowed = "not-allowed",
+        Grab = "grab",
+        Grabbing = "grabbing",
+        EResize = "e-resize",
+        NResize = "n-resize",
+        NeResize = "ne-resize",
+        NwResize = "nw-resize",
+        SResize = "s-resize",
+        SeResize = "se-resize",
+        SwResize = "sw-resize",
+        WResize = "w-resize",
+        EwResize = "ew-resize",
+        NsResize = "ns-resize",
+        NeswResize = "nesw-resize",
+        NwseResize = "nwse-resize",
+        ColResize = "col-resize",
+        RowResize = "row-resize",
+        AllScroll = "all-scroll",
+        ZoomIn = "zoom-in",
+        ZoomOut = "zoom-out",
+    }
+
     #[derive(Debug, Clone, Copy, PartialEq, Eq)]
     pub enum CssDisplay {
         None = "none",
@@ -106,6 +147,13 @@ css_enums! {
         WrapReverse = "wrap-reverse",
     }
 
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub enum CssFontStyle {
+        Normal = "normal",
+        Italic = "italic",
+        Oblique = "oblique",
+    }
+
     #[derive(Debug, Clone, Copy, PartialEq, Eq)]
     pub enum CssOverflow {
         Visible = "visible",
@@ -114,6 +162,13 @@ css_enums! {
         Auto = "auto",
     }
 
+    // SVG values are not supported
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub enum CssPointerEvents {
+        Auto = "auto",
+        None = "none",
+    }
+
     #[derive(Debug, Clone, Copy, PartialEq, Eq)]
     pub enum CssPosition {
         Static = "static",
@@ -122,6 +177,15 @@ css_enums! {
         Sticky = "sticky",
     }
 
+    // https://drafts.csswg.org/css-images-3/#typedef-radial-extent
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub enum CssRadialExtent {
+        ClosestSide = "closest-side",
+        ClosestCorner = "closest-corner",
+        FarthestSide = "farthest-side",
+        FarthestCorner = "farthest-corner",
+    }
+
     #[derive(Debug, Clone, Copy, PartialEq, Eq)]
     pub enum CssTextAlign {
         Left = "left",
@@ -130,20 +194,86 @@ css_enums! {
         Justify = "justify",
     }
 
+    // https://drafts.csswg.org/css-text-decor-3/#text-decoration-style-property
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub enum CssTextDecorationStyle {
+        Solid = "solid",
+        Double = "double",
+        Dotted = "dotted",
+        Dashed = "dashed",
+        Wavy = "wavy",
+    }
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub enum CssTextTransform {
+        None = "none",
+        Capitalize = "capitalize",
+        Uppercase = "uppercase",
+        Lowercase = "lowercase",
+    }
+
     #[derive(Debug, Clone, Copy, PartialEq, Eq)]
     pub enum CssVisibility {
         Visible = "visible",
         Hidden = "hidden",
         Collapse = "collapse",
     }
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub enum CssWhiteSpace {
+        Normal = "normal",
+        NoWrap = "nowrap",
+        Pre = "pre",
+        PreWrap = "pre-wrap",
+        PreLine = "pre-line",
+    }
+
+    // https://drafts.csswg.org/css-cascade-4/#defaulting-keywords
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub enum CssWideKeyword {
+        Inherit = "inherit",
+        Initial = "initial",
+        Unset = "unset",
+        Revert = "revert",
+    }
+
+    // https://drafts.csswg.org/css-easing-1/#step-position
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub enum CssStepPosition {
+        JumpStart = "jump-start",
+        JumpEnd = "jump-end",
+        JumpNone = "jump-none",
+        JumpBoth = "jump-both",
+    }
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub enum CssAnimationDirection {
+        Normal = "normal",
+        Reverse = "reverse",
+        Alternate = "alternate",
+        AlternateReverse = "alternate-reverse",
+    }
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub enum CssAnimationFillMode {
+        None = "none",
+        Forwards = "forwards",
+        Backwards = "backwards",
+        Both = "both",
+    }
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub enum CssAnimationPlayState {
+        Running = "running",
+        Paused = "paused",
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub struct CssColor {
-    pub r: u8,
-    pub g: u8,
-    pub b: u8,
-    pub a: u8,
+pub enum CssColor {
+    Rgba(u8, u8, u8, u8),
+    // resolved to the computed `color` in Style::compute()
+    CurrentColor,
 }
 
 impl CssColor {
@@ -315,28 +445,97 @@ impl CssColor {
     }
 
     pub(super) const fn from_rgba8(r: u8, g: u8, b: u8, a: u8) -> Self {
-        Self { r, g, b, a }
+        Self::Rgba(r, g, b, a)
+    }
+
+    // h in degrees, s & l in 0..1
+    pub(super) fn from_hsla(h: f32, s: f32, l: f32, a: u8) -> Self {
+        // https://drafts.csswg.org/css-color/#hsl-to-rgb
+        let f = |n: f32| {
+            let k = (n + h / 30.).rem_euclid(12.);
+            let a = s * l.min(1. - l);
+
+            l - a * (k - 3.).min(9. - k).clamp(-1., 1.)
+        };
+
+        Self::from_rgba8(to_u8(f(0.)), to_u8(f(8.)), to_u8(f(4.)), a)
+    }
+
+    // h in degrees, w & b in 0..1
+    pub(super) fn from_hwba(h: f32, w: f32, b: f32, a: u8) -> Self {
+        // https://drafts.csswg.org/css-color/#hwb-to-rgb
+        if w + b >= 1. {
+            let gray = to_u8(w / (w + b));
+
+            return Self::from_rgba8(gray, gray, gray, a);
+        }
+
+        match Self::from_hsla(h, 1., 0.5, a) {
+            Self::Rgba(r, g, bl, a) => {
+                let f = |c: u8| to_u8(c as f32 / 255. * (1. - w - b) + w);
+
+                Self::from_rgba8(f(r), f(g), f(bl), a)
+            }
+            c => c,
+        }
     }
+
+    // should be resolved by now (but black is the initial value of `color` anyway)
+    pub fn to_rgba8(&self) -> [u8; 4] {
+        match *self {
+            Self::Rgba(r, g, b, a) => [r, g, b, a],
+            Self::CurrentColor => [0, 0, 0, 255],
+        }
+    }
+}
+
+// 0..1 to 0..255
+fn to_u8(v: f32) -> u8 {
+    (v.clamp(0., 1.) * 255.).round() as u8
 }
 
 impl Display for CssColor {
     fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), Error> {
-        let Self { r, g, b, a } = self;
-        write!(f, "rgba({}, {}, {}, {})", r, g, b, a)
+        match self {
+            Self::Rgba(r, g, b, a) => write!(f, "rgba({}, {}, {}, {})", r, g, b, a),
+            Self::CurrentColor => write!(f, "currentcolor"),
+        }
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum CssDimension {
     Auto,
     Px(f32),
     Percent(f32),
-    //Vw(f32)
-    //Vh(f32)
+    Em(f32),
+    Rem(f32),
+    Vw(f32),
+    Vh(f32),
+    Vmin(f32),
+    Vmax(f32),
+    Calc(Box<CssCalc>),
 }
 
 impl CssDimension {
     pub const ZERO: Self = Self::Px(0.);
+
+    // relative units to px (% is left for layout)
+    pub fn resolve_units(&self, units: &CssUnits) -> Self {
+        match *self {
+            Self::Em(v) => Self::Px(v * units.em),
+            Self::Rem(v) => Self::Px(v * units.rem),
+            Self::Vw(v) => Self::Px(v * units.vw),
+            Self::Vh(v) => Self::Px(v * units.vh),
+            Self::Vmin(v) => Self::Px(v * units.vw.min(units.vh)),
+            Self::Vmax(v) => Self::Px(v * units.vw.max(units.vh)),
+            Self::Calc(ref calc) => match calc.resolve_units(units) {
+                calc if calc.has_percent() => Self::Calc(Box::new(calc)),
+                calc => Self::Px(calc.resolve(0.)),
+            },
+            _ => self.clone(),
+        }
+    }
 }
 
 impl Display for CssDimension {
@@ -345,12 +544,260 @@ impl Display for CssDimension {
             Self::Auto => write!(f, "auto"),
             Self::Px(v) => write!(f, "{}px", v),
             Self::Percent(v) => write!(f, "{}%", v),
+            Self::Em(v) => write!(f, "{}em", v),
+            Self::Rem(v) => write!(f, "{}rem", v),
+            Self::Vw(v) => write!(f, "{}vw", v),
+            Self::Vh(v) => write!(f, "{}vh", v),
+            Self::Vmin(v) => write!(f, "{}vmin", v),
+            Self::Vmax(v) => write!(f, "{}vmax", v),
+            Self::Calc(calc) => write!(f, "{}", calc),
+        }
+    }
+}
+
+// px size of each relative unit (1em, 1rem, 1vw, 1vh)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CssUnits {
+    pub em: f32,
+    pub rem: f32,
+    pub vw: f32,
+    pub vh: f32,
+}
+
+impl CssUnits {
+    pub fn new(font_size: f32, root_font_size: f32, viewport_size: (f32, f32)) -> Self {
+        Self {
+            em: font_size,
+            rem: root_font_size,
+            vw: viewport_size.0 / 100.,
+            vh: viewport_size.1 / 100.,
+        }
+    }
+}
+
+// calc(), min(), max() & clamp()
+// x px & % can be mixed so it can't be resolved until layout
+// x mul/div is only allowed with plain numbers
+#[derive(Debug, Clone, PartialEq)]
+pub enum CssCalc {
+    Px(f32),
+    Percent(f32),
+    Em(f32),
+    Rem(f32),
+    Vw(f32),
+    Vh(f32),
+    Vmin(f32),
+    Vmax(f32),
+    Add(Box<Self>, Box<Self>),
+    Sub(Box<Self>, Box<Self>),
+    Mul(Box<Self>, f32),
+    Div(Box<Self>, f32),
+    Min(Vec<Self>),
+    Max(Vec<Self>),
+    Clamp(Box<(Self, Self, Self)>),
+}
+
+impl CssCalc {
+    // percentages are relative to the base (usually containing block width/height),
+    // relative units have to be resolved first (see resolve_units())
+    pub fn resolve(&self, base: f32) -> f32 {
+        match self {
+            Self::Px(v) => *v,
+            Self::Percent(v) => base * v / 100.,
+            Self::Em(_) | Self::Rem(_) | Self::Vw(_) | Self::Vh(_) | Self::Vmin(_) | Self::Vmax(_) => {
+                debug_assert!(false, "unresolved {}", self);
+                0.
+            }
+            Self::Add(a, b) => a.resolve(base) + b.resolve(base),
+            Self::Sub(a, b) => a.resolve(base) - b.resolve(base),
+            Self::Mul(a, n) => a.resolve(base) * n,
+            Self::Div(a, n) => a.resolve(base) / n,
+            Self::Min(args) => args.iter().map(|a| a.resolve(base)).fold(f32::INFINITY, f32::min),
+            Self::Max(args) => args.iter().map(|a| a.resolve(base)).fold(f32::NEG_INFINITY, f32::max),
+            Self::Clamp(args) => {
+                let (min, v, max) = &**args;
+                v.resolve(base).min(max.resolve(base)).max(min.resolve(base))
+            }
+        }
+    }
+
+    pub fn resolve_units(&self, units: &CssUnits) -> Self {
+        let r = |calc: &Self| Box::new(calc.resolve_units(units));
+
+        match self {
+            Self::Em(v) => Self::Px(v * units.em),
+            Self::Rem(v) => Self::Px(v * units.rem),
+            Self::Vw(v) => Self::Px(v * units.vw),
+            Self::Vh(v) => Self::Px(v * units.vh),
+            Self::Vmin(v) => Self::Px(v * units.vw.min(units.vh)),
+            Self::Vmax(v) => Self::Px(v * units.vw.max(units.vh)),
+            Self::Add(a, b) => Self::Add(r(a), r(b)),
+            Self::Sub(a, b) => Self::Sub(r(a), r(b)),
+            Self::Mul(a, n) => Self::Mul(r(a), *n),
+            Self::Div(a, n) => Self::Div(r(a), *n),
+            Self::Min(args) => Self::Min(args.iter().map(|a| a.resolve_units(units)).collect()),
+            Self::Max(args) => Self::Max(args.iter().map(|a| a.resolve_units(units)).collect()),
+            Self::Clamp(args) => Self::Clamp(Box::new((
+                args.0.resolve_units(units),
+                args.1.resolve_units(units),
+                args.2.resolve_units(units),
+            ))),
+            _ => self.clone(),
+        }
+    }
+
+    pub fn has_percent(&self) -> bool {
+        match self {
+            Self::Percent(_) => true,
+            Self::Add(a, b) | Self::Sub(a, b) => a.has_percent() || b.has_percent(),
+            Self::Mul(a, _) | Self::Div(a, _) => a.has_percent(),
+            Self::Min(args) | Self::Max(args) => args.iter().any(Self::has_percent),
+            Self::Clamp(args) => args.0.has_percent() || args.1.has_percent() || args.2.has_percent(),
+            _ => false,
+        }
+    }
+
+    fn fmt_inner(&self, f: &mut Formatter<'_>) -> Result<(), Error> {
+        match self {
+            Self::Px(v) => write!(f, "{}px", v),
+            Self::Percent(v) => write!(f, "{}%", v),
+            Self::Em(v) => write!(f, "{}em", v),
+            Self::Rem(v) => write!(f, "{}rem", v),
+            Self::Vw(v) => write!(f, "{}vw", v),
+            Self::Vh(v) => write!(f, "{}vh", v),
+            Self::Vmin(v) => write!(f, "{}vmin", v),
+            Self::Vmax(v) => write!(f, "{}vmax", v),
+            Self::Add(a, b) => {
+                a.fmt_inner(f)?;
+                write!(f, " + ")?;
+                b.fmt_inner(f)
+            }
+            Self::Sub(a, b) => {
+                a.fmt_inner(f)?;
+                write!(f, " - ")?;
+                b.fmt_inner(f)
+            }
+            Self::Mul(a, n) | Self::Div(a, n) => {
+                let op = if let Self::Mul(..) = self { "*" } else { "/" };
+
+                match **a {
+                    Self::Add(..) | Self::Sub(..) => {
+                        write!(f, "(")?;
+                        a.fmt_inner(f)?;
+                        write!(f, ")")?;
+                    }
+                    _ => a.fmt_inner(f)?,
+                }
+
+                write!(f, " {} {}", op, n)
+            }
+            Self::Min(_) | Self::Max(_) | Self::Clamp(_) => write!(f, "{}", self),
         }
     }
 }
 
+impl Display for CssCalc {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), Error> {
+        let (name, args): (_, Vec<_>) = match self {
+            Self::Min(args) => ("min", args.iter().collect()),
+            Self::Max(args) => ("max", args.iter().collect()),
+            Self::Clamp(args) => ("clamp", vec![&args.0, &args.1, &args.2]),
+            _ => ("calc", vec![self]),
+        };
+
+        write!(f, "{}(", name)?;
+
+        for (i, a) in args.into_iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+
+            a.fmt_inner(f)?;
+        }
+
+        write!(f, ")")
+    }
+}
+
 // TODO: Border (border-spacing/collapse is shared for all sides so it can't be array)
 
+// TODO: url()
+#[derive(Debug, Clone, PartialEq)]
+pub enum CssImage {
+    None,
+    Gradient(Box<CssGradient>),
+}
+
+impl Display for CssImage {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), Error> {
+        match self {
+            Self::None => write!(f, "none"),
+            Self::Gradient(g) => write!(f, "{}", g),
+        }
+    }
+}
+
+// https://drafts.csswg.org/css-images-3/#gradients
+// TODO: repeating-*, color hints, two-position stops
+#[derive(Debug, Clone, PartialEq)]
+pub struct CssGradient {
+    pub shape: CssGradientShape,
+    pub stops: Vec<CssColorStop>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum CssGradientShape {
+    Linear(CssGradientDirection),
+    // (circle, extent, center)
+    Radial(bool, CssRadialExtent, CssOrigin),
+    // (from angle, center)
+    Conic(f32, CssOrigin),
+}
+
+// angles are in degrees, 0 is up (to top)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CssGradientDirection {
+    Angle(f32),
+    // (right, bottom), the angle depends on the box size
+    Corner(bool, bool),
+}
+
+// position is along the gradient line (or % of the full turn for conic)
+#[derive(Debug, Clone, PartialEq)]
+pub struct CssColorStop(pub CssColor, pub Option<CssDimension>);
+
+impl Display for CssGradient {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), Error> {
+        match &self.shape {
+            CssGradientShape::Linear(CssGradientDirection::Angle(a)) => write!(f, "linear-gradient({}deg", a)?,
+            CssGradientShape::Linear(CssGradientDirection::Corner(right, bottom)) => write!(
+                f,
+                "linear-gradient(to {} {}",
+                if *bottom { "bottom" } else { "top" },
+                if *right { "right" } else { "left" }
+            )?,
+            CssGradientShape::Radial(circle, extent, at) => write!(
+                f,
+                "radial-gradient({} {} at {}",
+                if *circle { "circle" } else { "ellipse" },
+                extent,
+                at
+            )?,
+            CssGradientShape::Conic(from, at) => write!(f, "conic-gradient(from {}deg at {}", from, at)?,
+        }
+
+        for CssColorStop(color, pos) in &self.stops {
+            write!(f, ", {}", color)?;
+
+            if let Some(pos) = pos {
+                write!(f, " {}", pos)?;
+            }
+        }
+
+        write!(f, ")")
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub struct CssBoxShadow {
     // TODO: Dimension
@@ -369,3 +816,168 @@ impl Display for CssBoxShadow {
         )
     }
 }
+
+// space-separated 2D functions (empty for none)
+#[derive(Debug, Clone, PartialEq)]
+pub struct CssTransform(pub Vec<CssTransformFn>);
+
+// angles are in degrees
+#[derive(Debug, Clone, PartialEq)]
+pub enum CssTransformFn {
+    Translate(CssDimension, CssDimension),
+    Scale(f32, f32),
+    Rotate(f32),
+    Skew(f32, f32),
+    Matrix([f32; 6]),
+}
+
+impl Display for CssTransform {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), Error> {
+        if self.0.is_empty() {
+            return write!(f, "none");
+        }
+
+        for (i, func) in self.0.iter().enumerate() {
+            if i > 0 {
+                write!(f, " ")?;
+            }
+
+            write!(f, "{}", func)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Display for CssTransformFn {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), Error> {
+        match self {
+            Self::Translate(x, y) => write!(f, "translate({}, {})", x, y),
+            Self::Scale(x, y) => write!(f, "scale({}, {})", x, y),
+            Self::Rotate(a) => write!(f, "rotate({}deg)", a),
+            Self::Skew(x, y) => write!(f, "skew({}deg, {}deg)", x, y),
+            Self::Matrix([a, b, c, d, e, f_]) => write!(f, "matrix({}, {}, {}, {}, {}, {})", a, b, c, d, e, f_),
+        }
+    }
+}
+
+// (x, y) relative to the border box (transform-origin)
+#[derive(Debug, Clone, PartialEq)]
+pub struct CssOrigin(pub CssDimension, pub CssDimension);
+
+impl Display for CssOrigin {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), Error> {
+        write!(f, "{} {}", self.0, self.1)
+    }
+}
+
+// comma-separated
+#[derive(Debug, Clone, PartialEq)]
+pub struct CssList<T>(pub Vec<T>);
+
+impl<T> Default for CssList<T> {
+    fn default() -> Self {
+        Self(Vec::new())
+    }
+}
+
+impl<T: Display> Display for CssList<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), Error> {
+        for (i, v) in self.0.iter().enumerate() {
+            if i != 0 {
+                write!(f, ", ")?;
+            }
+
+            write!(f, "{}", v)?;
+        }
+
+        Ok(())
+    }
+}
+
+// seconds
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CssTime(pub f32);
+
+impl Display for CssTime {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), Error> {
+        write!(f, "{}s", self.0)
+    }
+}
+
+// infinite is f32::INFINITY
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CssIterationCount(pub f32);
+
+impl Display for CssIterationCount {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), Error> {
+        match self.0.is_infinite() {
+            true => write!(f, "infinite"),
+            false => write!(f, "{}", self.0),
+        }
+    }
+}
+
+// all false is "none"
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct CssTextDecorationLine {
+    pub underline: bool,
+    pub overline: bool,
+    pub line_through: bool,
+}
+
+impl Display for CssTextDecorationLine {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), Error> {
+        let lines = [
+            (self.underline, "underline"),
+            (self.overline, "overline"),
+            (self.line_through, "line-through"),
+        ];
+        let names: Vec<_> = lines.iter().filter(|l| l.0).map(|l| l.1).collect();
+
+        match names.is_empty() {
+            true => write!(f, "none"),
+            false => write!(f, "{}", names.join(" ")),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CssZIndex {
+    Auto,
+    Index(i32),
+}
+
+impl Display for CssZIndex {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), Error> {
+        match self {
+            Self::Auto => write!(f, "auto"),
+            Self::Index(i) => write!(f, "{}", i),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CssTimingFunction {
+    Linear,
+    Ease,
+    EaseIn,
+    EaseOut,
+    EaseInOut,
+    CubicBezier(f32, f32, f32, f32),
+    Steps(u32, CssStepPosition),
+}
+
+impl Display for CssTimingFunction {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), Error> {
+        match self {
+            Self::Linear => write!(f, "linear"),
+            Self::Ease => write!(f, "ease"),
+            Self::EaseIn => write!(f, "ease-in"),
+            Self::EaseOut => write!(f, "ease-out"),
+            Self::EaseInOut => write!(f, "ease-in-out"),
+            Self::CubicBezier(x1, y1, x2, y2) => write!(f, "cubic-bezier({}, {}, {}, {})", x1, y1, x2, y2),
+            Self::Steps(n, pos) => write!(f, "steps({}, {})", n, pos),
+        }
+    }
+}
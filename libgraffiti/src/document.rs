@@ -5,7 +5,7 @@
 // x panics for invalid node types
 //  (another layer on top of this should make sure it never happens)
 
-use crate::css::{MatchingContext, Selector, Style};
+use crate::css::{MatchingContext, Selector, Style, StyleSheet};
 use crate::util::{Atom, SlotMap};
 use std::any::Any;
 use std::borrow::Cow;
@@ -18,6 +18,10 @@ pub enum DocumentEvent<'a> {
     Insert(NodeId, NodeId, usize),
     Remove(NodeId, NodeId),
     Cdata(NodeId, &'a str),
+    // id, class, style, ...
+    Attribute(NodeId, &'a str),
+    // <style> sheet is going to be changed (CSSOM)
+    StyleSheet(NodeId),
 
     // TODO: call during Document::Drop, probably in document order (children first)
     Drop(NodeId, NodeType),
@@ -50,6 +54,9 @@ pub struct Document {
     weak_data: SlotMap<NodeId, Vec<Box<dyn Any>>>,
 
     free_ids: Vec<NodeId>,
+
+    // for resolving <link href>, @import, ...
+    base_url: Option<String>,
 }
 
 // private shorthand
@@ -63,6 +70,7 @@ impl Document {
             listeners: Vec::new(),
             weak_data: SlotMap::new(),
             free_ids: Vec::new(),
+            base_url: None,
         };
 
         let root = doc.create_node(NodeData::Document);
@@ -79,6 +87,14 @@ impl Document {
         self.root
     }
 
+    pub fn base_url(&self) -> Option<&str> {
+        self.base_url.as_deref()
+    }
+
+    pub fn set_base_url(&mut self, url: &str) {
+        self.base_url = Some(url.to_owned());
+    }
+
     // shared for all node types
 
     pub fn node_type(&self, node: NodeId) -> NodeType {
@@ -181,6 +197,7 @@ impl Document {
 
         self.nodes[child].parent = Some(parent);
 
+        self.text_changed(parent);
         self.emit(Event::Insert(parent, child, index));
     }
 
@@ -196,6 +213,7 @@ impl Document {
         self.nodes[child].next_sibling = None;
         self.nodes[child].parent = None;
 
+        self.text_changed(parent);
         self.emit(Event::Remove(parent, child));
     }
 
@@ -262,6 +280,10 @@ impl Document {
             panic!("not a cdata node")
         }
 
+        if let Some(parent) = self.parent(cdata_node) {
+            self.text_changed(parent);
+        }
+
         self.emit(Event::Cdata(cdata_node, cdata));
     }
 
@@ -313,6 +335,8 @@ impl Document {
                 }
             }
         }
+
+        self.emit(Event::Attribute(element, att_name));
     }
 
     pub fn remove_attribute(&mut self, element: NodeId, att_name: &str) {
@@ -320,10 +344,12 @@ impl Document {
 
         match att_name {
             "id" => drop(el_data.identifier.take()),
-            "class" => drop(el_data.identifier.take()),
+            "class" => drop(el_data.class_name.take()),
             "style" => el_data.style = Style::EMPTY,
             _ => el_data.attrs.retain(|(a, _)| att_name != **a),
         };
+
+        self.emit(Event::Attribute(element, att_name));
     }
 
     pub fn attribute_names(&self, element: NodeId) -> Vec<String> {
@@ -355,10 +381,40 @@ impl Document {
 
     pub fn set_element_style_property(&mut self, element: NodeId, prop: &str, value: &str) {
         self.el_mut(element).style.set_property(prop, value);
+        self.emit(Event::Attribute(element, "style"));
+    }
+
+    // style
+
+    // parsed lazily and then kept until the text is changed
+    pub fn style_sheet(&mut self, style: NodeId) -> &StyleSheet {
+        self.style_sheet_mut_quiet(style)
+    }
+
+    // CSSOM, listeners are notified upfront
+    pub fn style_sheet_mut(&mut self, style: NodeId) -> &mut StyleSheet {
+        self.emit(Event::StyleSheet(style));
+        self.style_sheet_mut_quiet(style)
     }
 
     // helpers
 
+    fn style_sheet_mut_quiet(&mut self, style: NodeId) -> &mut StyleSheet {
+        if self.weak_data::<StyleSheet>(style).is_none() {
+            let sheet = StyleSheet::from(&*self.text_content(style));
+            self.set_weak_data(style, sheet);
+        }
+
+        self.weak_data_mut(style).unwrap()
+    }
+
+    // <style> needs to be parsed again
+    fn text_changed(&mut self, node: NodeId) {
+        if self.node_type(node) == NodeType::Element && self.local_name(node) == "style" {
+            self.remove_weak_data::<StyleSheet>(node);
+        }
+    }
+
     fn create_node(&mut self, data: NodeData) -> NodeId {
         let node = Node {
             parent: None,
@@ -370,7 +426,7 @@ impl Document {
         if let Some(id) = self.free_ids.pop() {
             self.nodes.put(id, node);
             self.weak_data.put(id, Vec::new());
-            return id
+            return id;
         }
 
         let id = self.nodes.insert(node);
@@ -392,12 +448,9 @@ impl Document {
 
     pub(crate) fn with_matching_context<R, F: FnOnce(MatchingContext<'_, NodeId>) -> R>(&self, f: F) -> R {
         f(MatchingContext {
-            has_local_name: &|el, name| **name == self.local_name(el),
-            has_identifier: &|el, id| Some(id.to_string()) == self.attribute(el, "id"),
-            has_class: &|el, cls| match self.attribute(el, "class") {
-                Some(s) => s.split_ascii_whitespace().any(|part| part == **cls),
-                None => false,
-            },
+            local_name: &|el| self.local_name(el),
+            identifier: &|el| self.el(el).identifier.as_deref().map(String::as_str),
+            class_name: &|el| self.el(el).class_name.as_deref().map(String::as_str),
             parent: &|el| self.parent_element(el),
         })
     }
@@ -490,6 +543,11 @@ mod tests {
         assert_eq!(doc.attribute(div, "id").as_deref(), Some("panel"));
 
         assert_eq!(doc.query_selector(doc.root(), "div#panel"), Some(div));
+
+        doc.set_attribute(div, "class", "a");
+        doc.remove_attribute(div, "class");
+        assert_eq!(doc.attribute(div, "class"), None);
+        assert_eq!(doc.attribute(div, "id").as_deref(), Some("panel"));
     }
 
     #[test]
@@ -569,4 +627,22 @@ mod tests {
         d.remove_weak_data::<usize>(root);
         assert_eq!(d.weak_data(root), None::<&usize>);
     }
+
+    #[test]
+    fn style_sheet() {
+        let mut d = Document::new();
+        let style = d.create_element("style");
+        let text = d.create_text_node("div { color: red }");
+        d.insert_child(style, text, 0);
+
+        assert_eq!(d.style_sheet(style).css_rules().len(), 1);
+        d.style_sheet_mut(style).insert_rule("a { color: blue }", 1).unwrap();
+        assert_eq!(d.style_sheet(style).css_rules().len(), 2);
+
+        d.set_cdata(text, "div {} a {} p {}");
+        assert_eq!(d.style_sheet(style).css_rules().len(), 3);
+
+        d.remove_child(style, text);
+        assert_eq!(d.style_sheet(style).css_rules().len(), 0);
+    }
 }
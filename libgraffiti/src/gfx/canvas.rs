@@ -2,7 +2,7 @@
 // x outputs (textured) vertices + draw "ops"
 // x easy to integrate (and to compose)
 
-use super::{CachedGlyph, GlyphCache, GlyphPos, Text, Vec2, AABB};
+use super::{CachedGlyph, GlyphCache, GlyphPos, Text, Transform, Vec2, AABB};
 use std::ops::{Index, IndexMut};
 
 #[allow(clippy::upper_case_acronyms)]
@@ -61,7 +61,12 @@ impl Canvas {
         self.state_mut().opacity = opacity;
     }
 
+    pub fn set_transform(&mut self, transform: Transform) {
+        self.state_mut().transform = transform;
+    }
+
     pub fn fill_rect(&mut self, rect: AABB, color: RGBA8) {
+        let start = self.frame.vertices.len();
         let AABB { min, max } = rect;
         let uv = Vec2::ZERO;
 
@@ -74,15 +79,18 @@ impl Canvas {
             Vertex::new(max, uv, color),
         ]);
 
+        self.transform_vertices(start);
+
         // TODO: join
         self.frame.draw_ops.push(DrawOp::DrawArrays(6));
     }
 
     pub fn fill_text(&mut self, text: &Text, rect: AABB, color: RGBA8) {
+        let start = self.frame.vertices.len();
         let mut count = 0;
 
-        text.for_each_glyph(rect, |GlyphPos { glyph, pos }| {
-            let CachedGlyph { rect, uv } = self.glyph_cache.use_glyph(/* &text.font(),*/ glyph);
+        text.for_each_glyph(rect, |GlyphPos { font_id, glyph, pos }| {
+            let CachedGlyph { rect, uv } = self.glyph_cache.use_glyph(font_id, glyph);
 
             self.frame.vertices.extend_from_slice(&[
                 Vertex::new(pos + rect.min, uv.min, color),
@@ -112,19 +120,212 @@ impl Canvas {
             count += 1;
         });
 
+        self.transform_vertices(start);
+
         self.frame.draw_ops.push(DrawOp::DrawArrays(count * 6))
     }
 
     // TODO: stroke_text()
+
+    // mesh with per-vertex colors, exact for linear (except for the stops in between),
+    // radial & conic are approximated with rings/segments
+    pub fn fill_gradient(&mut self, rect: AABB, gradient: &Gradient) {
+        const SEGMENTS: usize = 64;
+        const TAU: f32 = std::f32::consts::PI * 2.;
+
+        let start = self.frame.vertices.len();
+        let corners = [
+            rect.min,
+            Vec2::new(rect.max.x, rect.min.y),
+            rect.max,
+            Vec2::new(rect.min.x, rect.max.y),
+        ];
+        let offsets = gradient.stops.iter().map(|s| s.0);
+
+        match gradient.shape {
+            GradientShape::Linear(a, b) => {
+                let dir = b - a;
+                let t = |p: Vec2| (p - a).dot(dir) / dir.dot(dir);
+
+                // bands between the stops (+ before the first & after the last one)
+                let mut bounds: Vec<_> = offsets.collect();
+                bounds.insert(0, f32::NEG_INFINITY);
+                bounds.push(f32::INFINITY);
+
+                for band in bounds.windows(2) {
+                    let poly = clip(&corners, |p| t(p) - band[0]);
+                    let poly = clip(&poly, |p| band[1] - t(p));
+                    self.push_polygon(&poly, |p| gradient.color_at(t(p)));
+                }
+            }
+            GradientShape::Radial(center, radius) => {
+                let t = |p: Vec2| Vec2::new((p.x - center.x) / radius.x, (p.y - center.y) / radius.y).length();
+                let point = |r: f32, a: f32| center + Vec2::new(radius.x * r * a.cos(), radius.y * r * a.sin());
+                let max = corners.iter().map(|p| t(*p)).fold(0., f32::max);
+
+                // rings at each stop so colors are interpolated between them
+                let mut rings: Vec<_> = offsets.filter(|o| *o > 0. && *o < max).collect();
+                rings.insert(0, 0.);
+                rings.push(max);
+
+                for ring in rings.windows(2) {
+                    for i in 0..SEGMENTS {
+                        let (a0, a1) = (TAU * i as f32 / SEGMENTS as f32, TAU * (i + 1) as f32 / SEGMENTS as f32);
+                        let quad = [
+                            point(ring[0], a0),
+                            point(ring[1], a0),
+                            point(ring[1], a1),
+                            point(ring[0], a1),
+                        ];
+                        let poly = clip_rect(&quad, rect);
+                        self.push_polygon(&poly, |p| gradient.color_at(t(p)));
+                    }
+                }
+            }
+            GradientShape::Conic(center, from) => {
+                let radius = corners.iter().map(|p| (*p - center).length()).fold(0., f32::max) + 1.;
+                let point = |a: f32| center + Vec2::new(a.sin(), -a.cos()) * radius;
+
+                // segments (relative to from) split at each stop
+                let mut angles: Vec<_> = (0..=SEGMENTS).map(|i| TAU * i as f32 / SEGMENTS as f32).collect();
+                angles.extend(offsets.filter(|o| *o > 0. && *o < 1.).map(|o| o * TAU));
+                angles.sort_by(|a, b| a.partial_cmp(b).unwrap());
+
+                for seg in angles.windows(2) {
+                    let (a0, a1) = (seg[0], seg[1]);
+
+                    // angle of the point, kept in the segment (the last one ends at TAU, not 0)
+                    let angle = |p: Vec2| {
+                        let d = p - center;
+
+                        if d.length() < 1e-3 {
+                            return (a0 + a1) / 2.;
+                        }
+
+                        let mut a = (d.x.atan2(-d.y) - from).rem_euclid(TAU);
+
+                        if a < a0 - 1e-3 {
+                            a += TAU;
+                        }
+
+                        a.max(a0).min(a1)
+                    };
+
+                    let poly = clip_rect(&[center, point(from + a0), point(from + a1)], rect);
+                    self.push_polygon(&poly, |p| gradient.color_at(angle(p) / TAU));
+                }
+            }
+        }
+
+        let count = self.frame.vertices.len() - start;
+        self.transform_vertices(start);
+        self.frame.draw_ops.push(DrawOp::DrawArrays(count as u32));
+    }
+
+    // convex, as a triangle fan
+    fn push_polygon(&mut self, poly: &[Vec2], color: impl Fn(Vec2) -> RGBA8) {
+        for i in 2..poly.len() {
+            for &p in &[poly[0], poly[i - 1], poly[i]] {
+                self.frame.vertices.push(Vertex::new(p, Vec2::ZERO, color(p)));
+            }
+        }
+    }
+
+    fn transform_vertices(&mut self, start: usize) {
+        let transform = self.state().transform;
+
+        if transform != Transform::IDENTITY {
+            for v in &mut self.frame.vertices[start..] {
+                v.xy = transform.apply(v.xy);
+            }
+        }
+    }
+}
+
+// offsets are relative to the gradient line/ray (or to the full turn for conic)
+#[derive(Debug, Clone, PartialEq)]
+pub struct Gradient {
+    pub shape: GradientShape,
+    // (offset, color), sorted by offset
+    pub stops: Vec<(f32, RGBA8)>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum GradientShape {
+    // (start, end) of the gradient line
+    Linear(Vec2, Vec2),
+    // (center, radius)
+    Radial(Vec2, Vec2),
+    // (center, start angle), angles are clockwise from the top (in radians)
+    Conic(Vec2, f32),
+}
+
+impl Gradient {
+    pub fn color_at(&self, t: f32) -> RGBA8 {
+        let stops = &self.stops;
+
+        match stops.iter().position(|s| s.0 > t) {
+            None => stops.last().map_or([0, 0, 0, 0], |s| s.1),
+            Some(0) => stops[0].1,
+            // premultiplied, so transparent doesn't bring any black in
+            Some(i) => {
+                let ((o1, c1), (o2, c2)) = (stops[i - 1], stops[i]);
+                let f = (t - o1) / (o2 - o1);
+                let (a1, a2) = (c1[3] as f32 / 255., c2[3] as f32 / 255.);
+                let a = a1 + (a2 - a1) * f;
+
+                if a <= 0. {
+                    return [0, 0, 0, 0];
+                }
+
+                let (p1, p2) = (|i: usize| c1[i] as f32 * a1, |i: usize| c2[i] as f32 * a2);
+                let ch = |i| ((p1(i) + (p2(i) - p1(i)) * f) / a).round() as u8;
+
+                [ch(0), ch(1), ch(2), (a * 255.).round() as u8]
+            }
+        }
+    }
+}
+
+// Sutherland-Hodgman, keeps the part where side(p) >= 0 (side has to be linear)
+fn clip(poly: &[Vec2], side: impl Fn(Vec2) -> f32) -> Vec<Vec2> {
+    let mut res = Vec::new();
+
+    for (i, &a) in poly.iter().enumerate() {
+        let b = poly[(i + 1) % poly.len()];
+        let (sa, sb) = (side(a), side(b));
+
+        if sa >= 0. {
+            res.push(a);
+        }
+
+        if (sa >= 0.) != (sb >= 0.) {
+            res.push(a + (b - a) * (sa / (sa - sb)));
+        }
+    }
+
+    res
+}
+
+fn clip_rect(poly: &[Vec2], rect: AABB) -> Vec<Vec2> {
+    let poly = clip(poly, |p| p.x - rect.min.x);
+    let poly = clip(&poly, |p| rect.max.x - p.x);
+    let poly = clip(&poly, |p| p.y - rect.min.y);
+
+    clip(&poly, |p| rect.max.y - p.y)
 }
 
 #[derive(Debug, Clone)]
 struct State {
     opacity: f32,
+    transform: Transform,
 }
 
 impl State {
-    const DEFAULT: Self = Self { opacity: 1. };
+    const DEFAULT: Self = Self {
+        opacity: 1.,
+        transform: Transform::IDENTITY,
+    };
 }
 
 #[derive(Debug)]
@@ -185,3 +386,32 @@ impl Vertex {
         Self { xy, uv, color }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn gradient_color_at() {
+        let gradient = Gradient {
+            shape: GradientShape::Linear(Vec2::ZERO, Vec2::ONE),
+            stops: vec![
+                (0.25, [0, 0, 0, 0]),
+                (0.5, [255, 0, 0, 255]),
+                (0.5, [0, 0, 255, 255]),
+                (1., [0, 0, 0, 255]),
+            ],
+        };
+
+        // ends are extended
+        assert_eq!(gradient.color_at(0.), [0, 0, 0, 0]);
+        assert_eq!(gradient.color_at(1.5), [0, 0, 0, 255]);
+
+        // transparent doesn't darken the other color
+        assert_eq!(gradient.color_at(0.375), [255, 0, 0, 128]);
+
+        // hard stop
+        assert_eq!(gradient.color_at(0.5), [0, 0, 255, 255]);
+        assert_eq!(gradient.color_at(0.75), [0, 0, 128, 255]);
+    }
+}
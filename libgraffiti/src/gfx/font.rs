@@ -1,9 +1,30 @@
-use fontdb::{Database, Family, Query};
+// fonts
+// x system fonts
+// x generic families (sans-serif, serif, monospace, ...)
+// x font files (@font-face)
+// x font stacks with per-char fallback (unicode-range, missing glyphs)
+
+use fontdb::{Database, Family, Query, Style, Weight, ID};
 use once_cell::sync::Lazy;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, RwLock};
 
 pub use ab_glyph::{Font, FontArc, Glyph, GlyphId, ScaleFont};
 
-pub static FONT_DB: Lazy<Database> = Lazy::new(|| {
+// fonts are identified by their face id in the FONT_DB
+pub type FontId = ID;
+
+// shared by all threads (ids have to be the same everywhere)
+static FONT_DB: Lazy<RwLock<Database>> = Lazy::new(|| RwLock::new(system_fonts()));
+static FONTS: Lazy<Mutex<HashMap<FontId, Option<FontArc>>>> = Lazy::new(Default::default);
+static FONT_FILES: Lazy<Mutex<HashMap<PathBuf, Option<FontId>>>> = Lazy::new(Default::default);
+// (hashed sheet key, faces) in order of registration
+static FONT_FACES: Lazy<RwLock<Vec<(u64, Vec<FontFace>)>>> = Lazy::new(Default::default);
+
+fn system_fonts() -> Database {
     let mut db = Database::new();
     db.load_system_fonts();
 
@@ -21,20 +42,217 @@ pub static FONT_DB: Lazy<Database> = Lazy::new(|| {
     db.set_sans_serif_family(db.face(id).unwrap().family.clone());
 
     db
-});
+}
 
-pub(crate) static SANS_SERIF_FONT: Lazy<FontArc> = Lazy::new(|| {
-    let id = FONT_DB
-        .query(&Query {
-            families: &[Family::SansSerif],
-            ..Default::default()
-        })
-        .unwrap();
+// user-provided face (@font-face), overrides system fonts with the same family name
+#[derive(Debug, Clone, PartialEq)]
+pub struct FontFace {
+    pub family: String,
+    pub weight: (u16, u16),
+    pub italic: bool,
+    // empty means everything
+    pub unicode_range: Vec<(u32, u32)>,
+    pub id: FontId,
+}
+
+impl FontFace {
+    // lower is better
+    fn distance(&self, weight: u16, italic: bool) -> u32 {
+        let (min, max) = self.weight;
+        let w = if weight < min {
+            min - weight
+        } else if weight > max {
+            weight - max
+        } else {
+            0
+        };
+
+        w as u32 + if self.italic != italic { 1000 } else { 0 }
+    }
+}
 
+// replaces all faces registered for the sheet (any unique key),
+// returns true if they have changed
+pub fn set_font_faces(sheet: &impl Hash, faces: Vec<FontFace>) -> bool {
+    let mut hasher = DefaultHasher::new();
+    sheet.hash(&mut hasher);
+    let key = hasher.finish();
+
+    let mut font_faces = FONT_FACES.write().unwrap();
+
+    match font_faces.iter().position(|(k, _)| *k == key) {
+        Some(i) if font_faces[i].1 == faces => false,
+        Some(i) if faces.is_empty() => {
+            font_faces.remove(i);
+            true
+        }
+        Some(i) => {
+            font_faces[i].1 = faces;
+            true
+        }
+        None if faces.is_empty() => false,
+        None => {
+            font_faces.push((key, faces));
+            true
+        }
+    }
+}
+
+// loads (first face of) the file into the FONT_DB, results are cached (including failures)
+pub fn load_font_file(path: impl AsRef<Path>) -> Option<FontId> {
+    let path = path.as_ref();
+
+    *FONT_FILES.lock().unwrap().entry(path.to_owned()).or_insert_with(|| {
+        let mut db = FONT_DB.write().unwrap();
+        let len = db.faces().len();
+
+        db.load_font_file(path).ok()?;
+        db.faces().get(len).map(|f| f.id)
+    })
+}
+
+// local(name) in @font-face
+pub fn find_local_font(name: &str) -> Option<FontId> {
     FONT_DB
-        .with_face_data(id, |data, _| FontArc::try_from_vec(data.to_owned()).unwrap())
+        .read()
+        .unwrap()
+        .faces()
+        .iter()
+        .find(|f| f.post_script_name == name || f.family == name)
+        .map(|f| f.id)
+}
+
+// families in order of preference, comma-separated
+// TODO: stretch
+pub fn query_font(families: Option<&str>, weight: u16, italic: bool) -> FontStack {
+    let families: Vec<_> = families.unwrap_or("").split(',').map(str::trim).collect();
+    let mut faces = Vec::new();
+
+    for &family in &families {
+        // @font-face (all unicode-range subsets of the best match)
+        let font_faces = FONT_FACES.read().unwrap();
+        let candidates: Vec<_> = font_faces
+            .iter()
+            .flat_map(|(_, faces)| faces)
+            .filter(|f| f.family.eq_ignore_ascii_case(family))
+            .collect();
+
+        if let Some(best) = candidates.iter().map(|f| f.distance(weight, italic)).min() {
+            for f in candidates.iter().filter(|f| f.distance(weight, italic) == best) {
+                if let Some(font) = font(f.id) {
+                    faces.push((f.id, font, f.unicode_range.clone()));
+                }
+            }
+        }
+
+        // system
+        let family = match family {
+            "" | "sans-serif" => Family::SansSerif,
+            "serif" => Family::Serif,
+            "monospace" => Family::Monospace,
+            "cursive" => Family::Cursive,
+            "fantasy" => Family::Fantasy,
+            name => Family::Name(name),
+        };
+
+        if let Some(id) = query_system_font(family, weight, italic) {
+            if let Some(font) = font(id) {
+                faces.push((id, font, Vec::new()));
+            }
+        }
+    }
+
+    // last resort
+    let id = query_system_font(Family::SansSerif, weight, italic).expect("no sans-serif found");
+    faces.push((id, font(id).expect("invalid sans-serif"), Vec::new()));
+
+    FontStack { faces }
+}
+
+fn query_system_font(family: Family, weight: u16, italic: bool) -> Option<FontId> {
+    FONT_DB.read().unwrap().query(&Query {
+        families: &[family],
+        weight: Weight(weight),
+        style: if italic { Style::Italic } else { Style::Normal },
+        ..Default::default()
+    })
+}
+
+// None if the data can't be parsed (cached too)
+pub fn font(id: FontId) -> Option<FontArc> {
+    FONTS
+        .lock()
         .unwrap()
-});
+        .entry(id)
+        .or_insert_with(|| {
+            FONT_DB
+                .read()
+                .unwrap()
+                .with_face_data(id, |data, _| FontArc::try_from_vec(data.to_owned()).ok())
+                .flatten()
+        })
+        .clone()
+}
+
+// resolved font-family
+#[derive(Clone)]
+pub struct FontStack {
+    faces: Vec<(FontId, FontArc, Vec<(u32, u32)>)>,
+}
+
+impl FontStack {
+    pub fn primary(&self) -> &FontArc {
+        &self.faces[0].1
+    }
+
+    // index of the first face which covers the char (or the primary one)
+    pub fn face_for(&self, ch: char) -> usize {
+        let code = ch as u32;
+
+        self.faces
+            .iter()
+            .position(|(_, font, range)| {
+                (range.is_empty() || range.iter().any(|&(start, end)| start <= code && code <= end))
+                    && font.glyph_id(ch).0 != 0
+            })
+            .unwrap_or(0)
+    }
+
+    pub fn face(&self, index: usize) -> (FontId, &FontArc) {
+        (self.faces[index].0, &self.faces[index].1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn font_faces() {
+        let id = query_system_font(Family::SansSerif, 400, false).unwrap();
+        let face = |family: &str| FontFace {
+            family: family.to_owned(),
+            weight: (400, 400),
+            italic: false,
+            unicode_range: Vec::new(),
+            id,
+        };
+        // @font-face + last resort
+        let faces = |family| query_font(Some(family), 400, false).faces.len();
+
+        assert!(set_font_faces(&"sheet", vec![face("Test A")]));
+        assert!(!set_font_faces(&"sheet", vec![face("Test A")]));
+        assert_eq!(faces("Test A"), 2);
+
+        // replaced, not added
+        assert!(set_font_faces(&"sheet", vec![face("Test B")]));
+        assert_eq!(faces("Test A"), 1);
+        assert_eq!(faces("Test B"), 2);
+
+        assert!(set_font_faces(&"sheet", Vec::new()));
+        assert_eq!(faces("Test B"), 1);
+    }
+}
 
 /*
 use crate::util::{Atom, SlotMap};
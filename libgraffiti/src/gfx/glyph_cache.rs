@@ -1,4 +1,4 @@
-use super::{Atlas, Font, Glyph, TexData, Vec2, AABB, SANS_SERIF_FONT};
+use super::{font, Atlas, Font, FontId, Glyph, TexData, Vec2, AABB};
 use std::collections::HashMap;
 use std::hash::Hash;
 
@@ -26,12 +26,12 @@ impl GlyphCache {
         }
     }
 
-    pub fn use_glyph(&mut self, glyph: Glyph) -> &CachedGlyph {
+    pub fn use_glyph(&mut self, font_id: FontId, glyph: Glyph) -> &CachedGlyph {
         let Self { atlas, glyphs } = self;
 
-        glyphs.entry(CacheKey::new(&glyph)).or_insert_with(|| {
+        glyphs.entry(CacheKey::new(font_id, &glyph)).or_insert_with(|| {
             // TODO: evicting, rebuilding
-            if let Some(g) = SANS_SERIF_FONT.outline_glyph(glyph) {
+            if let Some(g) = font(font_id).and_then(|f| f.outline_glyph(glyph)) {
                 let pxb = g.px_bounds();
                 let rect = AABB::new(Vec2::new(pxb.min.x, pxb.min.y), Vec2::new(pxb.max.x, pxb.max.y));
 
@@ -61,10 +61,10 @@ impl GlyphCache {
 }
 
 #[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
-struct CacheKey(u16, u32);
+struct CacheKey(FontId, u16, u32);
 
 impl CacheKey {
-    pub fn new(glyph: &Glyph) -> Self {
-        Self(glyph.id.0, glyph.scale.x.to_bits())
+    pub fn new(font_id: FontId, glyph: &Glyph) -> Self {
+        Self(font_id, glyph.id.0, glyph.scale.x.to_bits())
     }
 }
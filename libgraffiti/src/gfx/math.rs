@@ -15,6 +15,14 @@ impl Vec2 {
     pub const fn new(x: f32, y: f32) -> Self {
         Self { x, y }
     }
+
+    pub fn dot(self, other: Self) -> f32 {
+        self.x * other.x + self.y * other.y
+    }
+
+    pub fn length(self) -> f32 {
+        self.dot(self).sqrt()
+    }
 }
 
 impl From<(f32, f32)> for Vec2 {
@@ -115,3 +123,107 @@ impl Add<Vec2> for AABB {
         }
     }
 }
+
+// 2D affine transform, same as CSS matrix(a, b, c, d, e, f)
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Transform {
+    pub a: f32,
+    pub b: f32,
+    pub c: f32,
+    pub d: f32,
+    pub e: f32,
+    pub f: f32,
+}
+
+impl Transform {
+    pub const IDENTITY: Self = Self::new(1., 0., 0., 1., 0., 0.);
+
+    pub const fn new(a: f32, b: f32, c: f32, d: f32, e: f32, f: f32) -> Self {
+        Self { a, b, c, d, e, f }
+    }
+
+    pub const fn translate(x: f32, y: f32) -> Self {
+        Self::new(1., 0., 0., 1., x, y)
+    }
+
+    pub const fn scale(x: f32, y: f32) -> Self {
+        Self::new(x, 0., 0., y, 0., 0.)
+    }
+
+    // radians, clockwise (y goes down)
+    pub fn rotate(angle: f32) -> Self {
+        let (sin, cos) = angle.sin_cos();
+
+        Self::new(cos, sin, -sin, cos, 0., 0.)
+    }
+
+    pub fn skew(x: f32, y: f32) -> Self {
+        Self::new(1., y.tan(), x.tan(), 1., 0., 0.)
+    }
+
+    pub fn apply(&self, pos: Vec2) -> Vec2 {
+        Vec2::new(
+            self.a * pos.x + self.c * pos.y + self.e,
+            self.b * pos.x + self.d * pos.y + self.f,
+        )
+    }
+
+    // None if it can't be inverted (scale(0), ...)
+    pub fn invert(&self) -> Option<Self> {
+        let det = self.a * self.d - self.b * self.c;
+
+        if det == 0. {
+            return None;
+        }
+
+        Some(Self::new(
+            self.d / det,
+            -self.b / det,
+            -self.c / det,
+            self.a / det,
+            (self.c * self.f - self.d * self.e) / det,
+            (self.b * self.e - self.a * self.f) / det,
+        ))
+    }
+}
+
+// (a * b).apply(pos) == a.apply(b.apply(pos))
+impl Mul for Transform {
+    type Output = Self;
+
+    fn mul(self, o: Self) -> Self {
+        Self::new(
+            self.a * o.a + self.c * o.b,
+            self.b * o.a + self.d * o.b,
+            self.a * o.c + self.c * o.d,
+            self.b * o.c + self.d * o.d,
+            self.a * o.e + self.c * o.f + self.e,
+            self.b * o.e + self.d * o.f + self.f,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn transform() {
+        let t =
+            Transform::translate(10., 20.) * Transform::rotate(std::f32::consts::FRAC_PI_2) * Transform::scale(2., 2.);
+        let p = t.apply(Vec2::new(1., 0.));
+
+        assert!((p.x - 10.).abs() < 1e-5 && (p.y - 22.).abs() < 1e-5);
+
+        let back = t.invert().unwrap().apply(p);
+        assert!((back.x - 1.).abs() < 1e-5 && back.y.abs() < 1e-5);
+
+        assert_eq!(Transform::scale(0., 1.).invert(), None);
+    }
+
+    #[test]
+    fn vec2() {
+        assert_eq!(Vec2::new(1., 2.).dot(Vec2::new(3., 4.)), 11.);
+        assert_eq!(Vec2::new(3., 4.).length(), 5.);
+    }
+}
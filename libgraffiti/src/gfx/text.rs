@@ -3,12 +3,16 @@
 // x measure(max_width)
 // x for_each_glyph(rect, f)
 
-use super::{Font, Glyph, GlyphId, ScaleFont, Vec2, AABB, SANS_SERIF_FONT};
+use super::{query_font, Font, FontId, FontStack, Glyph, GlyphId, ScaleFont, Vec2, AABB, RGBA8};
+use crate::util::Atom;
+use std::borrow::Cow;
 use std::cell::{Ref, RefCell};
+use std::ops::Range;
 
 pub struct Text {
     text: String,
     style: TextStyle,
+    fonts: FontStack,
 
     single_line: RefCell<Option<SingleLine>>,
 }
@@ -18,6 +22,7 @@ impl Text {
         Self {
             text: text.to_owned(),
             style: style.clone(),
+            fonts: style.query_font(),
             single_line: Default::default(),
         }
     }
@@ -35,83 +40,149 @@ impl Text {
         &self.style
     }
 
+    // note that fonts are always re-queried (there might be new @font-face)
     pub fn set_style(&mut self, style: &TextStyle) {
+        self.fonts = style.query_font();
         self.style = style.clone();
         self.single_line.replace(None);
     }
 
-    // TODO: check/refactor (it's old code)
     // TODO: start_x for inline-layout (some el follows on the same line)
     pub fn measure(&self, max_width: f32 /* start_x */) -> (f32, f32) {
-        let &SingleLine {
-            ref xglyphs,
-            ref break_hints,
-        } = &*self.single_line();
+        let single_line = self.single_line();
 
         // empty or white-space
-        if xglyphs.is_empty() {
+        if single_line.xglyphs.is_empty() {
             return (0., 0.);
         }
 
-        let (mut width, mut offset, mut breaks) = (0., 0., 0);
-
-        // go through the hints, make a break each time it overflows
-        if !break_hints.is_empty() {
-            for (i, xend) in break_hints {
-                if (xend - offset) > max_width {
-                    let x = xglyphs[*i].0;
-                    let line_width = x - offset;
+        // widest line, including the advance of its last glyph
+        let lines = self.lines(&single_line, max_width);
+        let width = lines.iter().map(|&(_, _, width)| width).fold(0., f32::max);
 
-                    if line_width > width {
-                        width = line_width;
-                    }
+        (width, lines.len() as f32 * self.style.line_height)
+    }
 
-                    breaks += 1;
-                    offset = x;
-                }
+    pub fn for_each_glyph<F: FnMut(GlyphPos)>(&self, rect: AABB /* start_x */, mut f: F) {
+        let scale_font = self.fonts.primary().as_scaled(self.style.font_size);
+
+        self.for_each_line(rect, |single_line, range, offset, start, _| {
+            for &(gx, face, glyph_id) in &single_line.xglyphs[range] {
+                f(GlyphPos {
+                    font_id: self.fonts.face(face).0,
+                    glyph: glyph_id.with_scale(scale_font.scale()),
+                    pos: Vec2::new(start.x + gx - offset, start.y),
+                })
             }
-        }
+        });
+    }
 
-        if breaks == 0 {
-            return (xglyphs.last().unwrap().0 * self.style.font_size, self.style.line_height);
+    // (underline + overline, line-through) rects of each line,
+    // first ones should be painted before the text and the second ones after it
+    pub fn decorations(&self, rect: AABB) -> (Vec<AABB>, Vec<AABB>) {
+        let TextDecoration {
+            underline,
+            overline,
+            line_through,
+            ..
+        } = self.style.decoration;
+        let (mut under, mut over) = (Vec::new(), Vec::new());
+
+        if !underline && !overline && !line_through {
+            return (under, over);
         }
 
-        (width, (breaks + 1) as f32 * self.style.line_height)
+        // TODO: use underline position/thickness from the font (if present)
+        let ascent = self.fonts.primary().as_scaled(self.style.font_size).ascent();
+        let thickness = (self.style.font_size / 16.).max(1.);
+        let line = |x: f32, y: f32, width: f32| AABB::new(Vec2::new(x, y), Vec2::new(x + width, y + thickness));
+
+        self.for_each_line(rect, |_, _, _, start, width| {
+            if underline {
+                under.push(line(start.x, start.y + thickness, width));
+            }
+
+            if overline {
+                under.push(line(start.x, start.y - ascent, width));
+            }
+
+            if line_through {
+                over.push(line(start.x, start.y - ascent * 0.3 - thickness / 2., width));
+            }
+        });
+
+        (under, over)
     }
 
-    // TODO: check/refactor (it's old code)
-    pub fn for_each_glyph<F: FnMut(GlyphPos)>(&self, rect: AABB /* start_x */, mut f: F) {
-        let scale_font = SANS_SERIF_FONT.as_scaled(self.style.font_size);
+    // (single_line, glyphs, x offset, baseline start, width) of each line
+    fn for_each_line<F: FnMut(&SingleLine, Range<usize>, f32, Vec2, f32)>(&self, rect: AABB, mut f: F) {
+        let scale_font = self.fonts.primary().as_scaled(self.style.font_size);
         let single_line = self.single_line();
         let baseline = scale_font.height() + scale_font.descent();
+        let avail_width = rect.size().x;
 
         let mut y = rect.min.y + self.style.line_height - (self.style.line_height - baseline) / 2.;
-        let mut offset = 0.;
-        let mut hints = single_line.break_hints.iter().copied();
-        let mut next_hint = (0, 0.);
-
-        for &(x, glyph_id) in single_line.xglyphs.iter() {
-            // next word/hint
-            if x > next_hint.1 {
-                if (rect.min.x + next_hint.1 - offset) > rect.max.x {
-                    offset = x;
-                    y += self.style.line_height;
+
+        for (range, offset, width) in self.lines(&single_line, avail_width) {
+            let x = rect.min.x
+                + match self.style.align {
+                    TextAlign::Left => 0.,
+                    TextAlign::Center => (avail_width - width) / 2.,
+                    TextAlign::Right => avail_width - width,
+                };
+
+            f(&single_line, range, offset, Vec2::new(x, y), width);
+
+            y += self.style.line_height;
+        }
+    }
+
+    // (glyphs, x offset, width) of each line, breaks are the same as in measure()
+    fn lines(&self, single_line: &SingleLine, max_width: f32) -> Vec<(Range<usize>, f32, f32)> {
+        let xglyphs = &single_line.xglyphs;
+        let line = |range: Range<usize>, offset| {
+            let width = match xglyphs[range.clone()].last() {
+                Some(&(x, face, glyph_id)) => {
+                    x + self
+                        .fonts
+                        .face(face)
+                        .1
+                        .as_scaled(self.style.font_size)
+                        .h_advance(glyph_id)
+                        - offset
                 }
+                None => 0.,
+            };
 
-                // advance
-                next_hint = hints.next().unwrap_or((std::usize::MAX, 0.));
-            }
+            (range, offset, width)
+        };
+
+        let mut lines = Vec::new();
+        let (mut start, mut offset) = (0, 0.);
 
-            f(GlyphPos {
-                glyph: glyph_id.with_scale(scale_font.scale()),
-                pos: Vec2::new(rect.min.x + x - offset, y),
-            })
+        for &(i, xend) in &single_line.break_hints {
+            if (xend - offset) > max_width {
+                lines.push(line(start..i, offset));
+                start = i;
+                offset = xglyphs[i].0;
+            }
         }
+
+        lines.push(line(start..xglyphs.len(), offset));
+
+        lines
     }
 
     fn single_line(&self) -> Ref<SingleLine> {
         if !self.single_line.borrow().is_some() {
-            let scale_font = SANS_SERIF_FONT.as_scaled(self.style.font_size);
+            let scale_font = self.fonts.primary().as_scaled(self.style.font_size);
+            let TextStyle {
+                letter_spacing,
+                word_spacing,
+                white_space,
+                ..
+            } = self.style;
+            let space = scale_font.h_advance(scale_font.glyph_id(' '));
 
             let mut xglyphs = Vec::new();
             let mut break_hints = Vec::new();
@@ -121,35 +192,56 @@ impl Text {
             // where the current breakpoint starts
             let mut hint = None;
 
+            let text = match self.style.transform {
+                TextTransform::None => Cow::Borrowed(&*self.text),
+                TextTransform::Uppercase => Cow::Owned(self.text.to_uppercase()),
+                TextTransform::Lowercase => Cow::Owned(self.text.to_lowercase()),
+                TextTransform::Capitalize => Cow::Owned(capitalize(&self.text)),
+            };
+
             // TODO: shape
-            for ch in self.text.chars() {
+            for ch in text.chars() {
                 // TODO: FSM could be (a bit) more readable
                 // but it's not that bad, it just adds hint after each space
-                // ignoring any adjacent whitespace
-                if is_space(ch, self.style.pre) {
+                // ignoring any adjacent whitespace (unless it's preserved)
+                if ch == '\n' && white_space.preserve_newlines() {
+                    if let Some(i) = hint.take() {
+                        break_hints.push((i, x));
+                    }
+
+                    break_hints.push((xglyphs.len(), std::f32::MAX));
+                    xglyphs.push((x, 0, scale_font.glyph_id(' ')));
+                    in_space = false;
+                } else if is_space(ch) {
+                    if !in_space || !white_space.collapse_spaces() {
+                        let n = if ch == '\t' && !white_space.collapse_spaces() {
+                            8.
+                        } else {
+                            1.
+                        };
+                        x += n * space + word_spacing + letter_spacing;
+                    }
+
                     if !in_space {
                         in_space = true;
-                        x += scale_font.h_advance(scale_font.glyph_id(' '));
 
-                        if let Some(i) = hint {
+                        if let Some(i) = hint.take() {
                             break_hints.push((i, x));
-                            hint = None;
                         }
                     }
-                // only if pre == false
-                } else if ch == '\n' {
-                    break_hints.push((xglyphs.len(), std::f32::MAX));
-                    xglyphs.push((x, scale_font.glyph_id(' ')));
-                    in_space = false;
                 } else {
                     if in_space {
                         hint = Some(xglyphs.len());
                         in_space = false;
                     }
 
+                    // fallback
+                    let face = self.fonts.face_for(ch);
+                    let scale_font = self.fonts.face(face).1.as_scaled(self.style.font_size);
+
                     let glyph_id = scale_font.glyph_id(ch);
-                    xglyphs.push((x, glyph_id));
-                    x += scale_font.h_advance(glyph_id);
+                    xglyphs.push((x, face, glyph_id));
+                    x += scale_font.h_advance(glyph_id) + letter_spacing;
                 }
             }
 
@@ -157,6 +249,11 @@ impl Text {
                 break_hints.push((i, x));
             }
 
+            // only forced breaks
+            if !white_space.wrap() {
+                break_hints.retain(|&(_, xend)| xend == std::f32::MAX);
+            }
+
             self.single_line.replace(Some(SingleLine { xglyphs, break_hints }));
         }
 
@@ -164,14 +261,31 @@ impl Text {
     }
 }
 
-fn is_space(ch: char, preserve: bool) -> bool {
-    ch == ' ' || !preserve && (ch == '\t' || ch == '\n' || ch == '\r')
+fn is_space(ch: char) -> bool {
+    matches!(ch, ' ' | '\t' | '\n' | '\r')
+}
+
+// first letter of each word
+fn capitalize(text: &str) -> String {
+    let mut res = String::with_capacity(text.len());
+    let mut prev = ' ';
+
+    for ch in text.chars() {
+        match prev.is_whitespace() {
+            true => res.extend(ch.to_uppercase()),
+            false => res.push(ch),
+        }
+
+        prev = ch;
+    }
+
+    res
 }
 
 #[derive(Debug)]
 struct SingleLine {
-    // (x, glyph_id) of each glyph when on single line
-    xglyphs: Vec<(f32, GlyphId)>,
+    // (x, face, glyph_id) of each glyph when on single line
+    xglyphs: Vec<(f32, usize, GlyphId)>,
 
     // word boundaries
     // (start_index, x of the glyph after)
@@ -180,37 +294,119 @@ struct SingleLine {
 
 #[derive(Debug)]
 pub struct GlyphPos {
+    pub font_id: FontId,
     pub glyph: Glyph,
     pub pos: Vec2,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct TextStyle {
-    //pub font_query: FontQuery,
+    // None means default (sans-serif)
+    pub font_family: Option<Atom<String>>,
     pub font_size: f32,
+    pub font_weight: u16,
+    pub italic: bool,
     pub line_height: f32,
-    //pub letter_spacing: f32,
-    //pub word_spacing: f32,
+    pub letter_spacing: f32,
+    pub word_spacing: f32,
     pub align: TextAlign,
-    pub pre: bool,
+    pub transform: TextTransform,
+    pub white_space: WhiteSpace,
+    pub decoration: TextDecoration,
+    pub color: RGBA8,
 }
 
 impl TextStyle {
     pub const DEFAULT: Self = Self {
+        font_family: None,
         font_size: 16.,
+        font_weight: 400,
+        italic: false,
         line_height: 20.,
+        letter_spacing: 0.,
+        word_spacing: 0.,
         align: TextAlign::Left,
-        pre: false,
+        transform: TextTransform::None,
+        white_space: WhiteSpace::Normal,
+        decoration: TextDecoration::NONE,
+        color: [0, 0, 0, 255],
     };
+
+    fn query_font(&self) -> FontStack {
+        query_font(
+            self.font_family.as_ref().map(|f| f.as_str()),
+            self.font_weight,
+            self.italic,
+        )
+    }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub enum TextAlign {
     Left,
     Center,
     Right,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TextTransform {
+    None,
+    Capitalize,
+    Uppercase,
+    Lowercase,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum WhiteSpace {
+    Normal,
+    NoWrap,
+    Pre,
+    PreWrap,
+    PreLine,
+}
+
+impl WhiteSpace {
+    fn collapse_spaces(self) -> bool {
+        matches!(self, Self::Normal | Self::NoWrap | Self::PreLine)
+    }
+
+    fn preserve_newlines(self) -> bool {
+        !matches!(self, Self::Normal | Self::NoWrap)
+    }
+
+    fn wrap(self) -> bool {
+        !matches!(self, Self::NoWrap | Self::Pre)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TextDecoration {
+    pub underline: bool,
+    pub overline: bool,
+    pub line_through: bool,
+    pub style: TextDecorationStyle,
+    pub color: RGBA8,
+}
+
+impl TextDecoration {
+    pub const NONE: Self = Self {
+        underline: false,
+        overline: false,
+        line_through: false,
+        style: TextDecorationStyle::Solid,
+        color: [0, 0, 0, 255],
+    };
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TextDecorationStyle {
+    Solid,
+    Double,
+    Dotted,
+    Dashed,
+    Wavy,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -224,6 +420,63 @@ mod tests {
         assert_eq!(text.measure(100.).1, 30.);
     }
 
+    #[test]
+    fn single_line_width() {
+        let width = |font_size| {
+            let style = TextStyle {
+                font_size,
+                ..TextStyle::DEFAULT
+            };
+            let text = Text::new("XX", &style);
+            let scale_font = text.fonts.primary().as_scaled(font_size);
+
+            (
+                text.measure(f32::MAX).0,
+                2. * scale_font.h_advance(scale_font.glyph_id('X')),
+            )
+        };
+
+        let (w16, expected) = width(16.);
+        assert_eq!(w16, expected);
+
+        let (w32, expected) = width(32.);
+        assert_eq!(w32, expected);
+        assert_eq!(w32, 2. * w16);
+    }
+
+    #[test]
+    fn white_space() {
+        let lines = |white_space| {
+            let text = Text::new(
+                "a  b\nc",
+                &TextStyle {
+                    white_space,
+                    ..TextStyle::DEFAULT
+                },
+            );
+
+            text.measure(1000.).1 / TextStyle::DEFAULT.line_height
+        };
+
+        assert_eq!(lines(WhiteSpace::Normal), 1.);
+        assert_eq!(lines(WhiteSpace::Pre), 2.);
+        assert_eq!(lines(WhiteSpace::PreLine), 2.);
+
+        let nowrap = Text::new(
+            "X XX XXX XXXX",
+            &TextStyle {
+                white_space: WhiteSpace::NoWrap,
+                ..TextStyle::DEFAULT
+            },
+        );
+        assert_eq!(nowrap.measure(30.).1, TextStyle::DEFAULT.line_height);
+    }
+
+    #[test]
+    fn capitalize() {
+        assert_eq!(super::capitalize("hello  big world"), "Hello  Big World");
+    }
+
     #[test]
     fn glyphs() {
         let text = Text::new("Hello", &TextStyle::DEFAULT);
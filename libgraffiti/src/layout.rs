@@ -9,7 +9,9 @@
 #![allow(unused)]
 
 use graffiti_yoga::*;
+use std::cell::RefCell;
 use std::convert::TryInto;
+use std::rc::Rc;
 
 pub struct LayoutStyle {
     // size
@@ -48,6 +50,8 @@ pub struct LayoutStyle {
     pub align_items: Align,
     pub align_self: Align,
     pub justify_content: Justify,
+    pub row_gap: Dimension,
+    pub column_gap: Dimension,
 
     // position
     pub position: Position,
@@ -103,6 +107,8 @@ impl Default for LayoutStyle {
             align_items: Align::Stretch,
             align_self: Align::Auto,
             justify_content: Justify::FlexStart,
+            row_gap: Dimension::Undefined,
+            column_gap: Dimension::Undefined,
 
             // position
             position: Position::Relative,
@@ -130,30 +136,79 @@ pub type Align = YGAlign;
 pub type Justify = YGJustify;
 pub type Position = YGPositionType;
 
+#[derive(Clone)]
 pub enum Dimension {
     Undefined,
     Px(f32),
     Percent(f32),
     Auto,
+    // fn(containing_block_size) -> px
+    Calc(Rc<dyn Fn(f32) -> f32>),
 }
 
-#[derive(Debug)]
-pub struct LayoutNode(YGNodeRef);
+pub struct LayoutNode(YGNodeRef, Box<NodeData>);
+
+// yoga context, so it's reachable from the parent too
+struct NodeData {
+    calcs: RefCell<Vec<CalcDim>>,
+    measure: Option<Box<dyn Fn(f32) -> (f32, f32)>>,
+}
+
+// yoga can't do calc() so it's resolved after the first pass,
+// when we know the size of the containing block
+struct CalcDim {
+    // which yoga setter (and edge), the last value is kept between restyles
+    key: (&'static str, u32),
+    calc: Box<dyn Fn(ContainingBlock) -> f32>,
+    set: Box<dyn Fn(f32)>,
+    resolved: Option<f32>,
+}
+
+#[derive(Clone, Copy)]
+struct ContainingBlock {
+    width: f32,
+    height: f32,
+    row: bool,
+}
+
+#[derive(Clone, Copy)]
+enum Axis {
+    X,
+    Y,
+    // main axis of the parent (flex-basis)
+    Main,
+}
+
+impl Axis {
+    fn size(self, cb: ContainingBlock) -> f32 {
+        match self {
+            Axis::X => cb.width,
+            Axis::Y => cb.height,
+            Axis::Main if cb.row => cb.width,
+            Axis::Main => cb.height,
+        }
+    }
+}
 
 impl LayoutNode {
     pub fn new() -> Self {
-        Self(unsafe { YGNodeNew() })
+        Self::with_data(NodeData::new(None))
+    }
+
+    pub fn new_leaf<F: Fn(f32) -> (f32, f32) + 'static>(measure: F) -> Self {
+        let node = Self::with_data(NodeData::new(Some(Box::new(measure))));
+        unsafe { YGNodeSetMeasureFunc(node.0, Some(measure_node)) }
+
+        node
     }
 
-    pub fn new_leaf<F: Fn(f32) -> (f32, f32)>(measure: F) -> Self {
+    fn with_data(data: NodeData) -> Self {
         unsafe {
             let node = YGNodeNew();
+            let data = Box::new(data);
+            YGNodeSetContext(node, &*data as *const _ as _);
 
-            YGNodeSetMeasureFunc(node, Some(measure_node::<F>));
-            // TODO: drop
-            YGNodeSetContext(node, Box::into_raw(Box::new(measure)) as _);
-
-            Self(node)
+            Self(node, data)
         }
     }
 
@@ -162,25 +217,58 @@ impl LayoutNode {
     }
 
     pub fn set_style(&self, style: LayoutStyle) {
+        let mut calcs = self.1.calcs.borrow_mut();
+        let prev = std::mem::take(&mut *calcs);
+
+        // last resolved value is used until the layout is calculated again
+        // so the extra pass is only needed if it really changes
+        let prev_value = |key| prev.iter().find(|c: &&CalcDim| c.key == key).and_then(|c| c.resolved);
+
         macro_rules! set_dim {
-            ($node:expr, $value:expr; $set:ident $set_perc:ident $($set_auto:ident)*) => (
+            ($node:expr, $axis:expr, $value:expr; $set:ident $set_perc:ident $($set_auto:ident)*) => (
                 unsafe {
                     match $value {
                         Dimension::Px(v) => $set($node.0, v),
                         Dimension::Percent(v) => $set_perc($node.0, v),
                         $(Dimension::Auto => $set_auto($node.0),)*
+                        Dimension::Calc(calc) => {
+                            let node = $node.0;
+                            let key = (stringify!($set), 0);
+                            let resolved = prev_value(key);
+                            $set(node, resolved.unwrap_or(YGUndefined));
+                            let axis = $axis;
+                            calcs.push(CalcDim {
+                                key,
+                                calc: Box::new(move |cb| calc(axis.size(cb))),
+                                set: Box::new(move |v| $set(node, v)),
+                                resolved,
+                            });
+                        }
                         _ => $set($node.0, YGUndefined)
                     }
                 }
             )
         }
         macro_rules! set_edge_dim {
-            ($node:expr, $edge:expr, $value:expr; $set:ident $set_perc:ident $($set_auto:ident)*) => (
+            ($node:expr, $axis:expr, $edge:expr, $value:expr; $set:ident $set_perc:ident $($set_auto:ident)*) => (
                 unsafe {
                     match $value {
                         Dimension::Px(v) => $set($node.0, $edge, v),
                         Dimension::Percent(v) => $set_perc($node.0, $edge, v),
                         $(Dimension::Auto => $set_auto($node.0, $edge),)*
+                        Dimension::Calc(calc) => {
+                            let node = $node.0;
+                            let key = (stringify!($set), $edge as _);
+                            let resolved = prev_value(key);
+                            $set(node, $edge, resolved.unwrap_or(YGUndefined));
+                            let axis = $axis;
+                            calcs.push(CalcDim {
+                                key,
+                                calc: Box::new(move |cb| calc(axis.size(cb))),
+                                set: Box::new(move |v| $set(node, $edge, v)),
+                                resolved,
+                            });
+                        }
                         _ => $set($node.0, $edge, YGUndefined)
                     }
                 }
@@ -188,24 +276,24 @@ impl LayoutNode {
         }
 
         // size
-        set_dim!(self, style.width; YGNodeStyleSetWidth YGNodeStyleSetWidthPercent YGNodeStyleSetWidthAuto);
-        set_dim!(self, style.height; YGNodeStyleSetHeight YGNodeStyleSetHeightPercent YGNodeStyleSetHeightAuto);
-        set_dim!(self, style.min_width; YGNodeStyleSetMinWidth YGNodeStyleSetMinWidthPercent);
-        set_dim!(self, style.min_height; YGNodeStyleSetMinHeight YGNodeStyleSetMinHeightPercent);
-        set_dim!(self, style.max_width; YGNodeStyleSetMaxWidth YGNodeStyleSetMaxWidthPercent);
-        set_dim!(self, style.max_height; YGNodeStyleSetMaxHeight YGNodeStyleSetMaxHeightPercent);
-
-        // padding
-        set_edge_dim!(self, YGEdge::Top, style.padding_top; YGNodeStyleSetPadding YGNodeStyleSetPaddingPercent);
-        set_edge_dim!(self, YGEdge::Right, style.padding_right; YGNodeStyleSetPadding YGNodeStyleSetPaddingPercent);
-        set_edge_dim!(self, YGEdge::Bottom, style.padding_bottom; YGNodeStyleSetPadding YGNodeStyleSetPaddingPercent);
-        set_edge_dim!(self, YGEdge::Left, style.padding_left; YGNodeStyleSetPadding YGNodeStyleSetPaddingPercent);
-
-        // margin
-        set_edge_dim!(self, YGEdge::Top, style.margin_top; YGNodeStyleSetMargin YGNodeStyleSetMarginPercent YGNodeStyleSetMarginAuto);
-        set_edge_dim!(self, YGEdge::Right, style.margin_right; YGNodeStyleSetMargin YGNodeStyleSetMarginPercent YGNodeStyleSetMarginAuto);
-        set_edge_dim!(self, YGEdge::Bottom, style.margin_bottom; YGNodeStyleSetMargin YGNodeStyleSetMarginPercent YGNodeStyleSetMarginAuto);
-        set_edge_dim!(self, YGEdge::Left, style.margin_left; YGNodeStyleSetMargin YGNodeStyleSetMarginPercent YGNodeStyleSetMarginAuto);
+        set_dim!(self, Axis::X, style.width; YGNodeStyleSetWidth YGNodeStyleSetWidthPercent YGNodeStyleSetWidthAuto);
+        set_dim!(self, Axis::Y, style.height; YGNodeStyleSetHeight YGNodeStyleSetHeightPercent YGNodeStyleSetHeightAuto);
+        set_dim!(self, Axis::X, style.min_width; YGNodeStyleSetMinWidth YGNodeStyleSetMinWidthPercent);
+        set_dim!(self, Axis::Y, style.min_height; YGNodeStyleSetMinHeight YGNodeStyleSetMinHeightPercent);
+        set_dim!(self, Axis::X, style.max_width; YGNodeStyleSetMaxWidth YGNodeStyleSetMaxWidthPercent);
+        set_dim!(self, Axis::Y, style.max_height; YGNodeStyleSetMaxHeight YGNodeStyleSetMaxHeightPercent);
+
+        // padding (% is always relative to width)
+        set_edge_dim!(self, Axis::X, YGEdge::Top, style.padding_top; YGNodeStyleSetPadding YGNodeStyleSetPaddingPercent);
+        set_edge_dim!(self, Axis::X, YGEdge::Right, style.padding_right; YGNodeStyleSetPadding YGNodeStyleSetPaddingPercent);
+        set_edge_dim!(self, Axis::X, YGEdge::Bottom, style.padding_bottom; YGNodeStyleSetPadding YGNodeStyleSetPaddingPercent);
+        set_edge_dim!(self, Axis::X, YGEdge::Left, style.padding_left; YGNodeStyleSetPadding YGNodeStyleSetPaddingPercent);
+
+        // margin (% is always relative to width)
+        set_edge_dim!(self, Axis::X, YGEdge::Top, style.margin_top; YGNodeStyleSetMargin YGNodeStyleSetMarginPercent YGNodeStyleSetMarginAuto);
+        set_edge_dim!(self, Axis::X, YGEdge::Right, style.margin_right; YGNodeStyleSetMargin YGNodeStyleSetMarginPercent YGNodeStyleSetMarginAuto);
+        set_edge_dim!(self, Axis::X, YGEdge::Bottom, style.margin_bottom; YGNodeStyleSetMargin YGNodeStyleSetMarginPercent YGNodeStyleSetMarginAuto);
+        set_edge_dim!(self, Axis::X, YGEdge::Left, style.margin_left; YGNodeStyleSetMargin YGNodeStyleSetMarginPercent YGNodeStyleSetMarginAuto);
 
         // border
         unsafe { YGNodeStyleSetBorder(self.0, YGEdge::Top, style.border_top) }
@@ -215,15 +303,15 @@ impl LayoutNode {
 
         // position
         unsafe { YGNodeStyleSetPositionType(self.0, style.position) }
-        set_edge_dim!(self, YGEdge::Top, style.top; YGNodeStyleSetPosition YGNodeStyleSetPositionPercent);
-        set_edge_dim!(self, YGEdge::Right, style.right; YGNodeStyleSetPosition YGNodeStyleSetPositionPercent);
-        set_edge_dim!(self, YGEdge::Bottom, style.bottom; YGNodeStyleSetPosition YGNodeStyleSetPositionPercent);
-        set_edge_dim!(self, YGEdge::Left, style.left; YGNodeStyleSetPosition YGNodeStyleSetPositionPercent);
+        set_edge_dim!(self, Axis::Y, YGEdge::Top, style.top; YGNodeStyleSetPosition YGNodeStyleSetPositionPercent);
+        set_edge_dim!(self, Axis::X, YGEdge::Right, style.right; YGNodeStyleSetPosition YGNodeStyleSetPositionPercent);
+        set_edge_dim!(self, Axis::Y, YGEdge::Bottom, style.bottom; YGNodeStyleSetPosition YGNodeStyleSetPositionPercent);
+        set_edge_dim!(self, Axis::X, YGEdge::Left, style.left; YGNodeStyleSetPosition YGNodeStyleSetPositionPercent);
 
         // flex
         unsafe { YGNodeStyleSetFlexGrow(self.0, style.flex_grow) }
         unsafe { YGNodeStyleSetFlexShrink(self.0, style.flex_shrink) }
-        set_dim!(self, style.flex_basis; YGNodeStyleSetFlexBasis YGNodeStyleSetFlexBasisPercent YGNodeStyleSetFlexBasisAuto);
+        set_dim!(self, Axis::Main, style.flex_basis; YGNodeStyleSetFlexBasis YGNodeStyleSetFlexBasisPercent YGNodeStyleSetFlexBasisAuto);
         unsafe { YGNodeStyleSetFlexDirection(self.0, style.flex_direction) }
         unsafe { YGNodeStyleSetFlexWrap(self.0, style.flex_wrap) }
         unsafe { YGNodeStyleSetAlignContent(self.0, style.align_content) }
@@ -231,6 +319,35 @@ impl LayoutNode {
         unsafe { YGNodeStyleSetAlignSelf(self.0, style.align_self) }
         unsafe { YGNodeStyleSetJustifyContent(self.0, style.justify_content) }
 
+        // gap (yoga has no % gaps so they are resolved like calc(), but against the own content box)
+        for (gutter, axis, gap) in vec![
+            (YGGutter::Row, Axis::Y, style.row_gap),
+            (YGGutter::Column, Axis::X, style.column_gap),
+        ] {
+            let calc: Rc<dyn Fn(f32) -> f32> = match gap {
+                Dimension::Px(v) => {
+                    unsafe { YGNodeStyleSetGap(self.0, gutter, v) }
+                    continue;
+                }
+                Dimension::Percent(v) => Rc::new(move |base| v / 100. * base),
+                Dimension::Calc(calc) => calc,
+                _ => {
+                    unsafe { YGNodeStyleSetGap(self.0, gutter, 0.) }
+                    continue;
+                }
+            };
+            let node = self.0;
+            let key = (GAP, gutter as _);
+            let resolved = prev_value(key);
+            unsafe { YGNodeStyleSetGap(node, gutter, resolved.unwrap_or(0.)) }
+            calcs.push(CalcDim {
+                key,
+                calc: Box::new(move |cb| calc(axis.size(cb))),
+                set: Box::new(move |v| unsafe { YGNodeStyleSetGap(node, gutter, v) }),
+                resolved,
+            });
+        }
+
         // TODO: overflow
 
         // other
@@ -249,6 +366,48 @@ impl LayoutNode {
         unsafe { YGNodeCalculateLayout(self.0, avail_size.0, avail_size.1, YGDirection::LTR) }
     }
 
+    // resolve calc() dimensions against the (already calculated) containing block
+    // (and % gaps against the own content box),
+    // returns true if anything has changed and the layout needs to be calculated again
+    pub fn resolve_calc(&self, containing_block: &LayoutNode) -> bool {
+        let mut calcs = self.1.calcs.borrow_mut();
+
+        if calcs.is_empty() {
+            return false;
+        }
+
+        // absolutely positioned boxes are relative to the padding box
+        let (width, height) = match unsafe { YGNodeStyleGetPositionType(self.0) } {
+            YGPositionType::Absolute => containing_block.padding_box_size(),
+            _ => containing_block.content_size(),
+        };
+        let row = matches!(
+            unsafe { YGNodeStyleGetFlexDirection(containing_block.0) },
+            YGFlexDirection::Row | YGFlexDirection::RowReverse
+        );
+        let (own_width, own_height) = self.content_size();
+        let mut changed = false;
+
+        for c in calcs.iter_mut() {
+            let v = match c.key.0 {
+                GAP => (c.calc)(ContainingBlock {
+                    width: own_width,
+                    height: own_height,
+                    row,
+                }),
+                _ => (c.calc)(ContainingBlock { width, height, row }),
+            };
+
+            if c.resolved != Some(v) {
+                (c.set)(v);
+                c.resolved = Some(v);
+                changed = true;
+            }
+        }
+
+        changed
+    }
+
     #[inline]
     pub fn offset(&self) -> (f32, f32) {
         unsafe { (YGNodeLayoutGetLeft(self.0), YGNodeLayoutGetTop(self.0)) }
@@ -258,31 +417,90 @@ impl LayoutNode {
     pub fn size(&self) -> (f32, f32) {
         unsafe { (YGNodeLayoutGetWidth(self.0), YGNodeLayoutGetHeight(self.0)) }
     }
+
+    // [top, right, bottom, left]
+    pub fn margin(&self) -> [f32; 4] {
+        self.edges(YGNodeLayoutGetMargin)
+    }
+
+    pub fn border(&self) -> [f32; 4] {
+        self.edges(YGNodeLayoutGetBorder)
+    }
+
+    pub fn padding(&self) -> [f32; 4] {
+        self.edges(YGNodeLayoutGetPadding)
+    }
+
+    fn edges(&self, get: unsafe extern "C" fn(YGNodeRef, YGEdge) -> f32) -> [f32; 4] {
+        unsafe {
+            [
+                get(self.0, YGEdge::Top),
+                get(self.0, YGEdge::Right),
+                get(self.0, YGEdge::Bottom),
+                get(self.0, YGEdge::Left),
+            ]
+        }
+    }
+
+    // size without border
+    pub fn padding_box_size(&self) -> (f32, f32) {
+        let (width, height) = self.size();
+        let border = |edge| unsafe { YGNodeLayoutGetBorder(self.0, edge) };
+
+        (
+            width - border(YGEdge::Left) - border(YGEdge::Right),
+            height - border(YGEdge::Top) - border(YGEdge::Bottom),
+        )
+    }
+
+    // size without padding & border
+    pub fn content_size(&self) -> (f32, f32) {
+        let (width, height) = self.size();
+        let inset = |edge| unsafe { YGNodeLayoutGetPadding(self.0, edge) + YGNodeLayoutGetBorder(self.0, edge) };
+
+        (
+            width - inset(YGEdge::Left) - inset(YGEdge::Right),
+            height - inset(YGEdge::Top) - inset(YGEdge::Bottom),
+        )
+    }
+}
+
+impl std::fmt::Debug for LayoutNode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("LayoutNode").field(&self.0).finish()
+    }
 }
 
 impl Drop for LayoutNode {
     fn drop(&mut self) {
-        // TODO: drop measure
-
         unsafe { YGNodeFree(self.0) }
     }
 }
 
-unsafe extern "C" fn measure_node<F: Fn(f32) -> (f32, f32)>(
-    node: YGNodeRef,
-    w: f32,
-    wm: YGMeasureMode,
-    _h: f32,
-    _hm: YGMeasureMode,
-) -> YGSize {
+const GAP: &str = stringify!(YGNodeStyleSetGap);
+
+impl NodeData {
+    fn new(measure: Option<Box<dyn Fn(f32) -> (f32, f32)>>) -> Self {
+        Self {
+            calcs: Default::default(),
+            measure,
+        }
+    }
+
+    unsafe fn get<'a>(node: YGNodeRef) -> &'a Self {
+        &*(YGNodeGetContext(node) as *const Self)
+    }
+}
+
+unsafe extern "C" fn measure_node(node: YGNodeRef, w: f32, wm: YGMeasureMode, _h: f32, _hm: YGMeasureMode) -> YGSize {
     let max_width = match wm {
         YGMeasureMode::Exactly => w,
         YGMeasureMode::AtMost => w,
         YGMeasureMode::Undefined => std::f32::MAX,
     };
 
-    let measure: *mut F = YGNodeGetContext(node) as _;
-    let size = (*measure)(max_width);
+    let measure = NodeData::get(node).measure.as_ref().unwrap();
+    let size = measure(max_width);
 
     YGSize {
         width: match wm {
@@ -292,3 +510,187 @@ unsafe extern "C" fn measure_node<F: Fn(f32) -> (f32, f32)>(
         height: size.1,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn node(width: f32, height: f32) -> LayoutNode {
+        let node = LayoutNode::new();
+        node.set_style(LayoutStyle {
+            width: Dimension::Px(width),
+            height: Dimension::Px(height),
+            ..Default::default()
+        });
+
+        node
+    }
+
+    #[test]
+    fn calc() {
+        let half = || Dimension::Calc(Rc::new(|base| base / 2.));
+        let parent = LayoutNode::new();
+        parent.set_style(LayoutStyle {
+            width: Dimension::Px(100.),
+            height: Dimension::Px(200.),
+            padding_top: Dimension::Px(10.),
+            padding_right: Dimension::Px(10.),
+            padding_bottom: Dimension::Px(10.),
+            padding_left: Dimension::Px(10.),
+            border_top: 5.,
+            border_right: 5.,
+            border_bottom: 5.,
+            border_left: 5.,
+            flex_direction: FlexDirection::Column,
+            align_items: Align::FlexStart,
+            ..Default::default()
+        });
+
+        let style = || LayoutStyle {
+            width: half(),
+            flex_basis: half(),
+            ..Default::default()
+        };
+        let child = LayoutNode::new();
+        child.set_style(style());
+        parent.insert_child(&child, 0);
+
+        let abs = LayoutNode::new();
+        abs.set_style(LayoutStyle {
+            position: Position::Absolute,
+            width: half(),
+            ..Default::default()
+        });
+        parent.insert_child(&abs, 1);
+
+        parent.calculate((500., 500.));
+        assert!(child.resolve_calc(&parent));
+        assert!(abs.resolve_calc(&parent));
+        parent.calculate((500., 500.));
+
+        // content box (70x170), flex-basis is on the main axis (column)
+        assert_eq!(child.size(), (35., 85.));
+        // padding box (90x190)
+        assert_eq!(abs.size().0, 45.);
+
+        // nothing changed, no extra pass
+        assert!(!child.resolve_calc(&parent));
+        child.set_style(style());
+        assert!(!child.resolve_calc(&parent));
+    }
+
+    #[test]
+    fn gap() {
+        let root = LayoutNode::new();
+        root.set_style(LayoutStyle {
+            flex_direction: FlexDirection::Column,
+            ..Default::default()
+        });
+        let parent = LayoutNode::new();
+        root.insert_child(&parent, 0);
+        let children = [node(20., 10.), node(20., 10.), node(20., 10.)];
+
+        for (i, ch) in children.iter().enumerate() {
+            parent.insert_child(ch, i);
+        }
+
+        let offsets = |style: LayoutStyle| {
+            parent.set_style(style);
+            root.calculate((100., 100.));
+
+            if parent.resolve_calc(&root) {
+                root.calculate((100., 100.));
+            }
+
+            children.iter().map(LayoutNode::offset).collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            offsets(LayoutStyle {
+                column_gap: Dimension::Px(10.),
+                ..Default::default()
+            }),
+            vec![(0., 0.), (30., 0.), (60., 0.)]
+        );
+
+        // row-gap is ignored if there's only one line
+        assert_eq!(
+            offsets(LayoutStyle {
+                row_gap: Dimension::Px(10.),
+                ..Default::default()
+            }),
+            vec![(0., 0.), (20., 0.), (40., 0.)]
+        );
+
+        assert_eq!(
+            offsets(LayoutStyle {
+                flex_direction: FlexDirection::Column,
+                align_items: Align::FlexStart,
+                row_gap: Dimension::Px(5.),
+                ..Default::default()
+            }),
+            vec![(0., 0.), (0., 15.), (0., 30.)]
+        );
+
+        assert_eq!(
+            offsets(LayoutStyle {
+                width: Dimension::Px(50.),
+                flex_wrap: FlexWrap::Wrap,
+                align_content: Align::FlexStart,
+                row_gap: Dimension::Px(5.),
+                column_gap: Dimension::Px(10.),
+                ..Default::default()
+            }),
+            vec![(0., 0.), (30., 0.), (0., 15.)]
+        );
+
+        assert_eq!(
+            offsets(LayoutStyle {
+                flex_direction: FlexDirection::RowReverse,
+                column_gap: Dimension::Px(10.),
+                ..Default::default()
+            }),
+            vec![(80., 0.), (50., 0.), (20., 0.)]
+        );
+
+        // % of the content box
+        assert_eq!(
+            offsets(LayoutStyle {
+                column_gap: Dimension::Percent(10.),
+                padding_left: Dimension::Px(20.),
+                ..Default::default()
+            }),
+            vec![(20., 0.), (48., 0.), (76., 0.)]
+        );
+
+        // normal
+        assert_eq!(
+            offsets(LayoutStyle {
+                column_gap: Dimension::Auto,
+                ..Default::default()
+            }),
+            vec![(0., 0.), (20., 0.), (40., 0.)]
+        );
+
+        // own margins are kept, out-of-flow children are skipped
+        children[0].set_style(LayoutStyle {
+            position: Position::Absolute,
+            width: Dimension::Px(20.),
+            height: Dimension::Px(10.),
+            ..Default::default()
+        });
+        children[2].set_style(LayoutStyle {
+            width: Dimension::Px(20.),
+            height: Dimension::Px(10.),
+            margin_left: Dimension::Px(5.),
+            ..Default::default()
+        });
+        assert_eq!(
+            offsets(LayoutStyle {
+                column_gap: Dimension::Px(10.),
+                ..Default::default()
+            }),
+            vec![(0., 0.), (0., 0.), (35., 0.)]
+        );
+    }
+}
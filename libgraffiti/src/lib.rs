@@ -12,10 +12,11 @@ mod window;
 
 pub use self::{
   app::App,
+  css::{CssWarning, CssWarningReason, StyleSheet},
   document::{Document, DocumentEvent, NodeId, NodeType},
-  viewport::Viewport,
+  viewport::{Viewport, ViewportEvent},
   webview::WebView,
-  window::{Window, Event},
+  window::{Cursor, Event, Window},
 };
 pub mod gfx;
 
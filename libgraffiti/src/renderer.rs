@@ -1,5 +1,7 @@
-use crate::css::Style;
-use crate::gfx::{Canvas, Frame, Text, Vec2, AABB, RGBA8};
+use crate::css::{CssBorderStyle, CssDisplay, CssGradient, CssPosition, CssZIndex, Style, StyleProp};
+use crate::gfx::{
+    Canvas, Frame, Gradient, GradientShape, Text, TextDecoration, TextDecorationStyle, Transform, Vec2, AABB, RGBA8,
+};
 use crate::layout::LayoutNode;
 use crate::util::SlotMap;
 use crate::{Document, NodeId, NodeType};
@@ -7,22 +9,28 @@ use std::cell::RefCell;
 use std::rc::Rc;
 
 pub struct RenderStyle {
+    // display: none or visibility: hidden (still laid out)
     pub hidden: bool,
     pub bg_color: Option<RGBA8>,
+    // top-most first
+    pub bg_images: Vec<CssGradient>,
     pub outline: Option<(f32, RGBA8)>,
+    // top, right, bottom, left
+    pub border: Option<[(f32, CssBorderStyle, RGBA8); 4]>,
 }
 
 impl RenderStyle {
     pub const DEFAULT: Self = Self {
         hidden: false,
         bg_color: None,
+        bg_images: Vec::new(),
         outline: None,
+        border: None,
     };
 }
 
 pub struct Renderer {
     document: Rc<RefCell<Document>>,
-    layout_nodes: Rc<RefCell<SlotMap<NodeId, LayoutNode>>>,
     styles: Rc<RefCell<SlotMap<NodeId, Style>>>,
     texts: Rc<RefCell<SlotMap<NodeId, Text>>>,
     canvas: Canvas,
@@ -31,77 +39,58 @@ pub struct Renderer {
 impl Renderer {
     pub fn new(
         document: &Rc<RefCell<Document>>,
-        layout_nodes: &Rc<RefCell<SlotMap<NodeId, LayoutNode>>>,
         styles: &Rc<RefCell<SlotMap<NodeId, Style>>>,
         texts: &Rc<RefCell<SlotMap<NodeId, Text>>>,
     ) -> Self {
         Self {
             document: Rc::clone(&document),
-            layout_nodes: Rc::clone(&layout_nodes),
             styles: Rc::clone(&styles),
             texts: Rc::clone(&texts),
             canvas: Canvas::new(),
         }
     }
 
-    pub fn render<'a>(&'a mut self) -> Frame {
+    // items are expected to be in paint_order()
+    pub fn render(&mut self, items: &[PaintItem]) -> Frame {
         let document = &*self.document.borrow();
-        let layout_nodes = &*self.layout_nodes.borrow();
         let styles = &*self.styles.borrow();
         let texts = &*self.texts.borrow();
-        let root = document.root();
 
         let mut ctx = RenderContext {
-            document,
             canvas: &mut self.canvas,
-            layout_nodes,
-            styles,
-            texts,
         };
 
-        ctx.render_node(Vec2::ZERO, root);
+        for item in items {
+            ctx.canvas.set_transform(item.transform);
 
+            match document.node_type(item.node) {
+                NodeType::Element => ctx.render_element(item.rect, &style(&styles[item.node])),
+                // visibility is inherited so the parent decides
+                _ => {
+                    if document.parent_element(item.node).map_or(true, |p| visible(&styles[p])) {
+                        ctx.render_text(item.rect, &texts[item.node])
+                    }
+                }
+            }
+        }
+
+        ctx.canvas.set_transform(Transform::IDENTITY);
         ctx.canvas.flush()
     }
 }
 
 struct RenderContext<'a> {
-    document: &'a Document,
-    layout_nodes: &'a SlotMap<NodeId, LayoutNode>,
-    styles: &'a SlotMap<NodeId, Style>,
-    texts: &'a SlotMap<NodeId, Text>,
     canvas: &'a mut Canvas,
 }
 
 impl<'a> RenderContext<'a> {
-    fn render_node(&mut self, offset: Vec2, node: NodeId) {
-        let ln = &self.layout_nodes[node];
-        let min = offset + ln.offset().into();
-        let max = min + ln.size().into();
-        let rect = AABB::new(min, max);
-
-        if self.document.node_type(node) == NodeType::Document {
-            for ch in self.document.children(node) {
-                self.render_node(rect.min, ch)
-            }
-        }
-
-        if self.document.node_type(node) == NodeType::Element {
-            self.render_element(rect, &style(&self.styles[node]), self.document.child_nodes(node));
-        }
-
-        if self.document.node_type(node) == NodeType::Text {
-            // TODO: color
-            self.canvas.fill_text(&self.texts[node], rect, [0, 0, 0, 255]);
-        }
-    }
-
-    fn render_element(&mut self, rect: AABB, style: &RenderStyle, children: impl Iterator<Item = NodeId>) {
+    // only the box itself, children are separate paint items
+    fn render_element(&mut self, rect: AABB, style: &RenderStyle) {
         if style.hidden {
             return;
         }
 
-        // TODO: border_radius, clip, scroll, opacity, transform, ...
+        // TODO: border_radius, clip, scroll, opacity, ...
 
         // TODO: outline shadow(s)
 
@@ -113,15 +102,132 @@ impl<'a> RenderContext<'a> {
             self.canvas.fill_rect(rect, bg_color);
         }
 
-        // TODO: image(s)
+        // last layer is painted first
+        for image in style.bg_images.iter().rev() {
+            self.canvas.fill_gradient(rect, &gradient(image, rect));
+        }
 
         // TODO: inset shadow(s)
 
-        for ch in children {
-            self.render_node(rect.min, ch);
+        if let Some(border) = &style.border {
+            self.render_border(rect, border);
+        }
+    }
+
+    fn render_text(&mut self, rect: AABB, text: &Text) {
+        let decoration = &text.style().decoration;
+        let (under, over) = text.decorations(rect);
+
+        for line in under {
+            self.render_decoration(line, decoration);
+        }
+
+        self.canvas.fill_text(text, rect, text.style().color);
+
+        for line in over {
+            self.render_decoration(line, decoration);
+        }
+    }
+
+    fn render_decoration(&mut self, line: AABB, decoration: &TextDecoration) {
+        let thickness = line.size().y;
+
+        match decoration.style {
+            TextDecorationStyle::Dotted | TextDecorationStyle::Dashed => {
+                let len = if decoration.style == TextDecorationStyle::Dotted {
+                    thickness
+                } else {
+                    3. * thickness
+                };
+                let mut x = line.min.x;
+
+                while x < line.max.x {
+                    let part = AABB::new(
+                        Vec2::new(x, line.min.y),
+                        Vec2::new((x + len).min(line.max.x), line.max.y),
+                    );
+                    self.canvas.fill_rect(part, decoration.color);
+                    x += 2. * len;
+                }
+            }
+            TextDecorationStyle::Double => {
+                self.canvas.fill_rect(line, decoration.color);
+                self.canvas
+                    .fill_rect(line + Vec2::new(0., 2. * thickness), decoration.color);
+            }
+            // thickness-wide columns following a sine, gentle enough so they stay connected
+            TextDecorationStyle::Wavy => {
+                let (amplitude, wavelength) = (0.75 * thickness, 6. * thickness);
+                let mut x = line.min.x;
+
+                while x < line.max.x {
+                    let y = amplitude * (std::f32::consts::TAU * (x - line.min.x) / wavelength).sin();
+                    let part = AABB::new(
+                        Vec2::new(x, line.min.y + y),
+                        Vec2::new((x + thickness).min(line.max.x), line.max.y + y),
+                    );
+                    self.canvas.fill_rect(part, decoration.color);
+                    x += thickness;
+                }
+            }
+            _ => self.canvas.fill_rect(line, decoration.color),
         }
+    }
 
-        // TODO: border
+    // TODO: groove, ridge, inset, outset (solid for now)
+    fn render_border(&mut self, rect: AABB, border: &[(f32, CssBorderStyle, RGBA8); 4]) {
+        let AABB { min, max } = rect;
+        let [top, right, bottom, left] = [border[0].0, border[1].0, border[2].0, border[3].0];
+
+        // top & bottom go over the corners, so nothing is painted twice
+        let edges = [
+            AABB::new(min, Vec2::new(max.x, min.y + top)),
+            AABB::new(Vec2::new(max.x - right, min.y + top), Vec2::new(max.x, max.y - bottom)),
+            AABB::new(Vec2::new(min.x, max.y - bottom), max),
+            AABB::new(Vec2::new(min.x, min.y + top), Vec2::new(min.x + left, max.y - bottom)),
+        ];
+
+        for (i, (edge, &(width, style, color))) in edges.iter().zip(border).enumerate() {
+            if width <= 0. {
+                continue;
+            }
+
+            let horizontal = i % 2 == 0;
+
+            // (along, across) -> rect
+            let part = |a: (f32, f32), b: (f32, f32)| match horizontal {
+                true => AABB::new(Vec2::new(a.0, b.0), Vec2::new(a.1, b.1)),
+                false => AABB::new(Vec2::new(b.0, a.0), Vec2::new(b.1, a.1)),
+            };
+            let (along, across) = match horizontal {
+                true => ((edge.min.x, edge.max.x), (edge.min.y, edge.max.y)),
+                false => ((edge.min.y, edge.max.y), (edge.min.x, edge.max.x)),
+            };
+
+            match style {
+                CssBorderStyle::Dotted | CssBorderStyle::Dashed => {
+                    let len = if style == CssBorderStyle::Dotted {
+                        width
+                    } else {
+                        3. * width
+                    };
+                    let mut pos = along.0;
+
+                    while pos < along.1 {
+                        self.canvas
+                            .fill_rect(part((pos, (pos + len).min(along.1)), across), color);
+                        pos += 2. * len;
+                    }
+                }
+                CssBorderStyle::Double => {
+                    let line = width / 3.;
+
+                    self.canvas.fill_rect(part(along, (across.0, across.0 + line)), color);
+                    self.canvas.fill_rect(part(along, (across.1 - line, across.1)), color);
+                }
+                _ => self.canvas.fill_rect(*edge, color),
+            }
+        }
     }
 
     fn render_outline(&mut self, rect: AABB, width: f32, color: RGBA8) {
@@ -153,22 +259,283 @@ impl<'a> RenderContext<'a> {
     }
 }
 
+// transform around the origin (in the same space as the rect)
+// shared with hit-testing so both use the same matrix
+pub(crate) fn transform(style: &Style, rect: AABB) -> Option<Transform> {
+    use super::css::*;
+
+    let mut funcs = None;
+    let mut origin = CssOrigin(CssDimension::Percent(50.), CssDimension::Percent(50.));
+
+    for p in style.props() {
+        match p {
+            StyleProp::Transform(t) if !t.0.is_empty() => funcs = Some(&t.0),
+            StyleProp::TransformOrigin(o) => origin = o.clone(),
+            _ => {}
+        }
+    }
+
+    // % is relative to the border box, units are already resolved
+    let size = rect.size();
+    let px = |d: &CssDimension, base: f32| match d {
+        CssDimension::Px(v) => *v,
+        CssDimension::Percent(v) => v / 100. * base,
+        CssDimension::Calc(c) => c.resolve(base),
+        _ => 0.,
+    };
+
+    let mut res = Transform::IDENTITY;
+
+    for f in funcs? {
+        res = res
+            * match f {
+                CssTransformFn::Translate(x, y) => Transform::translate(px(x, size.x), px(y, size.y)),
+                CssTransformFn::Scale(x, y) => Transform::scale(*x, *y),
+                CssTransformFn::Rotate(a) => Transform::rotate(a.to_radians()),
+                CssTransformFn::Skew(x, y) => Transform::skew(x.to_radians(), y.to_radians()),
+                &CssTransformFn::Matrix([a, b, c, d, e, f]) => Transform::new(a, b, c, d, e, f),
+            };
+    }
+
+    let origin = rect.min + Vec2::new(px(&origin.0, size.x), px(&origin.1, size.y));
+
+    Some(Transform::translate(origin.x, origin.y) * res * Transform::translate(-origin.x, -origin.y))
+}
+
+// element box or text, in absolute (untransformed) coords
+pub(crate) struct PaintItem {
+    pub node: NodeId,
+    pub rect: AABB,
+    pub transform: Transform,
+}
+
+// everything visible, back-to-front, in (simplified) CSS painting order
+// https://www.w3.org/TR/CSS22/zindex.html
+// shared with hit-testing which goes through it in reverse
+pub(crate) fn paint_order(
+    document: &Document,
+    layout_nodes: &SlotMap<NodeId, LayoutNode>,
+    styles: &SlotMap<NodeId, Style>,
+) -> Vec<PaintItem> {
+    let mut ctx = PaintOrder {
+        document,
+        layout_nodes,
+        styles,
+        items: Vec::new(),
+    };
+
+    ctx.stacking_context(document.root(), Vec2::ZERO, Transform::IDENTITY);
+
+    ctx.items
+}
+
+// positioned descendant or nested stacking context, painted after the normal flow
+// (or before it if z-index is negative)
+struct Layer {
+    z_index: i32,
+    node: NodeId,
+    offset: Vec2,
+    transform: Transform,
+    // None for stacking contexts, those are resolved when painted
+    items: Option<Vec<PaintItem>>,
+}
+
+struct PaintOrder<'a> {
+    document: &'a Document,
+    layout_nodes: &'a SlotMap<NodeId, LayoutNode>,
+    styles: &'a SlotMap<NodeId, Style>,
+    items: Vec<PaintItem>,
+}
+
+impl<'a> PaintOrder<'a> {
+    fn rect(&self, offset: Vec2, node: NodeId) -> AABB {
+        let ln = &self.layout_nodes[node];
+        let min = offset + ln.offset().into();
+
+        AABB::new(min, min + ln.size().into())
+    }
+
+    fn stacking_context(&mut self, node: NodeId, offset: Vec2, transform: Transform) {
+        let rect = self.rect(offset, node);
+        let mut transform = transform;
+
+        if self.document.node_type(node) == NodeType::Element {
+            // children are transformed too
+            if let Some(t) = self::transform(&self.styles[node], rect) {
+                transform = transform * t;
+            }
+
+            self.items.push(PaintItem { node, rect, transform });
+        }
+
+        let mut flow = Vec::new();
+        let mut layers = Vec::new();
+        self.flow(node, rect.min, transform, &mut flow, &mut layers);
+
+        // stable, so the tree order is kept for the same z-index
+        layers.sort_by_key(|l| l.z_index);
+        let (negative, rest): (Vec<_>, Vec<_>) = layers.into_iter().partition(|l| l.z_index < 0);
+
+        for l in negative {
+            self.layer(l);
+        }
+
+        self.items.extend(flow);
+
+        for l in rest {
+            self.layer(l);
+        }
+    }
+
+    fn layer(&mut self, layer: Layer) {
+        match layer.items {
+            Some(items) => self.items.extend(items),
+            None => self.stacking_context(layer.node, layer.offset, layer.transform),
+        }
+    }
+
+    // descendants in tree order, positioned ones & stacking contexts are deferred to layers
+    // (including those inside of positioned descendants, they all belong to the same context)
+    fn flow(
+        &self,
+        parent: NodeId,
+        offset: Vec2,
+        transform: Transform,
+        flow: &mut Vec<PaintItem>,
+        layers: &mut Vec<Layer>,
+    ) {
+        let flex_container = self.document.node_type(parent) == NodeType::Element
+            && self.styles[parent]
+                .props()
+                .any(|p| matches!(p, StyleProp::Display(CssDisplay::Flex)));
+
+        for node in self.document.child_nodes(parent) {
+            match self.document.node_type(node) {
+                NodeType::Text => flow.push(PaintItem {
+                    node,
+                    rect: self.rect(offset, node),
+                    transform,
+                }),
+                NodeType::Element => {
+                    let style = &self.styles[node];
+                    let mut positioned = false;
+                    let mut z_index = None;
+                    let mut stacking = false;
+                    let mut hidden = false;
+
+                    for p in style.props() {
+                        match p {
+                            StyleProp::Display(CssDisplay::None) => hidden = true,
+                            StyleProp::Position(pos) => positioned = *pos != CssPosition::Static,
+                            StyleProp::ZIndex(CssZIndex::Index(z)) => z_index = Some(*z),
+                            StyleProp::Opacity(o) if *o < 1. => stacking = true,
+                            StyleProp::Transform(t) if !t.0.is_empty() => stacking = true,
+                            _ => {}
+                        }
+                    }
+
+                    if hidden {
+                        continue;
+                    }
+
+                    // z-index only applies to positioned elements & flex items
+                    let z_index = z_index.filter(|_| positioned || flex_container);
+
+                    if stacking || z_index.is_some() {
+                        layers.push(Layer {
+                            z_index: z_index.unwrap_or(0),
+                            node,
+                            offset,
+                            transform,
+                            items: None,
+                        });
+                        continue;
+                    }
+
+                    let rect = self.rect(offset, node);
+                    let item = PaintItem { node, rect, transform };
+
+                    if positioned {
+                        // reserve the slot first so it's before its own positioned descendants
+                        let i = layers.len();
+                        layers.push(Layer {
+                            z_index: 0,
+                            node,
+                            offset,
+                            transform,
+                            items: None,
+                        });
+
+                        let mut items = vec![item];
+                        self.flow(node, rect.min, transform, &mut items, layers);
+                        layers[i].items = Some(items);
+                    } else {
+                        flow.push(item);
+                        self.flow(node, rect.min, transform, flow, layers);
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+// hidden boxes still take space and their children can be visible again
+pub(crate) fn visible(style: &Style) -> bool {
+    use super::css::CssVisibility;
+
+    !style.props().any(|p| {
+        matches!(
+            p,
+            StyleProp::Visibility(CssVisibility::Hidden | CssVisibility::Collapse)
+        )
+    })
+}
+
 fn style(style: &Style) -> RenderStyle {
     use super::css::*;
 
     let mut res = RenderStyle::DEFAULT;
+    let mut border_widths = [3.; 4];
+    let mut border_styles = [CssBorderStyle::None; 4];
+    let mut border_colors = [None; 4];
+    let mut color = CssColor::BLACK.to_rgba8();
+
+    res.hidden = !visible(style);
 
     for p in style.props() {
         use StyleProp as P;
 
         match p {
             P::Display(CssDisplay::None) => res.hidden = true,
-            P::BackgroundColor(c) => res.bg_color = Some([c.r, c.g, c.b, c.a]),
+            P::Color(c) => color = c.to_rgba8(),
+            P::BorderTopWidth(CssDimension::Px(w)) => border_widths[0] = *w,
+            P::BorderRightWidth(CssDimension::Px(w)) => border_widths[1] = *w,
+            P::BorderBottomWidth(CssDimension::Px(w)) => border_widths[2] = *w,
+            P::BorderLeftWidth(CssDimension::Px(w)) => border_widths[3] = *w,
+            P::BorderTopStyle(s) => border_styles[0] = *s,
+            P::BorderRightStyle(s) => border_styles[1] = *s,
+            P::BorderBottomStyle(s) => border_styles[2] = *s,
+            P::BorderLeftStyle(s) => border_styles[3] = *s,
+            P::BorderTopColor(c) => border_colors[0] = Some(c.to_rgba8()),
+            P::BorderRightColor(c) => border_colors[1] = Some(c.to_rgba8()),
+            P::BorderBottomColor(c) => border_colors[2] = Some(c.to_rgba8()),
+            P::BorderLeftColor(c) => border_colors[3] = Some(c.to_rgba8()),
+            P::BackgroundColor(c) => res.bg_color = Some(c.to_rgba8()),
+            P::BackgroundImage(CssList(images)) => {
+                res.bg_images = images
+                    .iter()
+                    .filter_map(|img| match img {
+                        CssImage::Gradient(g) => Some((**g).clone()),
+                        CssImage::None => None,
+                    })
+                    .collect()
+            }
             P::OutlineColor(c) => {
                 if let Some(o) = &mut res.outline {
-                    o.1 = [c.r, c.g, c.b, c.a];
+                    o.1 = c.to_rgba8();
                 } else {
-                    res.outline = Some((0., [c.r, c.g, c.b, c.a]));
+                    res.outline = Some((0., c.to_rgba8()));
                 }
             }
             P::OutlineWidth(CssDimension::Px(w)) => {
@@ -182,5 +549,154 @@ fn style(style: &Style) -> RenderStyle {
         }
     }
 
+    // same as in LayoutStyle, no style means no border
+    let side = |i: usize| match border_styles[i] {
+        CssBorderStyle::None | CssBorderStyle::Hidden => (0., border_styles[i], color),
+        s => (border_widths[i], s, border_colors[i].unwrap_or(color)),
+    };
+
+    if (0..4).any(|i| side(i).0 > 0.) {
+        res.border = Some([side(0), side(1), side(2), side(3)]);
+    }
+
     res
 }
+
+// resolve to absolute coords & normalized stop offsets
+// https://drafts.csswg.org/css-images-4/#gradients
+fn gradient(gradient: &CssGradient, rect: AABB) -> Gradient {
+    use super::css::*;
+
+    let size = rect.size();
+    let center = rect.min + size / 2.;
+    let px = |d: &CssDimension, base: f32| match d {
+        CssDimension::Px(v) => Some(*v),
+        CssDimension::Percent(v) => Some(v / 100. * base),
+        CssDimension::Calc(c) => Some(c.resolve(base)),
+        _ => None,
+    };
+    let at = |o: &CssOrigin| rect.min + Vec2::new(px(&o.0, size.x).unwrap_or(0.), px(&o.1, size.y).unwrap_or(0.));
+
+    // (shape, length of the gradient line/ray for px stops)
+    let (shape, len) = match &gradient.shape {
+        CssGradientShape::Linear(dir) => {
+            let angle = match *dir {
+                CssGradientDirection::Angle(a) => a.to_radians(),
+                // perpendicular to the diagonal between the other two corners
+                CssGradientDirection::Corner(right, bottom) => {
+                    let sx = if right { 1. } else { -1. };
+                    let sy = if bottom { 1. } else { -1. };
+
+                    (sx * size.y).atan2(-sy * size.x)
+                }
+            };
+            let dir = Vec2::new(angle.sin(), -angle.cos());
+            let len = (size.x * angle.sin()).abs() + (size.y * angle.cos()).abs();
+
+            (
+                GradientShape::Linear(center - dir * (len / 2.), center + dir * (len / 2.)),
+                len,
+            )
+        }
+        CssGradientShape::Radial(circle, extent, o) => {
+            let c = at(o);
+            let (dx, dy) = ((c.x - rect.min.x).abs(), (c.y - rect.min.y).abs());
+            let (dx2, dy2) = ((rect.max.x - c.x).abs(), (rect.max.y - c.y).abs());
+            let (closest, farthest) = (Vec2::new(dx.min(dx2), dy.min(dy2)), Vec2::new(dx.max(dx2), dy.max(dy2)));
+
+            let radius = match (circle, extent) {
+                (true, CssRadialExtent::ClosestSide) => Vec2::ONE * closest.x.min(closest.y),
+                (true, CssRadialExtent::FarthestSide) => Vec2::ONE * farthest.x.max(farthest.y),
+                (true, CssRadialExtent::ClosestCorner) => Vec2::ONE * closest.length(),
+                (true, CssRadialExtent::FarthestCorner) => Vec2::ONE * farthest.length(),
+                (false, CssRadialExtent::ClosestSide) => closest,
+                (false, CssRadialExtent::FarthestSide) => farthest,
+                // same aspect ratio as the side variant, passing through the corner
+                (false, CssRadialExtent::ClosestCorner) => closest * std::f32::consts::SQRT_2,
+                (false, CssRadialExtent::FarthestCorner) => farthest * std::f32::consts::SQRT_2,
+            };
+            let radius = Vec2::new(radius.x.max(0.01), radius.y.max(0.01));
+
+            (GradientShape::Radial(c, radius), radius.x)
+        }
+        CssGradientShape::Conic(from, o) => (GradientShape::Conic(at(o), from.to_radians()), 0.),
+    };
+
+    // missing positions are 0 & 1 at the ends and evenly spaced in between
+    // and every position has to be >= the previous one
+    let mut offsets: Vec<_> = gradient
+        .stops
+        .iter()
+        .map(|CssColorStop(_, pos)| match (pos, len) {
+            (Some(CssDimension::Percent(v)), _) => Some(v / 100.),
+            (Some(d), len) if len > 0. => px(d, len).map(|v| v / len),
+            _ => None,
+        })
+        .collect();
+
+    let last = offsets.len() - 1;
+    offsets[0].get_or_insert(0.);
+    offsets[last].get_or_insert(1.);
+
+    let mut max = f32::NEG_INFINITY;
+
+    for o in offsets.iter_mut().flatten() {
+        *o = o.max(max);
+        max = *o;
+    }
+
+    let mut i = 1;
+
+    while i < last {
+        if offsets[i].is_none() {
+            let end = (i..=last).find(|j| offsets[*j].is_some()).unwrap();
+            let (a, b) = (offsets[i - 1].unwrap(), offsets[end].unwrap());
+
+            for j in i..end {
+                offsets[j] = Some(a + (b - a) * (j - i + 1) as f32 / (end - i + 1) as f32);
+            }
+
+            i = end;
+        }
+
+        i += 1;
+    }
+
+    Gradient {
+        shape,
+        stops: gradient
+            .stops
+            .iter()
+            .zip(offsets)
+            .map(|(CssColorStop(color, _), o)| (o.unwrap(), color.to_rgba8()))
+            .collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::css::{CssColor, CssColorStop, CssDimension, CssGradientDirection, CssGradientShape};
+
+    #[test]
+    fn gradient_stops() {
+        let stop = |pos| CssColorStop(CssColor::BLACK, pos);
+        let css = CssGradient {
+            shape: CssGradientShape::Linear(CssGradientDirection::Angle(180.)),
+            stops: vec![
+                stop(None),
+                stop(Some(CssDimension::Px(20.))),
+                stop(Some(CssDimension::Percent(10.))),
+                stop(None),
+                stop(None),
+                stop(Some(CssDimension::Percent(80.))),
+                stop(None),
+            ],
+        };
+        let g = gradient(&css, AABB::new(Vec2::ZERO, Vec2::new(100., 100.)));
+        let offsets: Vec<_> = g.stops.iter().map(|s| (s.0 * 100.).round()).collect();
+
+        // missing ends, px relative to the gradient line, clamped, evenly spaced
+        assert_eq!(offsets, vec![0., 20., 20., 40., 60., 80., 100.]);
+    }
+}
@@ -37,6 +37,10 @@ impl<V> SlotMap<u32, V> {
             .filter_map(|(i, slot)| slot.as_mut().map(|v| (i as u32, v)))
     }
 
+    pub fn get(&self, key: u32) -> Option<&V> {
+        self.slots.get(key as usize)?.as_ref()
+    }
+
     pub fn slot(&self, key: u32) -> &Option<V> {
         self.slots.get(key as usize).expect("missing slot")
     }
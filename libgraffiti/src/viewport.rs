@@ -1,35 +1,110 @@
 use crate::css::{
-    matching_rules, CssAlign, CssDimension, CssDisplay, CssFlexDirection, CssFlexWrap, CssJustify, CssPosition, Style,
-    StyleProp, StyleSheet,
+    ColorScheme, CssAlign, CssAnimationPlayState, CssBorderStyle, CssColor, CssCursor, CssDimension, CssDisplay,
+    CssFlexDirection, CssFlexWrap, CssFontStyle, CssJustify, CssPointerEvents, CssPosition, CssTextAlign,
+    CssTextDecorationStyle, CssTextTransform, CssTimingFunction, CssWhiteSpace, FontFaceRule, FontFaceSource,
+    KeyframesRule, MediaContext, RuleIndex, SelectorMask, Style, StyleProp, StylePropId, StyleSheet,
+};
+use crate::gfx::{
+    find_local_font, load_font_file, set_font_faces, FontFace, Frame, Text, TextAlign, TextDecoration,
+    TextDecorationStyle, TextStyle, TextTransform, Vec2, WhiteSpace,
 };
-use crate::gfx::{Frame, Text, TextStyle, Vec2, AABB};
 use crate::layout::{Align, Dimension, Display, FlexDirection, FlexWrap, Justify, LayoutNode, LayoutStyle, Position};
-use crate::renderer::Renderer;
-use crate::util::SlotMap;
+use crate::renderer::{paint_order, visible, PaintItem, Renderer};
+use crate::util::{Atom, SlotMap};
 use crate::{Document, DocumentEvent, NodeId, NodeType};
-use std::cell::RefCell;
-use std::collections::BTreeSet;
+use std::cell::{Cell, RefCell};
+use std::collections::{BTreeSet, HashMap, VecDeque};
+use std::path::{Path, PathBuf};
 use std::rc::Rc;
+use std::time::Instant;
+
+const MAX_CALC_PASSES: usize = 3;
 
 pub struct Viewport {
     size: (i32, i32),
+    // media features which have to be provided (window/OS)
+    resolution: f32,
+    prefers_color_scheme: ColorScheme,
+    prefers_reduced_motion: bool,
 
     document: Rc<RefCell<Document>>,
+    ua_sheet: StyleSheet,
+    // to find out if any <style>/<link> was added/removed
+    sheet_sources: RefCell<Vec<SheetSource>>,
+    // <link> & @import, loaded only once
+    loaded_sheets: RefCell<HashMap<PathBuf, StyleSheet>>,
     // TODO: something like hibitset?
+    // subtrees which need to be restyled
     dirty_nodes: Rc<RefCell<BTreeSet<NodeId>>>,
     styles: Rc<RefCell<SlotMap<NodeId, Style>>>,
+    // resolved font-size (for em)
+    font_sizes: RefCell<SlotMap<NodeId, f32>>,
+    // text-decoration is not inherited but it's propagated to all descendant texts
+    decorations: RefCell<SlotMap<NodeId, TextDecoration>>,
+    root_font_size: Cell<f32>,
     layout_nodes: Rc<RefCell<SlotMap<NodeId, LayoutNode>>>,
     texts: Rc<RefCell<SlotMap<NodeId, Text>>>,
+    transitions: Rc<RefCell<SlotMap<NodeId, Vec<Transition>>>>,
+    animations: Rc<RefCell<SlotMap<NodeId, Vec<Animation>>>>,
+    // time of the last update (animations are advanced by the delta)
+    frame_time: Cell<Instant>,
+    events: RefCell<VecDeque<ViewportEvent>>,
+    // last paint order, reused for hit-testing until anything changes
+    paint_items: Rc<RefCell<Option<Vec<PaintItem>>>>,
+    // last pointer position (None if the paint order changed since) & the cursor there
+    cursor_pos: Cell<Option<(f32, f32)>>,
+    cursor: Cell<CssCursor>,
     renderer: Renderer,
 }
 
+// what happened during the last update (to be dispatched in JS)
+#[derive(Debug, Clone, PartialEq)]
+pub enum ViewportEvent {
+    // (node, prop, elapsed time)
+    TransitionEnd(NodeId, StylePropId, f32),
+    // (node, animation name, elapsed time)
+    AnimationStart(NodeId, Atom<String>, f32),
+    AnimationIteration(NodeId, Atom<String>, f32),
+    AnimationEnd(NodeId, Atom<String>, f32),
+}
+
+// where the rules come from
+#[derive(Debug, Clone, PartialEq, Hash)]
+enum SheetSource {
+    Style(NodeId),
+    File(PathBuf),
+}
+
+struct Transition {
+    from: StyleProp,
+    to: StyleProp,
+    start: Instant,
+    delay: f32,
+    duration: f32,
+    timing: CssTimingFunction,
+}
+
+struct Animation {
+    name: Atom<String>,
+    // paused time is not counted
+    elapsed: f32,
+    running: bool,
+    started: bool,
+    iteration: u32,
+    ended: bool,
+}
+
 impl Viewport {
     pub fn new(size: (i32, i32), document: &Rc<RefCell<Document>>) -> Self {
         let styles = Rc::new(RefCell::new(SlotMap::new()));
         let dirty_nodes = Rc::new(RefCell::new(BTreeSet::new()));
+        dirty_nodes.borrow_mut().insert(document.borrow().root());
         let layout_nodes = Rc::new(RefCell::new(SlotMap::new()));
         let texts = Rc::new(RefCell::new(SlotMap::new()));
-        let renderer = Renderer::new(document, &layout_nodes, &styles, &texts);
+        let transitions = Rc::new(RefCell::new(SlotMap::new()));
+        let animations = Rc::new(RefCell::new(SlotMap::new()));
+        let paint_items = Rc::new(RefCell::new(None));
+        let renderer = Renderer::new(document, &styles, &texts);
 
         // create root layout node
         layout_nodes
@@ -39,21 +114,43 @@ impl Viewport {
 
         let viewport = Self {
             size,
+            resolution: 1.,
+            prefers_color_scheme: ColorScheme::Light,
+            prefers_reduced_motion: false,
             document: Rc::clone(&document),
+            ua_sheet: StyleSheet::from(include_str!("../resources/ua.css")),
+            sheet_sources: RefCell::new(Vec::new()),
+            loaded_sheets: RefCell::new(HashMap::new()),
             dirty_nodes: Rc::clone(&dirty_nodes),
             layout_nodes: Rc::clone(&layout_nodes),
             styles: Rc::clone(&styles),
+            font_sizes: RefCell::new(SlotMap::new()),
+            decorations: RefCell::new(SlotMap::new()),
+            root_font_size: Cell::new(TextStyle::DEFAULT.font_size),
             texts: Rc::clone(&texts),
+            transitions: Rc::clone(&transitions),
+            animations: Rc::clone(&animations),
+            frame_time: Cell::new(Instant::now()),
+            events: RefCell::new(VecDeque::new()),
+            paint_items: Rc::clone(&paint_items),
+            cursor_pos: Cell::new(None),
+            cursor: Cell::new(CssCursor::Auto),
             renderer,
         };
 
         document.borrow_mut().add_listener(move |doc, e| {
             use DocumentEvent::*;
 
+            paint_items.borrow_mut().take();
+
             match *e {
                 Create(node, NodeType::Element) => {
+                    // so it's in sync with the empty style
                     layout_nodes.borrow_mut().put(node, LayoutNode::new());
+                    layout_nodes.borrow_mut()[node].set_style(Style::EMPTY.props().into());
                     styles.borrow_mut().put(node, Style::new());
+                    transitions.borrow_mut().put(node, Vec::new());
+                    animations.borrow_mut().put(node, Vec::new());
                 }
                 Create(node, NodeType::Text) => {
                     let texts2 = Rc::clone(&texts);
@@ -70,7 +167,8 @@ impl Viewport {
                 }
 
                 Insert(parent, child, index) => {
-                    layout_nodes.borrow()[parent].insert_child(&layout_nodes.borrow()[child], index)
+                    layout_nodes.borrow()[parent].insert_child(&layout_nodes.borrow()[child], index);
+                    dirty_nodes.borrow_mut().insert(child);
                 }
                 Remove(parent, child) => layout_nodes.borrow()[parent].remove_child(&layout_nodes.borrow()[child]),
 
@@ -79,12 +177,24 @@ impl Viewport {
                     layout_nodes.borrow()[node].mark_dirty();
                 }
 
+                // class, id & inline style (but also attribute selectors later)
+                Attribute(el, _) => {
+                    dirty_nodes.borrow_mut().insert(el);
+                }
+
+                // new/changed rules can match anything
+                StyleSheet(_) => {
+                    dirty_nodes.borrow_mut().insert(doc.root());
+                }
+
                 Drop(node, node_type) => {
                     dirty_nodes.borrow_mut().remove(&node);
                     layout_nodes.borrow_mut().remove(node);
 
                     if node_type == NodeType::Element {
                         styles.borrow_mut().remove(node);
+                        transitions.borrow_mut().remove(node);
+                        animations.borrow_mut().remove(node);
                     }
                 }
 
@@ -101,44 +211,42 @@ impl Viewport {
 
     pub fn render(&mut self) -> Frame {
         self.update();
+        self.update_paint_items();
 
-        self.renderer.render()
+        self.renderer.render(self.paint_items.borrow().as_ref().unwrap())
     }
 
-    // TODO: this is wrong anyway because children can be bigger than their parents
-    //       so we will need some kind of AABB tree anyway
-    // TODO: display: none
+    // TODO: this goes through everything so we will need some kind of AABB tree anyway
     // TODO: scroll
     // TODO: clip (hidden and/or radius)
     pub fn element_from_point(&self, point: (f32, f32)) -> Option<NodeId> {
-        let pos = Vec2::from(point);
+        self.update();
+        self.update_paint_items();
 
-        let document = self.document.borrow();
-        let layout_nodes = self.layout_nodes.borrow();
+        self.hit_test(point)
+    }
 
-        let mut parent = document.first_child(document.root())?;
-        let mut offset = Vec2::ZERO;
-        let mut continue_down;
+    // cursor of the element under the pointer (inherited like in the browser)
+    // TODO: auto should be text over the text nodes
+    pub fn cursor_at(&self, point: (f32, f32)) -> CssCursor {
+        self.element_cursor(self.element_from_point(point))
+    }
 
-        // go down (starting from root) through each matching surface and return the last & deepest one
-        loop {
-            continue_down = false;
-            offset = Vec2::from(layout_nodes[parent].offset()) + offset;
+    // Some(cursor) if it's different than the last time,
+    // hit-testing is skipped if neither the pointer nor the paint order has changed
+    pub fn update_cursor(&self, point: (f32, f32)) -> Option<CssCursor> {
+        self.update();
+        self.update_paint_items();
 
-            for el in document.children(parent) {
-                let rect = AABB::new(Vec2::ZERO, Vec2::from(layout_nodes[el].size()))
-                    + Vec2::from(layout_nodes[el].offset())
-                    + offset;
+        if self.cursor_pos.replace(Some(point)) == Some(point) {
+            return None;
+        }
 
-                if rect.contains(pos) {
-                    parent = el;
-                    continue_down = true;
-                }
-            }
+        let cursor = self.element_cursor(self.hit_test(point));
 
-            if !continue_down {
-                return Some(parent);
-            }
+        match self.cursor.replace(cursor) != cursor {
+            true => Some(cursor),
+            false => None,
         }
     }
 
@@ -148,10 +256,102 @@ impl Viewport {
 
     // TODO: scrollTo(), scrollTop, ...
 
-    // TODO: computed_style?
+    // resolved values of all supported props (window.getComputedStyle)
+    // box-model lengths are taken from the layout
+    pub fn computed_style(&self, el: NodeId) -> Style {
+        self.update();
+
+        // text nodes & dropped elements
+        let styles = self.styles.borrow();
+        let style = match styles.get(el) {
+            Some(style) => style,
+            None => return Style::new(),
+        };
+        let mut res = Style::new();
+
+        for id in StylePropId::ALL {
+            if let Some(p) = style.find_prop(*id).cloned().or_else(|| id.initial_value()) {
+                res.add_prop(p);
+            }
+        }
+
+        // initial values can be currentcolor too
+        res.compute(None, Style::new);
+        res.resolve_current_color();
+
+        if let Some(StyleProp::Display(CssDisplay::None)) = res.find_prop(StylePropId::Display) {
+            return res;
+        }
+
+        let ln = &self.layout_nodes.borrow()[el];
+        let (width, height) = ln.content_size();
+        let px = CssDimension::Px;
+        let (margin, border, padding) = (ln.margin(), ln.border(), ln.padding());
+
+        for p in [
+            StyleProp::Width(px(width)),
+            StyleProp::Height(px(height)),
+            StyleProp::MarginTop(px(margin[0])),
+            StyleProp::MarginRight(px(margin[1])),
+            StyleProp::MarginBottom(px(margin[2])),
+            StyleProp::MarginLeft(px(margin[3])),
+            StyleProp::BorderTopWidth(px(border[0])),
+            StyleProp::BorderRightWidth(px(border[1])),
+            StyleProp::BorderBottomWidth(px(border[2])),
+            StyleProp::BorderLeftWidth(px(border[3])),
+            StyleProp::PaddingTop(px(padding[0])),
+            StyleProp::PaddingRight(px(padding[1])),
+            StyleProp::PaddingBottom(px(padding[2])),
+            StyleProp::PaddingLeft(px(padding[3])),
+        ]
+        .iter()
+        {
+            res.add_prop(p.clone());
+        }
+
+        res
+    }
+
+    // events are collected during render()
+    pub fn next_event(&mut self) -> Option<ViewportEvent> {
+        self.events.get_mut().pop_front()
+    }
+
+    // if there's anything running, render() should be called again soon
+    pub fn is_animating(&self) -> bool {
+        self.transitions.borrow().iter().any(|(_, ts)| !ts.is_empty())
+            || self
+                .animations
+                .borrow()
+                .iter()
+                .any(|(_, anims)| anims.iter().any(|a| a.running && !a.ended))
+    }
 
     pub fn resize(&mut self, size: (i32, i32)) {
         self.size = size;
+
+        // media queries & vw/vh units
+        self.restyle_all();
+    }
+
+    // dppx
+    pub fn set_resolution(&mut self, resolution: f32) {
+        self.resolution = resolution;
+        self.restyle_all();
+    }
+
+    pub fn set_prefers_color_scheme(&mut self, scheme: ColorScheme) {
+        self.prefers_color_scheme = scheme;
+        self.restyle_all();
+    }
+
+    pub fn set_prefers_reduced_motion(&mut self, reduce: bool) {
+        self.prefers_reduced_motion = reduce;
+        self.restyle_all();
+    }
+
+    fn restyle_all(&self) {
+        self.dirty_nodes.borrow_mut().insert(self.document.borrow().root());
         self.update();
     }
 
@@ -160,96 +360,417 @@ impl Viewport {
         self.update_layout();
     }
 
+    // topmost element (last painted one), expects up-to-date paint items
+    fn hit_test(&self, point: (f32, f32)) -> Option<NodeId> {
+        let pos = Vec2::from(point);
+        let document = self.document.borrow();
+        let styles = self.styles.borrow();
+        let paint_items = self.paint_items.borrow();
+
+        // pos is mapped back to the local space of each item
+        let hit = paint_items
+            .iter()
+            .flatten()
+            .rev()
+            .filter(|item| document.node_type(item.node) == NodeType::Element && hit_testable(&styles[item.node]))
+            .find(|item| match item.transform.invert() {
+                Some(t) => item.rect.contains(t.apply(pos)),
+                None => false,
+            });
+
+        match hit {
+            Some(item) => Some(item.node),
+            None => document.first_child(document.root()),
+        }
+    }
+
+    fn element_cursor(&self, el: Option<NodeId>) -> CssCursor {
+        let el = match el {
+            Some(el) => el,
+            None => return CssCursor::Auto,
+        };
+
+        match self.styles.borrow()[el].find_prop(StylePropId::Cursor) {
+            Some(StyleProp::Cursor(c)) => *c,
+            _ => CssCursor::Auto,
+        }
+    }
+
+    fn update_paint_items(&self) {
+        if self.paint_items.borrow().is_none() {
+            let items = paint_order(
+                &self.document.borrow(),
+                &self.layout_nodes.borrow(),
+                &self.styles.borrow(),
+            );
+            self.paint_items.replace(Some(items));
+            self.cursor_pos.set(None);
+        }
+    }
+
     fn update_styles(&self) {
+        // parse new/changed sheets first (they are kept in <style> weak data)
+        let sources: Vec<_> = {
+            let doc = self.document.borrow();
+            let base = doc.base_url().and_then(base_dir);
+
+            // <link> is allowed in <body> too
+            doc.query_selector_all(doc.root(), "html > head > style, link")
+                .into_iter()
+                .filter_map(|el| match doc.local_name(el) {
+                    "style" => Some(SheetSource::Style(el)),
+                    _ => stylesheet_link(&doc, el, base.as_deref()).map(SheetSource::File),
+                })
+                .collect()
+        };
+
+        for s in &sources {
+            match s {
+                SheetSource::Style(el) => {
+                    let mut doc = self.document.borrow_mut();
+
+                    // new or changed text
+                    if doc.weak_data::<StyleSheet>(*el).is_none() {
+                        doc.style_sheet(*el);
+                        self.dirty_nodes.borrow_mut().insert(doc.root());
+                    }
+                }
+                SheetSource::File(path) => load_sheet(&self.loaded_sheets, path),
+            }
+        }
+
         let doc = self.document.borrow();
+        let mut dirty_nodes = self.dirty_nodes.borrow_mut();
         let mut styles = self.styles.borrow_mut();
         let layout_nodes = self.layout_nodes.borrow_mut();
+        let mut texts = self.texts.borrow_mut();
+        let mut transitions = self.transitions.borrow_mut();
+        let mut animations = self.animations.borrow_mut();
+        let mut events = self.events.borrow_mut();
+        let mut font_sizes = self.font_sizes.borrow_mut();
+        let mut decorations = self.decorations.borrow_mut();
+
+        // frame clock
+        let now = Instant::now();
+        let dt = now.duration_since(self.frame_time.replace(now)).as_secs_f32();
+
+        if *self.sheet_sources.borrow() != sources {
+            dirty_nodes.insert(doc.root());
+            self.sheet_sources.replace(sources.clone());
+        }
+
+        // running transitions & animations need to be updated every frame
+        for (el, ts) in transitions.iter() {
+            if !ts.is_empty() {
+                dirty_nodes.insert(el);
+            }
+        }
+
+        for (el, anims) in animations.iter() {
+            if anims.iter().any(|a| a.running && !a.ended) {
+                dirty_nodes.insert(el);
+            }
+        }
+
+        if dirty_nodes.is_empty() {
+            return;
+        }
+
+        self.paint_items.borrow_mut().take();
 
-        let mut sheets: Vec<_> = doc
-            .query_selector_all(doc.root(), "html > head > style")
+        let restyle_all = dirty_nodes.contains(&doc.root());
+
+        let viewport_size = (self.size.0 as f32, self.size.1 as f32);
+
+        // media queries are evaluated here (so resize will apply them)
+        let media = MediaContext {
+            resolution: self.resolution,
+            prefers_color_scheme: self.prefers_color_scheme,
+            prefers_reduced_motion: self.prefers_reduced_motion,
+            ..MediaContext::new(viewport_size.0, viewport_size.1)
+        };
+
+        // imported sheets go before the importing one
+        let mut all_sources = Vec::new();
+        for s in sources {
+            collect_sheets(&doc, &self.loaded_sheets, s, &media, &mut Vec::new(), &mut all_sources);
+        }
+
+        // href or base url has changed, files which are not used anymore will be loaded again
+        if restyle_all {
+            self.loaded_sheets.borrow_mut().retain(|path, _| {
+                all_sources
+                    .iter()
+                    .any(|s| matches!(s, SheetSource::File(p) if p == path))
+            });
+        }
+
+        let loaded_sheets = self.loaded_sheets.borrow();
+        let sheets: Vec<_> = std::iter::once(&self.ua_sheet)
+            .chain(
+                all_sources
+                    .iter()
+                    .filter_map(|s| sheet_with_base(&doc, &loaded_sheets, s).0),
+            )
+            .collect();
+
+        let rules: Vec<_> = sheets.iter().flat_map(|s| s.active_rules(&media)).collect();
+        let ua_rules = &rules[..sheets[0].active_rules(&media).len()];
+
+        // last one wins
+        let keyframes: HashMap<_, _> = sheets
             .iter()
-            //.inspect(|s| println!("style: {}", doc.text_content(**s)))
-            .map(|s| StyleSheet::from(&*doc.text_content(*s)))
+            .flat_map(|s| s.keyframes(&media))
+            .map(|k| (&*k.name, k))
             .collect();
 
-        sheets.insert(0, StyleSheet::from(include_str!("../resources/ua.css")));
+        // new @font-face means all texts need to be re-shaped
+        // (fonts can only change with sheets and then everything is restyled)
+        let mut fonts_changed = false;
+
+        if restyle_all {
+            for s in &all_sources {
+                let (sheet, base) = sheet_with_base(&doc, &loaded_sheets, s);
+                let faces = sheet
+                    .iter()
+                    .flat_map(|s| s.font_faces(&media))
+                    .filter_map(|f| font_face(f, base.as_deref()))
+                    .collect();
+
+                // the same sheet replaces its previous faces
+                fonts_changed |= set_font_faces(&(Rc::as_ptr(&self.document) as usize, s), faces);
+            }
+        }
+
+        // skip detached nodes & those which will be restyled with their ancestor
+        let is_subtree_root = |node| {
+            let mut ancestor = doc.parent(node);
+
+            while let Some(a) = ancestor {
+                if dirty_nodes.contains(&a) {
+                    return false;
+                }
+
+                if a == doc.root() {
+                    return true;
+                }
+
+                ancestor = doc.parent(a);
+            }
+
+            node == doc.root()
+        };
+
+        let mut els = Vec::new();
+
+        for &node in dirty_nodes.iter().filter(|n| is_subtree_root(**n)) {
+            match doc.node_type(node) {
+                NodeType::Element => {
+                    els.push(node);
+                    els.extend(doc.descendant_children(node));
+                }
+                NodeType::Text => {
+                    if let Some(parent) = doc.parent_element(node) {
+                        let text_style = text_style(&styles[parent], decorations[parent]);
+
+                        if texts[node].style() != &text_style {
+                            texts[node].set_style(&text_style);
+                            layout_nodes[node].mark_dirty();
+                        }
+                    }
+                }
+                _ if node == doc.root() => els.extend(doc.descendant_children(node)),
+                _ => {}
+            }
+        }
+
+        dirty_nodes.clear();
+
+        let index = RuleIndex::new(&rules);
+        let ua_index = RuleIndex::new(ua_rules);
+
+        // for descendant selectors
+        let mut ancestor_masks: HashMap<NodeId, SelectorMask> = HashMap::new();
 
         doc.with_matching_context(|ctx| {
-            for (el, out) in styles.iter_mut() {
+            let cascade = |index: &RuleIndex, el, ancestors| {
                 // TODO: just iterate props, no need to merge anymore
                 let mut style = Style::new();
 
-                for r in matching_rules(&ctx, &sheets, el) {
+                for r in index.matching_rules(&ctx, el, ancestors) {
                     for p in r.style().props() {
                         style.add_prop(p.clone());
                     }
                 }
 
+                style
+            };
+
+            // parents first (we need their computed style)
+            for el in els {
+                let ancestors = match doc.parent_element(el) {
+                    Some(p) => match ancestor_masks.get(&p) {
+                        Some(mask) => *mask | ctx.mask(p),
+                        None => ctx.ancestor_mask(el),
+                    },
+                    None => SelectorMask::default(),
+                };
+                ancestor_masks.insert(el, ancestors);
+
+                let mut style = cascade(&index, el, ancestors);
+
                 // add inline style
                 for p in doc.element_style(el).props() {
                     style.add_prop(p.clone());
                 }
 
-                layout_nodes[el].set_style(style.props().into());
+                // inherit & resolve keywords (revert goes back to ua.css)
+                style.compute(doc.parent_element(el).map(|p| &styles[p]), || {
+                    cascade(&ua_index, el, ancestors)
+                });
+
+                // before units are resolved (keyframes can use em, vw, ...)
+                let animated = update_animations(el, &mut animations[el], &keyframes, &mut style, dt, &mut events);
+
+                let font_size = match doc.parent_element(el) {
+                    Some(parent) => style.resolve_units(font_sizes[parent], self.root_font_size.get(), viewport_size),
+                    None => {
+                        let default = TextStyle::DEFAULT.font_size;
+                        let font_size = style.resolve_units(default, default, viewport_size);
+                        self.root_font_size.set(font_size);
+                        font_size
+                    }
+                };
+
+                font_sizes.put(el, font_size);
+
+                // styles[el] is still what was displayed last time
+                update_transitions(
+                    el,
+                    &mut transitions[el],
+                    &styles[el],
+                    &mut style,
+                    &animated,
+                    now,
+                    &mut events,
+                );
+
+                // after both animations and transitions (color can be animated too)
+                style.resolve_current_color();
+
+                // yoga would re-layout the whole subtree otherwise
+                if !layout_props(&styles[el]).eq(layout_props(&style)) {
+                    layout_nodes[el].set_style(style.props().into());
+                }
+
+                // text nodes are styled by their parent
+                let parent_decoration = match doc.parent_element(el) {
+                    Some(parent) => decorations[parent],
+                    None => TextDecoration::NONE,
+                };
+                let text_style = text_style(&style, parent_decoration);
+                decorations.put(el, text_style.decoration);
+
+                for ch in doc.child_nodes(el) {
+                    if doc.node_type(ch) == NodeType::Text && (fonts_changed || texts[ch].style() != &text_style) {
+                        texts[ch].set_style(&text_style);
+                        layout_nodes[ch].mark_dirty();
+                    }
+                }
 
                 // TODO: keep just renderstyle
-                *out = style;
+                styles[el] = style;
             }
         });
     }
 
     fn update_layout(&self) {
         let size = (self.size.0 as _, self.size.1 as _);
+        let doc = self.document.borrow();
+        let layout_nodes = self.layout_nodes.borrow();
+        let root = &layout_nodes[doc.root()];
 
-        self.layout_nodes.borrow()[self.document.borrow().root()].calculate(size);
+        root.calculate(size);
+
+        // calc() needs the size of the containing block so it can't be done in one pass
+        // (and it can be nested, so it might need few more)
+        for _ in 0..MAX_CALC_PASSES {
+            let mut changed = false;
+
+            for (node, ln) in layout_nodes.iter() {
+                if let Some(parent) = doc.parent(node) {
+                    changed |= ln.resolve_calc(&layout_nodes[parent]);
+                }
+            }
+
+            if !changed {
+                break;
+            }
+
+            root.calculate(size);
+        }
     }
 }
 
 impl<'a, I: Iterator<Item = &'a StyleProp>> From<I> for LayoutStyle {
     fn from(props: I) -> Self {
         let mut res = LayoutStyle::default();
+        let mut border_widths = [3.; 4];
+        let mut border_styles = [CssBorderStyle::None; 4];
 
         for p in props {
             use StyleProp as P;
 
             match *p {
                 // size
-                P::Width(v) => res.width = v.into(),
-                P::Height(v) => res.height = v.into(),
-                P::MinWidth(v) => res.min_width = v.into(),
-                P::MinHeight(v) => res.min_height = v.into(),
-                P::MaxWidth(v) => res.max_width = v.into(),
-                P::MaxHeight(v) => res.max_height = v.into(),
+                P::Width(ref v) => res.width = v.into(),
+                P::Height(ref v) => res.height = v.into(),
+                P::MinWidth(ref v) => res.min_width = v.into(),
+                P::MinHeight(ref v) => res.min_height = v.into(),
+                P::MaxWidth(ref v) => res.max_width = v.into(),
+                P::MaxHeight(ref v) => res.max_height = v.into(),
 
                 // padding
-                P::PaddingTop(v) => res.padding_top = v.into(),
-                P::PaddingRight(v) => res.padding_right = v.into(),
-                P::PaddingBottom(v) => res.padding_bottom = v.into(),
-                P::PaddingLeft(v) => res.padding_left = v.into(),
+                P::PaddingTop(ref v) => res.padding_top = v.into(),
+                P::PaddingRight(ref v) => res.padding_right = v.into(),
+                P::PaddingBottom(ref v) => res.padding_bottom = v.into(),
+                P::PaddingLeft(ref v) => res.padding_left = v.into(),
 
                 // margin
-                P::MarginTop(v) => res.margin_top = v.into(),
-                P::MarginRight(v) => res.margin_right = v.into(),
-                P::MarginBottom(v) => res.margin_bottom = v.into(),
-                P::MarginLeft(v) => res.margin_left = v.into(),
+                P::MarginTop(ref v) => res.margin_top = v.into(),
+                P::MarginRight(ref v) => res.margin_right = v.into(),
+                P::MarginBottom(ref v) => res.margin_bottom = v.into(),
+                P::MarginLeft(ref v) => res.margin_left = v.into(),
+
+                // border
+                P::BorderTopWidth(CssDimension::Px(v)) => border_widths[0] = v,
+                P::BorderRightWidth(CssDimension::Px(v)) => border_widths[1] = v,
+                P::BorderBottomWidth(CssDimension::Px(v)) => border_widths[2] = v,
+                P::BorderLeftWidth(CssDimension::Px(v)) => border_widths[3] = v,
+                P::BorderTopStyle(v) => border_styles[0] = v,
+                P::BorderRightStyle(v) => border_styles[1] = v,
+                P::BorderBottomStyle(v) => border_styles[2] = v,
+                P::BorderLeftStyle(v) => border_styles[3] = v,
 
                 // position
                 P::Position(v) => res.position = v.into(),
-                P::Top(v) => res.top = v.into(),
-                P::Right(v) => res.right = v.into(),
-                P::Bottom(v) => res.bottom = v.into(),
-                P::Left(v) => res.left = v.into(),
+                P::Top(ref v) => res.top = v.into(),
+                P::Right(ref v) => res.right = v.into(),
+                P::Bottom(ref v) => res.bottom = v.into(),
+                P::Left(ref v) => res.left = v.into(),
 
                 // flex
                 P::FlexGrow(v) => res.flex_grow = v,
                 P::FlexShrink(v) => res.flex_shrink = v,
-                P::FlexBasis(v) => res.flex_basis = v.into(),
+                P::FlexBasis(ref v) => res.flex_basis = v.into(),
                 P::FlexWrap(v) => res.flex_wrap = v.into(),
                 P::FlexDirection(v) => res.flex_direction = v.into(),
                 P::AlignContent(v) => res.align_content = v.into(),
                 P::AlignItems(v) => res.align_items = v.into(),
                 P::AlignSelf(v) => res.align_self = v.into(),
                 P::JustifyContent(v) => res.justify_content = v.into(),
+                P::RowGap(ref v) => res.row_gap = v.into(),
+                P::ColumnGap(ref v) => res.column_gap = v.into(),
 
                 // other
                 P::Display(v) => {
@@ -279,17 +800,44 @@ impl<'a, I: Iterator<Item = &'a StyleProp>> From<I> for LayoutStyle {
             }
         }
 
+        // width is ignored if there's no border
+        let border_width = |i: usize| match border_styles[i] {
+            CssBorderStyle::None | CssBorderStyle::Hidden => 0.,
+            _ => border_widths[i],
+        };
+        res.border_top = border_width(0);
+        res.border_right = border_width(1);
+        res.border_bottom = border_width(2);
+        res.border_left = border_width(3);
+
         res
     }
 }
 
-impl From<CssDimension> for Dimension {
-    fn from(v: CssDimension) -> Self {
-        match v {
+fn layout_props(style: &Style) -> impl Iterator<Item = &StyleProp> {
+    style.props().filter(|p| p.id().affects_layout())
+}
+
+impl From<&CssDimension> for Dimension {
+    fn from(v: &CssDimension) -> Self {
+        match *v {
             CssDimension::Px(v) => Self::Px(v),
             CssDimension::Percent(v) => Self::Percent(v),
             CssDimension::Auto => Self::Auto,
             // ? => Self::Undefined,
+
+            // should be resolved by now
+            CssDimension::Em(_)
+            | CssDimension::Rem(_)
+            | CssDimension::Vw(_)
+            | CssDimension::Vh(_)
+            | CssDimension::Vmin(_)
+            | CssDimension::Vmax(_) => Self::Undefined,
+
+            CssDimension::Calc(ref calc) => {
+                let calc = calc.clone();
+                Self::Calc(Rc::new(move |base| calc.resolve(base)))
+            }
         }
     }
 }
@@ -352,3 +900,824 @@ impl From<CssFlexDirection> for FlexDirection {
         }
     }
 }
+
+// start/cancel transitions and replace target values with interpolated ones
+// TODO: transitions from/to initial values (missing props)
+fn update_transitions(
+    el: NodeId,
+    transitions: &mut Vec<Transition>,
+    prev: &Style,
+    style: &mut Style,
+    animated: &[StylePropId],
+    now: Instant,
+    events: &mut VecDeque<ViewportEvent>,
+) {
+    // cancel those which are no longer applicable (animations take precedence)
+    transitions.retain(|t| {
+        let id = t.to.id();
+        style.find_prop(id).is_some() && style.transition(id).is_some() && !animated.contains(&id)
+    });
+
+    for to in style.props() {
+        let id = to.id();
+
+        if animated.contains(&id) {
+            continue;
+        }
+        let (duration, delay, timing) = match style.transition(id) {
+            Some(v) => v,
+            None => continue,
+        };
+        let running = transitions.iter().position(|t| t.to.id() == id);
+
+        if let Some(i) = running {
+            if &transitions[i].to == to {
+                continue;
+            }
+        }
+
+        // currentColor endpoints are resolved (again) during the transition
+        let resolved = resolve_current_color(to, style.current_color());
+
+        match prev.find_prop(id) {
+            Some(from) if *from != resolved && from.interpolate(&resolved, 0.).is_some() => {
+                let transition = Transition {
+                    from: from.clone(),
+                    to: to.clone(),
+                    start: now,
+                    delay,
+                    duration,
+                    timing,
+                };
+
+                // start or retarget (from the currently displayed value)
+                match running {
+                    Some(i) => transitions[i] = transition,
+                    None => transitions.push(transition),
+                }
+            }
+
+            // not animatable (anymore)
+            _ => {
+                if let Some(i) = running {
+                    transitions.remove(i);
+                }
+            }
+        }
+    }
+
+    // color goes first, others might depend on it
+    transitions.sort_by_key(|t| t.to.id() != StylePropId::Color);
+
+    let mut i = 0;
+
+    while i < transitions.len() {
+        let t = &transitions[i];
+        let elapsed = now.duration_since(t.start).as_secs_f32() - t.delay;
+
+        if elapsed >= t.duration {
+            events.push_back(ViewportEvent::TransitionEnd(el, t.to.id(), t.duration));
+            transitions.remove(i);
+            continue;
+        }
+
+        let progress = t.timing.at((elapsed / t.duration).max(0.));
+
+        if let Some(v) = t
+            .from
+            .interpolate(&resolve_current_color(&t.to, style.current_color()), progress)
+        {
+            style.add_prop(v);
+        }
+
+        i += 1;
+    }
+}
+
+fn resolve_current_color(prop: &StyleProp, current_color: CssColor) -> StyleProp {
+    let mut prop = prop.clone();
+    prop.resolve_current_color(current_color);
+    prop
+}
+
+// advance animations by dt, dispatch events and apply keyframes
+// returns ids of animated props (those should not be transitioned)
+fn update_animations(
+    el: NodeId,
+    animations: &mut Vec<Animation>,
+    keyframes: &HashMap<&str, &KeyframesRule>,
+    style: &mut Style,
+    dt: f32,
+    events: &mut VecDeque<ViewportEvent>,
+) -> Vec<StylePropId> {
+    let css_animations = style.animations();
+
+    // keep running ones (by name), start new ones, drop removed ones
+    let mut prev = std::mem::take(animations);
+
+    for a in &css_animations {
+        match prev.iter().position(|p| p.name == a.name) {
+            Some(i) => {
+                let mut anim = prev.remove(i);
+
+                if anim.running && !anim.ended {
+                    anim.elapsed += dt;
+                }
+
+                animations.push(anim)
+            }
+            None => animations.push(Animation {
+                name: a.name.clone(),
+                elapsed: 0.,
+                running: false,
+                started: false,
+                iteration: 0,
+                ended: false,
+            }),
+        }
+    }
+
+    let mut animated = Vec::new();
+
+    for (anim, css) in animations.iter_mut().zip(&css_animations) {
+        // TODO: animationcancel (and what should happen if @keyframes is missing?)
+        let keyframes = match keyframes.get(&**css.name) {
+            Some(k) => k,
+            None => {
+                anim.running = false;
+                continue;
+            }
+        };
+
+        anim.running = css.play_state == CssAnimationPlayState::Running;
+
+        let local = anim.elapsed - css.delay;
+        let active_duration = css.active_duration();
+
+        // zero duration jumps straight to the end
+        if active_duration == 0. {
+            if !anim.ended && local >= 0. {
+                anim.started = true;
+                anim.ended = true;
+                events.push_back(ViewportEvent::AnimationEnd(el, anim.name.clone(), 0.));
+            }
+        } else if !anim.started && local >= 0. {
+            anim.started = true;
+            events.push_back(ViewportEvent::AnimationStart(el, anim.name.clone(), 0.));
+        }
+
+        if anim.started && !anim.ended {
+            if local >= active_duration {
+                anim.ended = true;
+                events.push_back(ViewportEvent::AnimationEnd(el, anim.name.clone(), active_duration));
+            } else if (local / css.duration) as u32 > anim.iteration {
+                anim.iteration = (local / css.duration) as u32;
+                let elapsed = anim.iteration as f32 * css.duration;
+                events.push_back(ViewportEvent::AnimationIteration(el, anim.name.clone(), elapsed));
+            }
+        }
+
+        if let Some((progress, _)) = css.progress(anim.elapsed) {
+            keyframes.apply(progress, css.timing_function, style);
+            animated.extend(keyframes.keyframes.iter().flat_map(|(_, s)| s.props().map(|p| p.id())));
+        }
+    }
+
+    animated
+}
+
+// TODO: reload changed files
+fn load_sheet(loaded: &RefCell<HashMap<PathBuf, StyleSheet>>, path: &Path) {
+    if !loaded.borrow().contains_key(path) {
+        // unreadable file is the same as an empty one (and it's reported only once)
+        let css = std::fs::read_to_string(path).unwrap_or_else(|e| {
+            eprintln!("failed to load {}: {}", path.display(), e);
+            String::new()
+        });
+        loaded.borrow_mut().insert(path.to_owned(), StyleSheet::from(&*css));
+    }
+}
+
+// depth-first, sheet itself goes last (import cycles are skipped)
+fn collect_sheets(
+    doc: &Document,
+    loaded: &RefCell<HashMap<PathBuf, StyleSheet>>,
+    source: SheetSource,
+    media: &MediaContext,
+    importing: &mut Vec<PathBuf>,
+    res: &mut Vec<SheetSource>,
+) {
+    let imports: Vec<_> = {
+        let loaded = loaded.borrow();
+        let (sheet, base) = sheet_with_base(doc, &loaded, &source);
+
+        sheet
+            .iter()
+            .flat_map(|s| s.imports(media))
+            .filter_map(|i| resolve_path(base.as_deref(), &i.href))
+            .collect()
+    };
+
+    if let SheetSource::File(path) = &source {
+        importing.push(path.clone());
+    }
+
+    for path in imports {
+        if !importing.contains(&path) {
+            load_sheet(loaded, &path);
+            collect_sheets(doc, loaded, SheetSource::File(path), media, importing, res);
+        }
+    }
+
+    if let SheetSource::File(_) = &source {
+        importing.pop();
+    }
+
+    res.push(source);
+}
+
+// parsed sheet & the directory its urls are relative to
+fn sheet_with_base<'a>(
+    doc: &'a Document,
+    loaded: &'a HashMap<PathBuf, StyleSheet>,
+    source: &SheetSource,
+) -> (Option<&'a StyleSheet>, Option<PathBuf>) {
+    match source {
+        SheetSource::Style(el) => (doc.weak_data::<StyleSheet>(*el), doc.base_url().and_then(base_dir)),
+        SheetSource::File(path) => (loaded.get(path), path.parent().map(Path::to_owned)),
+    }
+}
+
+// <link rel="stylesheet" href="...">
+fn stylesheet_link(doc: &Document, link: NodeId, base: Option<&Path>) -> Option<PathBuf> {
+    let rel = doc.attribute(link, "rel")?;
+
+    if !rel
+        .split_ascii_whitespace()
+        .any(|r| r.eq_ignore_ascii_case("stylesheet"))
+    {
+        return None;
+    }
+
+    resolve_path(base, &doc.attribute(link, "href")?)
+}
+
+// only local files for now (remote ones are loaded in JS)
+fn resolve_path(base: Option<&Path>, href: &str) -> Option<PathBuf> {
+    let href = href.split(|c| c == '?' || c == '#').next().unwrap_or("");
+    let href = href.strip_prefix("file://").unwrap_or(href);
+
+    if href.is_empty() || href.contains("://") || href.starts_with("data:") {
+        return None;
+    }
+
+    let href = percent_decode(href);
+
+    // absolute href replaces the base
+    Some(match base {
+        Some(base) => base.join(href),
+        None => PathBuf::from(href),
+    })
+}
+
+// %20 -> ' ', invalid sequences are kept as they are
+fn percent_decode(s: &str) -> String {
+    let bytes = s.as_bytes();
+    let mut res = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+
+    while i < bytes.len() {
+        let hex = s.get(i + 1..i + 3).filter(|h| h.bytes().all(|b| b.is_ascii_hexdigit()));
+
+        match (bytes[i], hex) {
+            (b'%', Some(hex)) => {
+                res.push(u8::from_str_radix(hex, 16).unwrap());
+                i += 3;
+            }
+            (b, _) => {
+                res.push(b);
+                i += 1;
+            }
+        }
+    }
+
+    String::from_utf8_lossy(&res).into_owned()
+}
+
+// directory of the document
+fn base_dir(url: &str) -> Option<PathBuf> {
+    let path = resolve_path(None, url)?;
+
+    match url.ends_with('/') {
+        true => Some(path),
+        false => path.parent().map(Path::to_owned),
+    }
+}
+
+// TODO: faces of removed sheets stay registered
+fn font_face(rule: &FontFaceRule, base: Option<&Path>) -> Option<FontFace> {
+    // first usable source
+    let id = rule.src.iter().find_map(|src| match src {
+        FontFaceSource::Url(url) => load_font_file(resolve_path(base, url)?),
+        FontFaceSource::Local(name) => find_local_font(name),
+    })?;
+
+    Some(FontFace {
+        family: rule.family.clone(),
+        weight: rule.weight,
+        italic: rule.italic,
+        unicode_range: rule.unicode_range.clone(),
+        id,
+    })
+}
+
+// descendants can still be hit (both props are inherited but can be overridden)
+fn hit_testable(style: &Style) -> bool {
+    visible(style)
+        && !style
+            .props()
+            .any(|p| matches!(p, StyleProp::PointerEvents(CssPointerEvents::None)))
+}
+
+// TODO: out-of-flow descendants & inline-blocks should not get the parent decoration
+fn text_style(style: &Style, parent_decoration: TextDecoration) -> TextStyle {
+    let mut res = TextStyle::DEFAULT;
+    let mut line_height = None;
+    let mut decoration_line = None;
+    let mut decoration_style = TextDecorationStyle::Solid;
+    let mut decoration_color = None;
+
+    for p in style.props() {
+        use StyleProp as P;
+
+        match p {
+            P::FontFamily(f) => res.font_family = Some(f.clone()),
+            P::FontSize(CssDimension::Px(v)) => res.font_size = *v,
+            P::FontWeight(w) => res.font_weight = *w,
+            P::FontStyle(s) => res.italic = *s != CssFontStyle::Normal,
+            P::LineHeight(d) => line_height = Some(d),
+            P::LetterSpacing(CssDimension::Px(v)) => res.letter_spacing = *v,
+            P::WordSpacing(CssDimension::Px(v)) => res.word_spacing = *v,
+            P::TextAlign(a) => {
+                res.align = match a {
+                    // TODO: justify
+                    CssTextAlign::Left | CssTextAlign::Justify => TextAlign::Left,
+                    CssTextAlign::Center => TextAlign::Center,
+                    CssTextAlign::Right => TextAlign::Right,
+                }
+            }
+            P::TextTransform(t) => {
+                res.transform = match t {
+                    CssTextTransform::None => TextTransform::None,
+                    CssTextTransform::Capitalize => TextTransform::Capitalize,
+                    CssTextTransform::Uppercase => TextTransform::Uppercase,
+                    CssTextTransform::Lowercase => TextTransform::Lowercase,
+                }
+            }
+            P::WhiteSpace(w) => {
+                res.white_space = match w {
+                    CssWhiteSpace::Normal => WhiteSpace::Normal,
+                    CssWhiteSpace::NoWrap => WhiteSpace::NoWrap,
+                    CssWhiteSpace::Pre => WhiteSpace::Pre,
+                    CssWhiteSpace::PreWrap => WhiteSpace::PreWrap,
+                    CssWhiteSpace::PreLine => WhiteSpace::PreLine,
+                }
+            }
+            P::TextDecorationLine(l) => decoration_line = Some(l),
+            P::TextDecorationStyle(s) => {
+                decoration_style = match s {
+                    CssTextDecorationStyle::Solid => TextDecorationStyle::Solid,
+                    CssTextDecorationStyle::Double => TextDecorationStyle::Double,
+                    CssTextDecorationStyle::Dotted => TextDecorationStyle::Dotted,
+                    CssTextDecorationStyle::Dashed => TextDecorationStyle::Dashed,
+                    CssTextDecorationStyle::Wavy => TextDecorationStyle::Wavy,
+                }
+            }
+            P::TextDecorationColor(c) => decoration_color = Some(c),
+            P::Color(c) => res.color = c.to_rgba8(),
+            _ => {}
+        }
+    }
+
+    // lines are added to those from ancestors (but all of them share the same style & color)
+    res.decoration = match decoration_line {
+        Some(l) if l.underline || l.overline || l.line_through => TextDecoration {
+            underline: l.underline || parent_decoration.underline,
+            overline: l.overline || parent_decoration.overline,
+            line_through: l.line_through || parent_decoration.line_through,
+            style: decoration_style,
+            // currentcolor
+            color: match decoration_color {
+                Some(CssColor::CurrentColor) | None => res.color,
+                Some(c) => c.to_rgba8(),
+            },
+        },
+        _ => parent_decoration,
+    };
+
+    // after font-size is known
+    res.line_height = match line_height {
+        Some(CssDimension::Px(v)) => *v,
+        Some(CssDimension::Percent(v)) => res.font_size * v / 100.,
+        // normal
+        _ => res.font_size * (TextStyle::DEFAULT.line_height / TextStyle::DEFAULT.font_size),
+    };
+
+    res
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // <html><head><style>{css}</style></head><body></body></html>
+    fn viewport(css: &str) -> (Viewport, NodeId) {
+        let doc = Rc::new(RefCell::new(Document::new()));
+        let vp = Viewport::new((800, 600), &doc);
+
+        let mut d = doc.borrow_mut();
+        let (html, head, style, body) = (
+            d.create_element("html"),
+            d.create_element("head"),
+            d.create_element("style"),
+            d.create_element("body"),
+        );
+        let (root, css) = (d.root(), d.create_text_node(css));
+        d.insert_child(root, html, 0);
+        d.insert_child(html, head, 0);
+        d.insert_child(head, style, 0);
+        d.insert_child(style, css, 0);
+        d.insert_child(html, body, 1);
+        drop(d);
+
+        (vp, body)
+    }
+
+    #[test]
+    fn media_features() {
+        let (mut vp, body) = viewport(
+            "@media (prefers-color-scheme: dark) { body { opacity: 0.5 } }
+             @media (prefers-reduced-motion: reduce) { body { z-index: 1 } }
+             @media (min-resolution: 2dppx) { body { flex-grow: 1 } }",
+        );
+        let value = |vp: &Viewport, prop| vp.computed_style(body).property_value(prop);
+
+        assert_eq!(value(&vp, "opacity").as_deref(), Some("1"));
+        assert_eq!(value(&vp, "z-index").as_deref(), Some("auto"));
+        assert_eq!(value(&vp, "flex-grow").as_deref(), Some("0"));
+
+        vp.set_prefers_color_scheme(ColorScheme::Dark);
+        vp.set_prefers_reduced_motion(true);
+        vp.set_resolution(2.);
+
+        assert_eq!(value(&vp, "opacity").as_deref(), Some("0.5"));
+        assert_eq!(value(&vp, "z-index").as_deref(), Some("1"));
+        assert_eq!(value(&vp, "flex-grow").as_deref(), Some("1"));
+    }
+
+    #[test]
+    fn current_color_transition() {
+        let (vp, body) = viewport("body { color: #000; outline-color: currentColor; transition: color 10s 10s }");
+        vp.update();
+
+        // outline should follow the color (which has not started yet)
+        vp.document()
+            .borrow_mut()
+            .set_element_style_property(body, "color", "#fff");
+        let style = vp.computed_style(body);
+        assert_eq!(style.property_value("color").as_deref(), Some("rgba(0, 0, 0, 255)"));
+        assert_eq!(
+            style.property_value("outline-color").as_deref(),
+            Some("rgba(0, 0, 0, 255)")
+        );
+    }
+
+    #[test]
+    fn zero_duration_animation() {
+        let (mut vp, body) = viewport(
+            "@keyframes a { to { opacity: 0.5 } }
+             body { animation: a 0s infinite forwards }",
+        );
+        vp.update();
+
+        let events: Vec<_> = std::iter::from_fn(|| vp.next_event()).collect();
+        assert_eq!(events, vec![ViewportEvent::AnimationEnd(body, Atom::from("a"), 0.)]);
+        assert!(!vp.is_animating());
+
+        assert_eq!(
+            vp.computed_style(body).property_value("opacity").as_deref(),
+            Some("0.5")
+        );
+        assert_eq!(vp.next_event(), None);
+    }
+
+    #[test]
+    fn restyle() {
+        let (vp, body) = viewport(".a span { opacity: 0.5 }");
+        let z_index = |el| vp.computed_style(el).property_value("z-index");
+
+        let mut d = vp.document().borrow_mut();
+        let (div, span, other) = (
+            d.create_element("div"),
+            d.create_element("span"),
+            d.create_element("div"),
+        );
+        d.insert_child(body, div, 0);
+        d.insert_child(div, span, 0);
+        d.insert_child(body, other, 1);
+        drop(d);
+        vp.update();
+
+        // sneak in a rule without notifying, only restyled nodes will see it
+        let mut d = vp.document().borrow_mut();
+        let (style, head) = (
+            d.query_selector(d.root(), "style").unwrap(),
+            d.query_selector(d.root(), "head").unwrap(),
+        );
+        let sheet = d.weak_data_mut::<StyleSheet>(style).unwrap();
+        sheet.insert_rule("div { z-index: 1 }", 1).unwrap();
+
+        d.set_attribute(div, "class", "a");
+        drop(d);
+
+        assert_eq!(
+            vp.computed_style(span).property_value("opacity").as_deref(),
+            Some("0.5")
+        );
+        assert_eq!(z_index(div).as_deref(), Some("1"));
+        assert_eq!(z_index(other).as_deref(), Some("auto"));
+
+        // new sheet can match anything
+        let mut d = vp.document().borrow_mut();
+        let style2 = d.create_element("style");
+        d.insert_child(head, style2, 1);
+        drop(d);
+
+        assert_eq!(z_index(other).as_deref(), Some("1"));
+    }
+
+    #[test]
+    fn computed_style() {
+        let (vp, body) =
+            viewport("body { margin: 0 } div { width: 50%; margin: 0 10%; padding: 1em; font-size: 10px }");
+        let mut d = vp.document().borrow_mut();
+        let (div, text) = (d.create_element("div"), d.create_text_node("hello"));
+        d.insert_child(body, div, 0);
+        d.insert_child(div, text, 0);
+        drop(d);
+
+        let style = vp.computed_style(div);
+        let value = |prop| style.property_value(prop);
+        // content-box
+        assert_eq!(value("width").as_deref(), Some("380px"));
+        assert_eq!(value("margin-left").as_deref(), Some("80px"));
+        assert_eq!(value("margin-top").as_deref(), Some("0px"));
+        assert_eq!(value("padding-right").as_deref(), Some("10px"));
+
+        assert_eq!(vp.computed_style(text), Style::new());
+
+        let mut d = vp.document().borrow_mut();
+        d.remove_child(body, div);
+        d.remove_child(div, text);
+        d.drop_node(text);
+        d.drop_node(div);
+        drop(d);
+
+        assert_eq!(vp.computed_style(text), Style::new());
+        assert_eq!(vp.computed_style(div), Style::new());
+    }
+
+    #[test]
+    fn text_styles() {
+        let (vp, body) = viewport("div { color: #f00; font-size: 20px; line-height: 30px; text-align: center }");
+        let mut d = vp.document().borrow_mut();
+        let (div, text) = (d.create_element("div"), d.create_text_node("hello"));
+        d.insert_child(body, div, 0);
+        d.insert_child(div, text, 0);
+        drop(d);
+        vp.update();
+
+        let style = vp.texts.borrow()[text].style().clone();
+        assert_eq!(style.color, [255, 0, 0, 255]);
+        assert_eq!(style.font_size, 20.);
+        assert_eq!(style.line_height, 30.);
+        assert_eq!(style.align, TextAlign::Center);
+        assert_eq!(vp.layout_nodes.borrow()[text].size().1, 30.);
+
+        // the leaf has to be measured again
+        vp.document()
+            .borrow_mut()
+            .set_element_style_property(div, "line-height", "40px");
+        vp.update();
+
+        assert_eq!(vp.texts.borrow()[text].style().line_height, 40.);
+        assert_eq!(vp.layout_nodes.borrow()[text].size().1, 40.);
+    }
+
+    #[test]
+    fn resolve_path() {
+        let base = Some(Path::new("/a"));
+
+        assert_eq!(
+            super::resolve_path(base, "b.css?v=1#x"),
+            Some(PathBuf::from("/a/b.css"))
+        );
+        assert_eq!(super::resolve_path(base, "/b.css"), Some(PathBuf::from("/b.css")));
+        assert_eq!(
+            super::resolve_path(base, "file:///b%20c.css"),
+            Some(PathBuf::from("/b c.css"))
+        );
+        assert_eq!(
+            super::resolve_path(base, "100%zz.css"),
+            Some(PathBuf::from("/a/100%zz.css"))
+        );
+        assert_eq!(super::resolve_path(base, "http://x/b.css"), None);
+
+        assert_eq!(base_dir("file:///a%20b/index.html"), Some(PathBuf::from("/a b")));
+        assert_eq!(base_dir("file:///a/"), Some(PathBuf::from("/a/")));
+    }
+
+    #[test]
+    fn linked_sheets() {
+        let dir = std::env::temp_dir().join(format!("graffiti test {}", std::process::id()));
+        std::fs::create_dir_all(dir.join("css")).unwrap();
+        std::fs::write(dir.join("css/a.css"), "@import 'b.css'; body { opacity: 0.5 }").unwrap();
+        // cycle
+        std::fs::write(
+            dir.join("css/b.css"),
+            "@import url(a.css); body { opacity: 1; z-index: 2 }",
+        )
+        .unwrap();
+
+        let (vp, body) = viewport("");
+        let mut d = vp.document().borrow_mut();
+        let link = d.create_element("link");
+        d.set_base_url(&format!("file://{}/index.html", dir.display()).replace(' ', "%20"));
+        d.set_attribute(link, "rel", "stylesheet");
+        d.set_attribute(link, "href", "css/a.css");
+        d.insert_child(body, link, 0);
+        drop(d);
+
+        // imported sheet goes first
+        let style = vp.computed_style(body);
+        assert_eq!(style.property_value("opacity").as_deref(), Some("0.5"));
+        assert_eq!(style.property_value("z-index").as_deref(), Some("2"));
+
+        // missing file is empty, previous one is not cached anymore
+        let set_href = |href| vp.document().borrow_mut().set_attribute(link, "href", href);
+        set_href("css/missing.css");
+        assert_eq!(vp.computed_style(body).property_value("opacity").as_deref(), Some("1"));
+
+        std::fs::write(dir.join("css/a.css"), "body { opacity: 0.25 }").unwrap();
+        set_href("css/a.css");
+        assert_eq!(
+            vp.computed_style(body).property_value("opacity").as_deref(),
+            Some("0.25")
+        );
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn paint_order() {
+        let (vp, body) = viewport(
+            "body { margin: 0 } div { position: absolute; top: 0; left: 0; width: 100px; height: 100px }
+             #a { z-index: 2 } #b { z-index: 1 } #c { z-index: -1 }
+             #d { left: 200px; opacity: 0.5 } #e { z-index: -1 }
+             #f { left: 400px; transform: translate(100px, 0) }",
+        );
+
+        let mut doc = vp.document().borrow_mut();
+        let mut div = |parent, id| {
+            let el = doc.create_element("div");
+            doc.set_attribute(el, "id", id);
+            let index = doc.child_nodes(parent).count();
+            doc.insert_child(parent, el, index);
+            el
+        };
+        let (a, b, c, d, f) = (
+            div(body, "a"),
+            div(body, "b"),
+            div(body, "c"),
+            div(body, "d"),
+            div(body, "f"),
+        );
+        let e = div(d, "e");
+        drop(doc);
+        vp.update();
+
+        let doc = vp.document().borrow();
+        let html = doc.first_child(doc.root()).unwrap();
+        let items = crate::renderer::paint_order(&doc, &vp.layout_nodes.borrow(), &vp.styles.borrow());
+        let nodes: Vec<_> = items.iter().map(|item| item.node).collect();
+        drop(doc);
+
+        // negative z-index goes under the parent, unless the parent is a stacking context
+        assert_eq!(nodes, vec![c, html, body, d, e, f, b, a]);
+
+        assert_eq!(vp.element_from_point((50., 50.)), Some(a));
+        assert_eq!(vp.element_from_point((250., 50.)), Some(e));
+        assert_eq!(vp.element_from_point((450., 50.)), Some(html));
+        assert_eq!(vp.element_from_point((550., 50.)), Some(f));
+
+        // cached paint order is dropped after any change
+        vp.document().borrow_mut().set_attribute(a, "style", "display: none");
+        assert_eq!(vp.element_from_point((50., 50.)), Some(b));
+    }
+
+    #[test]
+    fn hit_testing() {
+        let (vp, body) = viewport(
+            "body { margin: 0 } div { position: absolute; top: 0; width: 100px; height: 100px }
+             #b { left: 200px } #c { left: 400px }
+             #overlay { pointer-events: none } #hidden { visibility: hidden } #child { visibility: visible }",
+        );
+
+        let mut doc = vp.document().borrow_mut();
+        let mut div = |parent, id| {
+            let el = doc.create_element("div");
+            doc.set_attribute(el, "id", id);
+            let index = doc.child_nodes(parent).count();
+            doc.insert_child(parent, el, index);
+            el
+        };
+        let (a, b, c) = (div(body, "a"), div(body, "b"), div(body, "c"));
+        div(a, "overlay");
+        div(b, "hidden");
+        let hidden = div(c, "hidden");
+        let child = div(hidden, "child");
+        drop(doc);
+
+        assert_eq!(vp.element_from_point((50., 50.)), Some(a));
+        assert_eq!(vp.element_from_point((250., 50.)), Some(b));
+        assert_eq!(vp.element_from_point((450., 50.)), Some(child));
+    }
+
+    #[test]
+    fn text_decoration() {
+        let (vp, body) = viewport("a { color: #00f } b { color: #f00; text-decoration: none }");
+
+        let mut doc = vp.document().borrow_mut();
+        let (a, b, x) = (
+            doc.create_element("a"),
+            doc.create_element("b"),
+            doc.create_text_node("x"),
+        );
+        let (u, s, y) = (
+            doc.create_element("u"),
+            doc.create_element("s"),
+            doc.create_text_node("y"),
+        );
+
+        for (parent, child) in [(body, a), (a, b), (b, x), (body, u), (u, s), (s, y)].iter() {
+            let index = doc.child_nodes(*parent).count();
+            doc.insert_child(*parent, *child, index);
+        }
+        drop(doc);
+        vp.update();
+
+        let decoration = |text| vp.texts.borrow()[text].style().decoration;
+
+        // none can't remove it, color is taken from the decorating box
+        let TextDecoration {
+            underline,
+            line_through,
+            color,
+            ..
+        } = decoration(x);
+        assert_eq!((underline, line_through, color), (true, false, [0, 0, 255, 255]));
+
+        let TextDecoration {
+            underline,
+            line_through,
+            ..
+        } = decoration(y);
+        assert_eq!((underline, line_through), (true, true));
+    }
+
+    #[test]
+    fn cursor() {
+        let (vp, body) = viewport("body { margin: 0; cursor: pointer } div { height: 100px } #b { cursor: text }");
+
+        let mut doc = vp.document().borrow_mut();
+        let (a, b) = (doc.create_element("div"), doc.create_element("div"));
+        doc.set_attribute(b, "id", "b");
+        doc.insert_child(body, a, 0);
+        doc.insert_child(body, b, 1);
+        drop(doc);
+
+        // inherited from the body
+        assert_eq!(vp.cursor_at((50., 50.)), CssCursor::Pointer);
+        assert_eq!(vp.cursor_at((50., 150.)), CssCursor::Text);
+
+        // only changes are reported
+        assert_eq!(vp.update_cursor((50., 50.)), Some(CssCursor::Pointer));
+        assert_eq!(vp.update_cursor((60., 50.)), None);
+        assert_eq!(vp.update_cursor((60., 150.)), Some(CssCursor::Text));
+
+        // restyled under the pointer
+        vp.document().borrow_mut().set_attribute(b, "style", "cursor: wait");
+        assert_eq!(vp.update_cursor((60., 150.)), Some(CssCursor::Wait));
+        assert_eq!(vp.update_cursor((60., 150.)), None);
+    }
+}
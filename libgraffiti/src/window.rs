@@ -3,17 +3,31 @@
 use super::App;
 use crossbeam_channel::{unbounded as channel, Receiver, Sender};
 use graffiti_glfw::*;
+use std::cell::{Cell, Ref, RefCell};
 use std::ffi::CStr;
 use std::os::raw::{c_double, c_int, c_uint, c_void};
 use std::ptr::null_mut;
-use std::rc::{Rc};
-use std::cell::{RefCell, Ref};
+use std::rc::Rc;
 
 pub struct Window {
     _app: Rc<App>,
     title: RefCell<String>,
     glfw_window: GlfwWindow,
     events: Receiver<Event>,
+    // standard cursors, created on first use
+    cursors: Cell<[GlfwCursor; 6]>,
+    cursor: Cell<Option<Cursor>>,
+}
+
+// standard shapes supported by GLFW
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Cursor {
+    Arrow,
+    IBeam,
+    Crosshair,
+    Hand,
+    HResize,
+    VResize,
 }
 
 impl Window {
@@ -52,6 +66,8 @@ impl Window {
                 title: title.to_owned().into(),
                 glfw_window,
                 events,
+                cursors: Cell::new([null_mut(); 6]),
+                cursor: Cell::new(Some(Cursor::Arrow)),
             }
         }
     }
@@ -163,6 +179,43 @@ impl Window {
         unsafe { glfwRequestWindowAttention(self.glfw_window) }
     }
 
+    pub fn cursor(&self) -> Option<Cursor> {
+        self.cursor.get()
+    }
+
+    // None hides the cursor (when over the window)
+    pub fn set_cursor(&self, cursor: Option<Cursor>) {
+        if cursor == self.cursor.get() {
+            return;
+        }
+
+        unsafe {
+            match cursor {
+                None => glfwSetInputMode(self.glfw_window, GLFW_CURSOR, GLFW_CURSOR_HIDDEN),
+                Some(c) => {
+                    let mut cursors = self.cursors.get();
+
+                    if cursors[c as usize].is_null() {
+                        cursors[c as usize] = glfwCreateStandardCursor(match c {
+                            Cursor::Arrow => GLFW_ARROW_CURSOR,
+                            Cursor::IBeam => GLFW_IBEAM_CURSOR,
+                            Cursor::Crosshair => GLFW_CROSSHAIR_CURSOR,
+                            Cursor::Hand => GLFW_HAND_CURSOR,
+                            Cursor::HResize => GLFW_HRESIZE_CURSOR,
+                            Cursor::VResize => GLFW_VRESIZE_CURSOR,
+                        });
+                        self.cursors.set(cursors);
+                    }
+
+                    glfwSetInputMode(self.glfw_window, GLFW_CURSOR, GLFW_CURSOR_NORMAL);
+                    glfwSetCursor(self.glfw_window, cursors[c as usize]);
+                }
+            }
+        }
+
+        self.cursor.set(cursor);
+    }
+
     // event loop
 
     pub fn should_close(&self) -> bool {
@@ -215,6 +268,10 @@ impl Drop for Window {
             glfwSetWindowUserPointer(self.glfw_window, null_mut());
             glfwDestroyWindow(self.glfw_window);
 
+            for c in self.cursors.get().iter().filter(|c| !c.is_null()) {
+                glfwDestroyCursor(*c);
+            }
+
             drop(Box::from_raw(ptr as *mut Sender<Event>));
         }
     }
@@ -52,7 +52,7 @@ export class AppWindow {
     native.window_restore(this.#id)
   }
 
-  async loadURL(url: URL | string, options = {}) {
+  async loadURL(url: URL | string, options: { colorScheme?: 'light' | 'dark'; reducedMotion?: boolean } = {}) {
     this.#worker?.terminate()
 
     const worker = new Worker(new URL('worker.js', import.meta.url), {
@@ -85,7 +85,8 @@ export class AppWindow {
       }))
 
     const [width, height] = native.window_size(this.#id)
-    await this.#send({ type: 'init', windowId: this.#id, width, height, url: '' + url, options })
+    const [resolution] = native.window_content_scale(this.#id)
+    await this.#send({ type: 'init', windowId: this.#id, width, height, resolution, url: '' + url, options })
   }
 
   async eval(js: string) {
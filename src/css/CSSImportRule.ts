@@ -0,0 +1,26 @@
+import { CSSRule } from './CSSRule'
+import { getImportHref } from '../dom/Document'
+import { UNSUPPORTED } from '../util'
+
+// imported sheets are loaded (and kept) in native
+export class CSSImportRule extends CSSRule implements globalThis.CSSImportRule {
+  get type() {
+    return CSSRule.IMPORT_RULE
+  }
+
+  get href() {
+    return this._native(getImportHref)
+  }
+
+  get media(): MediaList {
+    return UNSUPPORTED()
+  }
+
+  get styleSheet(): CSSStyleSheet {
+    return UNSUPPORTED()
+  }
+
+  // later
+  layerName
+  supportsText
+}
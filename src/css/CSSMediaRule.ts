@@ -0,0 +1,33 @@
+import { CSSRule } from './CSSRule'
+import { CSSRuleList, syncRules } from './CSSRuleList'
+import { createRule, insertIntoList, deleteFromList } from './CSSStyleSheet'
+import { getMediaText, getRuleTypes } from '../dom/Document'
+import { UNSUPPORTED } from '../util'
+
+export class CSSMediaRule extends CSSRule implements globalThis.CSSMediaRule {
+  #rules = new CSSRuleList()
+
+  get type() {
+    return CSSRule.MEDIA_RULE
+  }
+
+  get conditionText() {
+    return this._native(getMediaText)
+  }
+
+  get media(): MediaList {
+    return UNSUPPORTED()
+  }
+
+  get cssRules(): CSSRuleList {
+    return syncRules(this.#rules, this._native(getRuleTypes), type => createRule(this.parentStyleSheet, this, type))
+  }
+
+  insertRule(rule: string, index = 0): number {
+    return insertIntoList(this.parentStyleSheet as any, this, this.cssRules, this._path, rule, index)
+  }
+
+  deleteRule(index: number) {
+    deleteFromList(this.parentStyleSheet as any, this.cssRules, this._path, index)
+  }
+}
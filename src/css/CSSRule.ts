@@ -1,18 +1,35 @@
+import { getRuleCssText } from '../dom/Document'
+import { ERR } from '../util'
+import type { CSSStyleSheet } from './CSSStyleSheet'
+
 export abstract class CSSRule implements globalThis.CSSRule {
   abstract readonly type: number
-  abstract readonly cssText: string
 
-  constructor(private readonly parent: CSSStyleSheet) {}
+  constructor(private readonly sheet: CSSStyleSheet, private readonly parent: CSSRule | null) {}
 
   get parentStyleSheet(): CSSStyleSheet | null {
     // null if it has been removed already
-    return Array.from(this.parent.cssRules).includes(this) ?this.parent :null
+    return this._path ? this.sheet : null
   }
 
   get parentRule(): CSSRule | null {
-    console.warn('CSS @import is not supported')
+    return this.parent
+  }
+
+  get cssText() {
+    return this._native(getRuleCssText)
+  }
+
+  // indices in native (or null if removed)
+  get _path(): number[] | null {
+    const path = this.parent ? this.parent._path : []
+    const index = (this.parent ? (this.parent as any).cssRules : this.sheet.cssRules).indexOf(this)
 
-    return null
+    return path && index !== -1 ? [...path, index] : null
+  }
+
+  _native(fn, ...args) {
+    return this.sheet._native(fn, this._path ?? ERR('rule has been removed'), ...args)
   }
 
   // rule types
@@ -44,3 +61,10 @@ export abstract class CSSRule implements globalThis.CSSRule {
   get SUPPORTS_RULE() { return CSSRule.SUPPORTS_RULE }
   get VIEWPORT_RULE() { return CSSRule.VIEWPORT_RULE }
 }
+
+// @font-face, @keyframes, ...
+export class CSSOtherRule extends CSSRule {
+  constructor(sheet, parent, readonly type: number) {
+    super(sheet, parent)
+  }
+}
@@ -3,3 +3,13 @@ export class CSSRuleList extends Array<CSSRule> implements globalThis.CSSRuleLis
     return this[index] ?? null
   }
 }
+
+// native sheet can be re-parsed (<style> text changed) so wrappers
+// are kept only as long as the rule types are still the same
+export const syncRules = (list: CSSRuleList, types: number[], create: (type: number) => CSSRule) => {
+  if (list.length !== types.length || list.some((r, i) => r.type !== types[i])) {
+    list.splice(0, list.length, ...types.map(create))
+  }
+
+  return list
+}
@@ -89,6 +89,8 @@ export class CSSStyleDeclaration implements globalThis.CSSStyleDeclaration {
   set background(v: string) { this.setProperty('background', v) }
   get backgroundColor() { return this.getPropertyValue('background-color') }
   set backgroundColor(v: string) { this.setProperty('background-color', v) }
+  get backgroundImage() { return this.getPropertyValue('background-image') }
+  set backgroundImage(v: string) { this.setProperty('background-image', v) }
   get border() { return this.getPropertyValue('border') }
   set border(v: string) { this.setProperty('border', v) }
   get borderBottom() { return this.getPropertyValue('border-bottom') }
@@ -145,6 +147,10 @@ export class CSSStyleDeclaration implements globalThis.CSSStyleDeclaration {
   set boxShadow(v: string) { this.setProperty('box-shadow', v) }
   get color() { return this.getPropertyValue('color') }
   set color(v: string) { this.setProperty('color', v) }
+  get columnGap() { return this.getPropertyValue('column-gap') }
+  set columnGap(v: string) { this.setProperty('column-gap', v) }
+  get cursor() { return this.getPropertyValue('cursor') }
+  set cursor(v: string) { this.setProperty('cursor', v) }
   get display() { return this.getPropertyValue('display') }
   set display(v: string) { this.setProperty('display', v) }
   get flex() { return this.getPropertyValue('flex') }
@@ -173,12 +179,16 @@ export class CSSStyleDeclaration implements globalThis.CSSStyleDeclaration {
   set fontVariant(v: string) { this.setProperty('font-variant', v) }
   get fontWeight() { return this.getPropertyValue('font-weight') }
   set fontWeight(v: string) { this.setProperty('font-weight', v) }
+  get gap() { return this.getPropertyValue('gap') }
+  set gap(v: string) { this.setProperty('gap', v) }
   get height() { return this.getPropertyValue('height') }
   set height(v: string) { this.setProperty('height', v) }
   get justifyContent() { return this.getPropertyValue('justify-content') }
   set justifyContent(v: string) { this.setProperty('justify-content', v) }
   get left() { return this.getPropertyValue('left') }
   set left(v: string) { this.setProperty('left', v) }
+  get letterSpacing() { return this.getPropertyValue('letter-spacing') }
+  set letterSpacing(v: string) { this.setProperty('letter-spacing', v) }
   get lineHeight() { return this.getPropertyValue('line-height') }
   set lineHeight(v: string) { this.setProperty('line-height', v) }
   get margin() { return this.getPropertyValue('margin') }
@@ -211,16 +221,38 @@ export class CSSStyleDeclaration implements globalThis.CSSStyleDeclaration {
   set paddingRight(v: string) { this.setProperty('padding-right', v) }
   get paddingTop() { return this.getPropertyValue('padding-top') }
   set paddingTop(v: string) { this.setProperty('padding-top', v) }
+  get pointerEvents() { return this.getPropertyValue('pointer-events') }
+  set pointerEvents(v: string) { this.setProperty('pointer-events', v) }
   get right() { return this.getPropertyValue('right') }
   set right(v: string) { this.setProperty('right', v) }
+  get rowGap() { return this.getPropertyValue('row-gap') }
+  set rowGap(v: string) { this.setProperty('row-gap', v) }
   get textAlign() { return this.getPropertyValue('text-align') }
   set textAlign(v: string) { this.setProperty('text-align', v) }
+  get textDecoration() { return this.getPropertyValue('text-decoration') }
+  set textDecoration(v: string) { this.setProperty('text-decoration', v) }
+  get textDecorationColor() { return this.getPropertyValue('text-decoration-color') }
+  set textDecorationColor(v: string) { this.setProperty('text-decoration-color', v) }
+  get textDecorationLine() { return this.getPropertyValue('text-decoration-line') }
+  set textDecorationLine(v: string) { this.setProperty('text-decoration-line', v) }
+  get textDecorationStyle() { return this.getPropertyValue('text-decoration-style') }
+  set textDecorationStyle(v: string) { this.setProperty('text-decoration-style', v) }
+  get textTransform() { return this.getPropertyValue('text-transform') }
+  set textTransform(v: string) { this.setProperty('text-transform', v) }
   get top() { return this.getPropertyValue('top') }
   set top(v: string) { this.setProperty('top', v) }
   get transform() { return this.getPropertyValue('transform') }
   set transform(v: string) { this.setProperty('transform', v) }
+  get transformOrigin() { return this.getPropertyValue('transform-origin') }
+  set transformOrigin(v: string) { this.setProperty('transform-origin', v) }
+  get whiteSpace() { return this.getPropertyValue('white-space') }
+  set whiteSpace(v: string) { this.setProperty('white-space', v) }
   get width() { return this.getPropertyValue('width') }
   set width(v: string) { this.setProperty('width', v) }
+  get wordSpacing() { return this.getPropertyValue('word-spacing') }
+  set wordSpacing(v: string) { this.setProperty('word-spacing', v) }
+  get zIndex() { return this.getPropertyValue('z-index') }
+  set zIndex(v: string) { this.setProperty('z-index', v) }
 
   // maybe later (lot of them are SVG-only)
   all
@@ -236,7 +268,6 @@ export class CSSStyleDeclaration implements globalThis.CSSStyleDeclaration {
   backgroundPositionY
   backgroundRepeat
   backgroundSize
-  letterSpacing
   opacity
   outline
   outlineColor
@@ -247,15 +278,11 @@ export class CSSStyleDeclaration implements globalThis.CSSStyleDeclaration {
   overflowWrap
   overflowX
   overflowY
-  pointerEvents
   position
-  textTransform
   textUnderlinePosition
   transformBox
-  transformOrigin
   transformStyle
   visibility
-  whiteSpace
   alignmentBaseline
   animation
   animationDelay
@@ -268,7 +295,6 @@ export class CSSStyleDeclaration implements globalThis.CSSStyleDeclaration {
   animationTimingFunction
   backfaceVisibility
   baselineShift
-  backgroundImage
   backgroundOrigin
   blockSize
   borderBlockEnd
@@ -309,7 +335,6 @@ export class CSSStyleDeclaration implements globalThis.CSSStyleDeclaration {
   colorInterpolationFilters
   columnCount
   columnFill
-  columnGap
   columnRule
   columnRuleColor
   columnRuleStyle
@@ -321,7 +346,6 @@ export class CSSStyleDeclaration implements globalThis.CSSStyleDeclaration {
   counterIncrement
   counterReset
   cssFloat
-  cursor
   direction
   dominantBaseline
   emptyCells
@@ -343,7 +367,6 @@ export class CSSStyleDeclaration implements globalThis.CSSStyleDeclaration {
   fontVariantLigatures
   fontVariantNumeric
   fontVariantPosition
-  gap
   glyphOrientationHorizontal
   glyphOrientationVertical
   grid
@@ -423,7 +446,6 @@ export class CSSStyleDeclaration implements globalThis.CSSStyleDeclaration {
   quotes
   resize
   rotate
-  rowGap
   rubyAlign
   rubyOverhang
   rubyPosition
@@ -445,10 +467,6 @@ export class CSSStyleDeclaration implements globalThis.CSSStyleDeclaration {
   textAlignLast
   textAnchor
   textCombineUpright
-  textDecoration
-  textDecorationColor
-  textDecorationLine
-  textDecorationStyle
   textEmphasis
   textEmphasisColor
   textEmphasisPosition
@@ -474,10 +492,8 @@ export class CSSStyleDeclaration implements globalThis.CSSStyleDeclaration {
   widows
   willChange
   wordBreak
-  wordSpacing
   wordWrap
   writingMode
-  zIndex
   zoom
 
   // ignore vendor extensions
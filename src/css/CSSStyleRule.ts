@@ -1,18 +1,67 @@
 import { CSSRule } from './CSSRule'
 import { CSSStyleDeclaration } from './CSSStyleDeclaration'
+import {
+  getSelectorText,
+  setSelectorText,
+  getRuleStyleProp,
+  setRuleStyleProp,
+  removeRuleStyleProp,
+  getRuleStyleCssText,
+  setRuleStyleCssText,
+} from '../dom/Document'
 
 export class CSSStyleRule extends CSSRule implements globalThis.CSSStyleRule {
-  readonly style = new CSSStyleDeclaration(this, (prop, value) => console.log('TODO: change rule style', prop, value))
-
-  constructor(parent: CSSStyleSheet, public selectorText: string) {
-    super(parent)
-  }
+  readonly style = new CSSRuleStyleDeclaration(this)
 
   get type() {
     return CSSRule.STYLE_RULE
   }
 
-  get cssText() {
-    return `${this.selectorText} { ${this.style.cssText} }`
+  get selectorText() {
+    return this._native(getSelectorText)
+  }
+
+  set selectorText(selector: string) {
+    this._native(setSelectorText, selector)
+  }
+}
+
+// values are read from & written to the native rule
+class CSSRuleStyleDeclaration extends CSSStyleDeclaration {
+  constructor(private readonly rule: CSSStyleRule) {
+    super(rule, null)
+  }
+
+  getPropertyValue(propertyName: string): string {
+    return this.rule._native(getRuleStyleProp, propertyName) ?? ''
+  }
+
+  setProperty(propertyName: string, value: string | null, priority?: string | null) {
+    if (value === '' || value === null) {
+      this.removeProperty(propertyName)
+      return
+    }
+
+    if (priority === 'important') {
+      console.warn('!important is not supported')
+    }
+
+    this.rule._native(setRuleStyleProp, propertyName, value)
+  }
+
+  removeProperty(propertyName: string): string {
+    const prev = this.getPropertyValue(propertyName)
+
+    this.rule._native(removeRuleStyleProp, propertyName)
+
+    return prev
+  }
+
+  get cssText(): string {
+    return this.rule._native(getRuleStyleCssText)
+  }
+
+  set cssText(cssText: string) {
+    this.rule._native(setRuleStyleCssText, cssText)
   }
 }
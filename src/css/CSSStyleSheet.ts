@@ -1,11 +1,18 @@
 import { StyleSheet } from './StyleSheet'
-import { CSSRuleList } from './CSSRuleList'
+import { CSSRuleList, syncRules } from './CSSRuleList'
+import { CSSRule, CSSOtherRule } from './CSSRule'
 import { CSSStyleRule } from './CSSStyleRule'
+import { CSSMediaRule } from './CSSMediaRule'
+import { CSSSupportsRule } from './CSSSupportsRule'
+import { CSSImportRule } from './CSSImportRule'
+import { getRuleTypes, insertRule, deleteRule } from '../dom/Document'
+import { assert, ERR } from '../util'
 
+// rules live in native, we only keep wrappers so that identity is preserved
 export class CSSStyleSheet extends StyleSheet implements globalThis.CSSStyleSheet {
-  readonly cssRules = new CSSRuleList()
+  #rules = new CSSRuleList()
 
-  constructor(parent: HTMLStyleElement, private _onChange) {
+  constructor(parent: HTMLStyleElement) {
     super(parent)
   }
 
@@ -15,32 +22,25 @@ export class CSSStyleSheet extends StyleSheet implements globalThis.CSSStyleShee
     return null
   }
 
+  get cssRules(): CSSRuleList {
+    return syncRules(this.#rules, this._native(getRuleTypes, []), type => createRule(this, null, type))
+  }
+
   get rules() {
     return this.cssRules
   }
 
   insertRule(rule: string, index = 0): number {
-    //this.insertRules(rule, index)
-
-    return index
+    return insertIntoList(this, null, this.cssRules, [], rule, index)
   }
 
-  /*
-  insertRules(str, index = 0) {
-    const rules = parseRules(str).map(({ selector, props }) => {
-      const rule = new CSSStyleRule(this, selector)
-
-      Object.entries(props).forEach(([k, v]) => rule.style.setProperty(k, v))
-
-      return rule
-    })
-
-    this.cssRules.splice(index, 0, ...rules)
+  deleteRule(index: number) {
+    deleteFromList(this, this.cssRules, [], index)
   }
-  */
 
-  deleteRule(index: number) {
-    this.cssRules.splice(index, 1)
+  // call native with the owner <style> as the root of the path
+  _native(fn, path: number[], ...args) {
+    return fn(this.parent.ownerDocument, this.parent, path, ...args)
   }
 
   // deprecated
@@ -58,3 +58,39 @@ export class CSSStyleSheet extends StyleSheet implements globalThis.CSSStyleShee
   removeImport
   removeRule
 }
+
+// shared with CSSMediaRule
+export const insertIntoList = (sheet: CSSStyleSheet, parentRule, list: CSSRuleList, path, rule: string, index) => {
+  assert(index >= 0 && index <= list.length, 'IndexSizeError')
+
+  if (!sheet._native(insertRule, path, rule, index)) {
+    ERR('SyntaxError', rule)
+  }
+
+  const [type] = sheet._native(getRuleTypes, path).slice(index, index + 1)
+  list.splice(index, 0, createRule(sheet, parentRule, type))
+
+  return index
+}
+
+export const deleteFromList = (sheet: CSSStyleSheet, list: CSSRuleList, path, index) => {
+  assert(index >= 0 && index < list.length, 'IndexSizeError')
+
+  sheet._native(deleteRule, path, index)
+  list.splice(index, 1)
+}
+
+export const createRule = (sheet, parentRule, type) => {
+  switch (type) {
+    case CSSRule.STYLE_RULE:
+      return new CSSStyleRule(sheet, parentRule)
+    case CSSRule.MEDIA_RULE:
+      return new CSSMediaRule(sheet, parentRule)
+    case CSSRule.SUPPORTS_RULE:
+      return new CSSSupportsRule(sheet, parentRule)
+    case CSSRule.IMPORT_RULE:
+      return new CSSImportRule(sheet, parentRule)
+    default:
+      return new CSSOtherRule(sheet, parentRule, type)
+  }
+}
@@ -1,7 +1,7 @@
 import { UNSUPPORTED } from '../util'
 
 export class StyleSheet implements globalThis.StyleSheet {
-  constructor(private readonly parent: HTMLStyleElement) {}
+  constructor(protected readonly parent: HTMLStyleElement) {}
 
   get type() {
     return 'text/css'
@@ -314,6 +314,8 @@ const lookup = (doc, id) => (id && doc[REFS][id]?.deref()) ?? null
 
 // package-private
 export const getDocId = (doc) => doc[DOC_ID]
+export const setBaseURL = (doc, url) => native.document_set_base_url(doc[DOC_ID], '' + url)
+export const getNode = (doc, id) => lookup(doc, id)
 export const initTextNode = (doc, node, cdata) => initNode(doc, node, native.document_create_text_node(doc[DOC_ID], cdata))
 export const initComment = (doc, node, cdata) => initNode(doc, node, native.document_create_comment(doc[DOC_ID], cdata))
 export const setCdata = (doc, node, cdata) => native.document_set_cdata(doc[DOC_ID], node[NODE_ID], cdata)
@@ -329,4 +331,24 @@ export const matches = (doc, el, sel) => lookup(doc, native.document_matches(doc
 export const querySelector = (doc, ctxNode, sel) => lookup(doc, native.document_query_selector(doc[DOC_ID], ctxNode[NODE_ID], sel))
 export const querySelectorAll = (doc, ctxNode, sel) => native.document_query_selector_all(doc[DOC_ID], ctxNode[NODE_ID], sel).map(id => lookup(doc, id))
 
+// CSSOM (rules are addressed by [<style>, index, nested index, ...])
+const rulePath = (style, path) => [style[NODE_ID], ...path]
+export const getRuleTypes = (doc, style, path) => native.stylesheet_rule_types(doc[DOC_ID], rulePath(style, path))
+export const insertRule = (doc, style, path, rule, index) => native.stylesheet_insert_rule(doc[DOC_ID], rulePath(style, path), rule, index)
+export const deleteRule = (doc, style, path, index) => native.stylesheet_delete_rule(doc[DOC_ID], rulePath(style, path), index)
+export const getRuleCssText = (doc, style, path) => native.stylesheet_rule_css_text(doc[DOC_ID], rulePath(style, path))
+export const getSelectorText = (doc, style, path) => native.stylesheet_rule_selector_text(doc[DOC_ID], rulePath(style, path))
+export const setSelectorText = (doc, style, path, sel) => native.stylesheet_rule_set_selector_text(doc[DOC_ID], rulePath(style, path), sel)
+export const getRuleStyleProp = (doc, style, path, prop) => native.stylesheet_rule_style_property_value(doc[DOC_ID], rulePath(style, path), prop)
+export const setRuleStyleProp = (doc, style, path, prop, val) => native.stylesheet_rule_set_style_property(doc[DOC_ID], rulePath(style, path), prop, val)
+export const removeRuleStyleProp = (doc, style, path, prop) => native.stylesheet_rule_remove_style_property(doc[DOC_ID], rulePath(style, path), prop)
+export const getRuleStyleCssText = (doc, style, path) => native.stylesheet_rule_style_css_text(doc[DOC_ID], rulePath(style, path))
+export const setRuleStyleCssText = (doc, style, path, css) => native.stylesheet_rule_set_style_css_text(doc[DOC_ID], rulePath(style, path), css)
+export const getMediaText = (doc, style, path) => native.stylesheet_media_text(doc[DOC_ID], rulePath(style, path))
+export const getImportHref = (doc, style, path) => native.stylesheet_import_href(doc[DOC_ID], rulePath(style, path))
+
+// resolved values (only available if the document is rendered in a viewport)
+export const getComputedStyle = (doc, el): [string, string][] =>
+  doc['__VIEWPORT_ID'] === undefined ? [] : native.viewport_computed_style(doc['__VIEWPORT_ID'], el[NODE_ID])
+
 const DOCUMENT_REGISTRY = new FinalizationRegistry(id => native.document_drop(id))
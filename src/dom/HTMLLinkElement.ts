@@ -40,13 +40,18 @@ export class HTMLLinkElement extends HTMLElement implements globalThis.HTMLLinkE
   target
 }
 
-// for now, we replace <link> with <style> which works surprisingly well
+// local files are loaded in native (relative to the document base url)
+// but for remote ones, we replace <link> with <style> which works surprisingly well
 export async function loadStyles() {
   for (const link of document.querySelectorAll('link')) {
     if (link.rel === 'stylesheet' && link.href) {
-      const style = document.createElement('style')
-      style.textContent = await readURL('' + new URL(link.href, document.URL))
-      link.replaceWith(style)
+      const url = new URL(link.href, document.URL)
+
+      if (url.protocol !== 'file:') {
+        const style = document.createElement('style')
+        style.textContent = await readURL('' + url)
+        link.replaceWith(style)
+      }
     }
   }
 }
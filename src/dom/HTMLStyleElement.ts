@@ -1,7 +1,17 @@
 import { HTMLElement } from './index'
+import { CSSStyleSheet } from '../css/CSSStyleSheet'
 
 export class HTMLStyleElement extends HTMLElement implements globalThis.HTMLStyleElement {
-  sheet: CSSStyleSheet | null = null
+  #sheet
+
+  // rules are parsed lazily in native
+  get sheet(): CSSStyleSheet | null {
+    if (this.#sheet === undefined) {
+      this.#sheet = new CSSStyleSheet(this)
+    }
+
+    return this.#sheet
+  }
 
   get media() {
     return this.getAttribute('media') ?? ''
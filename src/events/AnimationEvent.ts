@@ -0,0 +1,14 @@
+import { Event } from './index'
+
+export class AnimationEvent extends Event implements globalThis.AnimationEvent {
+  animationName: string
+  elapsedTime: number
+  pseudoElement: string
+
+  constructor(type: string, eventInit?: AnimationEventInit) {
+    super(type, eventInit)
+    this.animationName = eventInit?.animationName ?? ''
+    this.elapsedTime = eventInit?.elapsedTime ?? 0
+    this.pseudoElement = eventInit?.pseudoElement ?? ''
+  }
+}
@@ -0,0 +1,14 @@
+import { Event } from './index'
+
+export class TransitionEvent extends Event implements globalThis.TransitionEvent {
+  propertyName: string
+  elapsedTime: number
+  pseudoElement: string
+
+  constructor(type: string, eventInit?: TransitionEventInit) {
+    super(type, eventInit)
+    this.propertyName = eventInit?.propertyName ?? ''
+    this.elapsedTime = eventInit?.elapsedTime ?? 0
+    this.pseudoElement = eventInit?.pseudoElement ?? ''
+  }
+}
@@ -6,6 +6,8 @@ export { EventTarget } from './EventTarget'
 export { UIEvent } from './UIEvent'
 export { HashChangeEvent } from './HashChangeEvent'
 export { PopStateEvent } from './PopStateEvent'
+export { TransitionEvent } from './TransitionEvent'
+export { AnimationEvent } from './AnimationEvent'
 
 export { KeyboardEvent } from './KeyboardEvent'
 export { FocusEvent } from './FocusEvent'
@@ -5,7 +5,9 @@ import { Location } from './Location'
 import { History } from './History'
 import { Storage } from './Storage'
 import { requestAnimationFrame, cancelAnimationFrame } from './raf'
-import { NOOP, TODO, fetch } from '../util'
+import { NOOP, TODO, ERR, fetch } from '../util'
+import { CSSStyleDeclaration as BaseStyleDeclaration } from '../css/CSSStyleDeclaration'
+import { getComputedStyle } from '../dom/Document'
 
 const G = globalThis
 
@@ -55,11 +57,11 @@ export class Window extends EventTarget implements globalThis.Window {
   }
 
   getComputedStyle(elt: Element, pseudoElt?: string | null): CSSStyleDeclaration {
-    // CSSStyleDeclaration
-    // - parentRule = null
-    // - onChange = NOOP
-    // - values = { resolvedProps + layoutProps }
-    throw new Error('Method not implemented.')
+    if (pseudoElt) {
+      console.warn('pseudo elements are not supported')
+    }
+
+    return new ComputedStyleDeclaration(elt)
   }
 
   // TODO (and no-op in <iframe>)
@@ -182,3 +184,44 @@ export const makeGlobal = window => {
   // we could use VM context for node but I don't know what to use for deno
   Object.setPrototypeOf(globalThis, window)
 }
+
+// live & read-only, values are fetched from the viewport on every access
+class ComputedStyleDeclaration extends BaseStyleDeclaration {
+  constructor(private readonly element: Element) {
+    super(null, null)
+  }
+
+  getPropertyValue(propertyName: string): string {
+    const prop = this.#values().find(([k]) => k === propertyName)
+
+    return prop?.[1] ?? ''
+  }
+
+  setProperty(propertyName: string, value: string | null, priority?: string | null) {
+    ERR('NoModificationAllowedError')
+  }
+
+  removeProperty(propertyName: string): string {
+    return ERR('NoModificationAllowedError')
+  }
+
+  get cssText(): string {
+    return this.#values().map(([prop, value]) => `${prop}: ${value}`).join('; ')
+  }
+
+  set cssText(cssText: string) {
+    ERR('NoModificationAllowedError')
+  }
+
+  get length() {
+    return this.#values().length
+  }
+
+  item(index: number): string {
+    return this.#values()[index]?.[0] ?? ''
+  }
+
+  #values() {
+    return getComputedStyle(this.element.ownerDocument, this.element)
+  }
+}
@@ -8,7 +8,7 @@
 import { native, loadNativeApi } from './native'
 import { Window, makeGlobal } from './window/Window'
 import { readURL } from './util'
-import { getDocId } from './dom/Document'
+import { getDocId, getNode, setBaseURL } from './dom/Document'
 import { parseIntoDocument } from './dom/DOMParser'
 import { loadStyles } from './dom/HTMLLinkElement'
 import { runScripts } from './dom/HTMLScriptElement'
@@ -47,7 +47,7 @@ async function send(result, error?) {
   native.wake_up()
 }
 
-async function main({ windowId, width, height, url, options }) {
+async function main({ windowId, width, height, resolution, url, options }) {
   // unfortunately, we need native in worker too - there are many blocking APIs
   // and those would be impossible to emulate with parent<->worker postMessage()
   await loadNativeApi()
@@ -55,11 +55,17 @@ async function main({ windowId, width, height, url, options }) {
   // setup env
   const { window, document } = new Window()
   document.URL = url
+  setBaseURL(document, url)
   makeGlobal(window)
 
   // init viewport
   const viewportId = native.viewport_new(width, height, getDocId(document))
   document['__VIEWPORT_ID'] = viewportId
+
+  // media features (GLFW can't tell us the OS preferences)
+  native.viewport_set_resolution(viewportId, resolution)
+  native.viewport_set_prefers_color_scheme(viewportId, options.colorScheme === 'dark')
+  native.viewport_set_prefers_reduced_motion(viewportId, !!options.reducedMotion)
   VIEWPORT_REGISTRY.register(window, viewportId)
 
   // load html
@@ -89,7 +95,30 @@ async function main({ windowId, width, height, url, options }) {
 
     native.viewport_render(windowId, viewportId)
 
-    setTimeout(loop, 100)
+    // styles might have changed even if the mouse did not move
+    native.viewport_update_cursor(windowId, viewportId, mousePos[0], mousePos[1])
+
+    // dispatch what happened during render (transitionend, animationstart, ...)
+    while ((ev = native.viewport_next_event(viewportId))) {
+      handleViewportEvent(ev)
+    }
+
+    // keep it smooth if there's something running
+    setTimeout(loop, native.viewport_animating(viewportId) ? 16 : 100)
+  }
+
+  function handleViewportEvent(event: [string, number, [string, number]]) {
+    const [kind, node, [name, elapsedTime]] = event
+    const target = getNode(document, node)
+
+    switch (kind) {
+      case 'transitionend':
+        return target?.dispatchEvent(new TransitionEvent(kind, { bubbles: true, propertyName: name, elapsedTime }))
+      case 'animationstart':
+      case 'animationiteration':
+      case 'animationend':
+        return target?.dispatchEvent(new AnimationEvent(kind, { bubbles: true, animationName: name, elapsedTime }))
+    }
   }
 
   // TODO: review, this is old code